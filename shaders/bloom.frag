@@ -0,0 +1,30 @@
+#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+// Strength of the glow, 0 (none) to 1
+uniform float intensity;
+// Size of the game area in pixels
+uniform vec2 resolution;
+// Brightness above which pixels start to glow
+uniform float threshold;
+
+void main() {
+    vec3 color = texture2D(Texture, uv).rgb;
+
+    // Gather the bright pixels around this one
+    vec2 texel = 2.0 / resolution;
+    vec3 glow = vec3(0.0);
+    for (int x = -3; x <= 3; x++) {
+        for (int y = -3; y <= 3; y++) {
+            vec3 neighbor = texture2D(Texture, uv + vec2(float(x), float(y)) * texel).rgb;
+            float brightness = max(neighbor.r, max(neighbor.g, neighbor.b));
+            glow += neighbor * smoothstep(threshold, 1.0, brightness);
+        }
+    }
+    glow /= 49.0;
+
+    gl_FragColor = vec4(color + glow * intensity * 1.5, 1.0);
+}
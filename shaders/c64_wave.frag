@@ -6,6 +6,8 @@ varying vec4 color;
 
 uniform sampler2D Texture;
 uniform float time;
+// Strength of the wobble, 0 (none) to 1
+uniform float intensity;
 
 // C64-style color palette effect
 vec3 c64_color_cycle(float t) {
@@ -21,10 +23,10 @@ void main() {
     vec2 distorted_uv = uv;
 
     // Horizontal wave (affects Y position based on X)
-    distorted_uv.y += sin(uv.x * 20.0 + time * 3.0) * 0.015;
+    distorted_uv.y += sin(uv.x * 20.0 + time * 3.0) * 0.015 * intensity;
 
     // Vertical wave (affects X position based on Y)
-    distorted_uv.x += sin(uv.y * 15.0 + time * 2.0) * 0.01;
+    distorted_uv.x += sin(uv.y * 15.0 + time * 2.0) * 0.01 * intensity;
 
     // Sample texture with distorted coordinates
     vec4 tex_color = texture2D(Texture, distorted_uv);
@@ -0,0 +1,41 @@
+#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform float time;
+// Strength of the effect, 0 (none) to 1
+uniform float intensity;
+// Size of the game area in pixels
+uniform vec2 resolution;
+
+// Bend the picture like the glass of an old monitor
+vec2 curve(vec2 p) {
+    p = p * 2.0 - 1.0;
+    vec2 offset = abs(p.yx) / vec2(6.0, 5.0);
+    p += p * offset * offset * intensity;
+    return p * 0.5 + 0.5;
+}
+
+void main() {
+    vec2 p = curve(uv);
+    if (p.x < 0.0 || p.x > 1.0 || p.y < 0.0 || p.y > 1.0) {
+        gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+    vec3 color = texture2D(Texture, p).rgb;
+
+    // Dark gaps between the lines of the game area
+    float scanline = 0.5 + 0.5 * sin(p.y * resolution.y * 3.14159);
+    color *= 1.0 - intensity * 0.35 * (1.0 - scanline);
+
+    // Faint flicker of the tube
+    color *= 1.0 - intensity * 0.015 * (0.5 + 0.5 * sin(time * 50.0));
+
+    // Darker corners
+    vec2 d = p - 0.5;
+    color *= 1.0 - dot(d, d) * intensity * 1.2;
+
+    gl_FragColor = vec4(color, 1.0);
+}
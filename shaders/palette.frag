@@ -0,0 +1,47 @@
+#version 100
+precision mediump float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+// How far colors move to the palette, 0 (not at all) to 1
+uniform float intensity;
+
+// The 16 colors of the C64
+vec3 c64_palette(int i) {
+    if (i == 0) return vec3(0.000, 0.000, 0.000);
+    if (i == 1) return vec3(1.000, 1.000, 1.000);
+    if (i == 2) return vec3(0.408, 0.216, 0.169);
+    if (i == 3) return vec3(0.439, 0.643, 0.698);
+    if (i == 4) return vec3(0.435, 0.239, 0.525);
+    if (i == 5) return vec3(0.345, 0.553, 0.263);
+    if (i == 6) return vec3(0.208, 0.157, 0.475);
+    if (i == 7) return vec3(0.722, 0.780, 0.435);
+    if (i == 8) return vec3(0.435, 0.310, 0.145);
+    if (i == 9) return vec3(0.263, 0.224, 0.000);
+    if (i == 10) return vec3(0.604, 0.404, 0.349);
+    if (i == 11) return vec3(0.267, 0.267, 0.267);
+    if (i == 12) return vec3(0.424, 0.424, 0.424);
+    if (i == 13) return vec3(0.604, 0.824, 0.518);
+    if (i == 14) return vec3(0.424, 0.369, 0.710);
+    return vec3(0.584, 0.584, 0.584);
+}
+
+void main() {
+    vec3 color = texture2D(Texture, uv).rgb;
+
+    // Nearest palette color
+    vec3 nearest = c64_palette(0);
+    float nearest_distance = 4.0;
+    for (int i = 0; i < 16; i++) {
+        vec3 candidate = c64_palette(i);
+        vec3 d = color - candidate;
+        float dist = dot(d, d);
+        if (dist < nearest_distance) {
+            nearest_distance = dist;
+            nearest = candidate;
+        }
+    }
+
+    gl_FragColor = vec4(mix(color, nearest, intensity), 1.0);
+}
@@ -0,0 +1,372 @@
+//! Achievements.
+//!
+//! [`ACHIEVEMENTS`] defines every achievement and the condition that
+//! unlocks it. [`AchievementManager`] evaluates those conditions on the
+//! [`GameEvent`] stream and persists which ones are unlocked, together
+//! with the career counters some of them need.
+
+use crate::constants::SCREEN_HEIGHT;
+use crate::entities::EnemyType;
+use crate::events::GameEvent;
+use crate::highscore::unix_time_now;
+use crate::stats::KillCounts;
+use crate::storage;
+use crate::systems::FormationType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What has to happen for an achievement to unlock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// Clear the given wave
+    ClearWave(u32),
+    /// Shoot down the flying bee this many times in one run
+    BeesInRun(u32),
+    /// Clear a wave without a bullet leaving the screen
+    FlawlessWave,
+    /// Clear a wave of this formation while its lowest enemy stays in the
+    /// upper half of the screen
+    HoldFormation(FormationType),
+    /// Destroy this many enemies of one type over all runs
+    CareerKills(EnemyType, u32),
+}
+
+/// A single achievement definition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Achievement {
+    /// Stable identifier used in the save file
+    pub id: &'static str,
+    /// Display name
+    pub name: &'static str,
+    /// What the player has to do, shown in the gallery
+    pub description: &'static str,
+    /// Unlock condition
+    pub condition: Condition,
+}
+
+/// All achievements, in gallery order.
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "clear_wave_10",
+        name: "Veteran",
+        description: "Clear wave 10",
+        condition: Condition::ClearWave(10),
+    },
+    Achievement {
+        id: "bee_hunter",
+        name: "Bee Hunter",
+        description: "Shoot the bee 5 times in one run",
+        condition: Condition::BeesInRun(5),
+    },
+    Achievement {
+        id: "flawless_wave",
+        name: "Sharpshooter",
+        description: "Clear a wave without missing a shot",
+        condition: Condition::FlawlessWave,
+    },
+    Achievement {
+        id: "diamond_hold",
+        name: "Hold the Line",
+        description: "Clear a Diamond wave before any enemy reaches half the screen",
+        condition: Condition::HoldFormation(FormationType::Diamond),
+    },
+    Achievement {
+        id: "tank_buster",
+        name: "Tank Buster",
+        description: "Destroy 1000 Tanks over all runs",
+        condition: Condition::CareerKills(EnemyType::Tank, 1000),
+    },
+];
+
+/// Everything persisted by [`AchievementManager`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AchievementStore {
+    /// Unlock time (Unix seconds) per achievement id
+    unlocked: BTreeMap<String, u64>,
+    /// Enemies destroyed over all runs
+    career_kills: KillCounts,
+}
+
+/// Progress within the current run and wave.
+#[derive(Debug, Clone, Default)]
+struct RunProgress {
+    bees: u32,
+    wave_misses: usize,
+    /// Bottom edge of the lowest enemy seen during the wave (largest y)
+    wave_lowest_enemy: f32,
+}
+
+/// Evaluates achievements from gameplay events and persists unlocks.
+///
+/// Uses the same storage backends as `HighscoreManager` (a JSON file on
+/// desktop, localStorage on WASM). Unlocks are saved immediately; career
+/// counters are saved by [`AchievementManager::save`] at the end of a run.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bumblebees::achievements::AchievementManager;
+/// use bumblebees::events::GameEvent;
+///
+/// let mut achievements = AchievementManager::new("achievements.json");
+/// achievements.handle(&GameEvent::RunStarted);
+/// let bee = GameEvent::BeeDestroyed { x: 0.0, y: 0.0, points: 1000, bullets: 1 };
+/// for _ in 0..5 {
+///     for unlocked in achievements.handle(&bee) {
+///         println!("Unlocked {}", unlocked.name);
+///     }
+/// }
+/// ```
+pub struct AchievementManager {
+    /// Storage key: filename (desktop) or localStorage key (WASM)
+    storage_key: String,
+    store: AchievementStore,
+    run: RunProgress,
+}
+
+impl AchievementManager {
+    /// Load unlock state from the given storage key.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        let store = storage::load(key)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            storage_key: key.to_string(),
+            store,
+            run: RunProgress::default(),
+        }
+    }
+
+    /// Process a gameplay event and return the achievements it unlocked.
+    pub fn handle(&mut self, event: &GameEvent) -> Vec<&'static Achievement> {
+        match *event {
+            GameEvent::RunStarted => {
+                self.run = RunProgress::default();
+                return Vec::new();
+            }
+            GameEvent::ShotMissed { bullets } => {
+                self.run.wave_misses += bullets;
+                return Vec::new();
+            }
+            GameEvent::FormationDescended { lowest_y } => {
+                self.run.wave_lowest_enemy = self.run.wave_lowest_enemy.max(lowest_y);
+                return Vec::new();
+            }
+            GameEvent::EnemyDestroyed { enemy_type, .. } => {
+                self.store.career_kills.add(enemy_type);
+            }
+            GameEvent::BeeDestroyed { .. } => self.run.bees += 1,
+            GameEvent::WaveCleared { .. } => {}
+            GameEvent::ShotFired { .. }
+            | GameEvent::EnemyHit { .. }
+            | GameEvent::DefenderBreached { .. } => return Vec::new(),
+        }
+
+        let unlocked: Vec<&'static Achievement> = ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| !self.is_unlocked(achievement.id))
+            .filter(|achievement| self.is_met(achievement.condition, event))
+            .collect();
+
+        if let GameEvent::WaveCleared { .. } = event {
+            self.run.wave_misses = 0;
+            self.run.wave_lowest_enemy = 0.0;
+        }
+
+        if !unlocked.is_empty() {
+            let now = unix_time_now();
+            for achievement in &unlocked {
+                self.store.unlocked.insert(achievement.id.to_string(), now);
+            }
+            self.save();
+        }
+
+        unlocked
+    }
+
+    /// Whether the achievement with the given id is unlocked.
+    #[must_use]
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.store.unlocked.contains_key(id)
+    }
+
+    /// Number of unlocked achievements.
+    #[must_use]
+    pub fn unlocked_count(&self) -> usize {
+        ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| self.is_unlocked(achievement.id))
+            .count()
+    }
+
+    /// Current and target count for achievements that build up over all
+    /// runs, `None` for the others.
+    #[must_use]
+    pub fn progress(&self, achievement: &Achievement) -> Option<(u32, u32)> {
+        match achievement.condition {
+            Condition::CareerKills(enemy_type, target) => {
+                Some((self.store.career_kills.get(enemy_type).min(target), target))
+            }
+            _ => None,
+        }
+    }
+
+    /// Persist unlocks and career counters.
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(&self.store) {
+            storage::save(&self.storage_key, &json);
+        }
+    }
+
+    fn is_met(&self, condition: Condition, event: &GameEvent) -> bool {
+        match (condition, *event) {
+            (Condition::ClearWave(target), GameEvent::WaveCleared { wave, .. }) => wave >= target,
+            (Condition::BeesInRun(target), GameEvent::BeeDestroyed { .. }) => {
+                self.run.bees >= target
+            }
+            (Condition::FlawlessWave, GameEvent::WaveCleared { .. }) => self.run.wave_misses == 0,
+            (Condition::HoldFormation(target), GameEvent::WaveCleared { formation, .. }) => {
+                formation == target && self.run.wave_lowest_enemy < SCREEN_HEIGHT / 2.0
+            }
+            (Condition::CareerKills(enemy_type, target), GameEvent::EnemyDestroyed { .. }) => {
+                self.store.career_kills.get(enemy_type) >= target
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+
+    fn ids(unlocked: &[&Achievement]) -> Vec<&'static str> {
+        unlocked.iter().map(|achievement| achievement.id).collect()
+    }
+
+    fn wave_cleared(wave: u32) -> GameEvent {
+        GameEvent::WaveCleared {
+            wave,
+            formation: crate::systems::formation_for_wave(wave),
+        }
+    }
+
+    const BEE: GameEvent = GameEvent::BeeDestroyed {
+        x: 0.0,
+        y: 0.0,
+        points: 1000,
+        bullets: 1,
+    };
+
+    const MISS: GameEvent = GameEvent::ShotMissed { bullets: 1 };
+
+    const TANK_DESTROYED: GameEvent = GameEvent::EnemyDestroyed {
+        enemy_type: EnemyType::Tank,
+        x: 0.0,
+        y: 0.0,
+        points: 50,
+    };
+
+    #[test]
+    fn test_achievement_ids_are_unique() {
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            assert!(ACHIEVEMENTS[i + 1..]
+                .iter()
+                .all(|other| other.id != achievement.id));
+        }
+    }
+
+    #[test]
+    fn test_bee_hunter_counts_per_run() {
+        let test_file = "test_achievements_bees.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut manager = AchievementManager::new(test_file);
+        manager.handle(&GameEvent::RunStarted);
+        for _ in 0..4 {
+            assert!(manager.handle(&BEE).is_empty());
+        }
+        // A new run starts counting from zero again
+        manager.handle(&GameEvent::RunStarted);
+        for _ in 0..4 {
+            assert!(manager.handle(&BEE).is_empty());
+        }
+        let unlocked = manager.handle(&BEE);
+        assert_eq!(ids(&unlocked), ["bee_hunter"]);
+
+        // Unlocks only once
+        assert!(manager.handle(&BEE).is_empty());
+
+        let _ = std::fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_wave_achievements() {
+        let test_file = "test_achievements_waves.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut manager = AchievementManager::new(test_file);
+        manager.handle(&GameEvent::RunStarted);
+
+        // Wave 1 with misses: nothing
+        manager.handle(&MISS);
+        manager.handle(&MISS);
+        assert!(manager.handle(&wave_cleared(1)).is_empty());
+
+        // Wave 2 without misses
+        assert_eq!(ids(&manager.handle(&wave_cleared(2))), ["flawless_wave"]);
+
+        // Diamond wave 3 that got too low
+        manager.handle(&MISS);
+        manager.handle(&GameEvent::FormationDescended {
+            lowest_y: SCREEN_HEIGHT * 0.6,
+        });
+        assert!(manager.handle(&wave_cleared(3)).is_empty());
+
+        // Diamond wave 7 held high
+        manager.handle(&MISS);
+        manager.handle(&GameEvent::FormationDescended {
+            lowest_y: SCREEN_HEIGHT * 0.4,
+        });
+        assert_eq!(ids(&manager.handle(&wave_cleared(7))), ["diamond_hold"]);
+
+        manager.handle(&MISS);
+        assert_eq!(ids(&manager.handle(&wave_cleared(10))), ["clear_wave_10"]);
+        assert_eq!(manager.unlocked_count(), 3);
+
+        let _ = std::fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_career_kills_persist() {
+        let test_file = "test_achievements_career.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let tank_buster = ACHIEVEMENTS
+            .iter()
+            .find(|achievement| achievement.id == "tank_buster")
+            .unwrap();
+        let mut manager = AchievementManager::new(test_file);
+        for _ in 0..999 {
+            assert!(manager.handle(&TANK_DESTROYED).is_empty());
+        }
+        assert_eq!(manager.progress(tank_buster), Some((999, 1000)));
+        manager.save();
+
+        let mut reloaded = AchievementManager::new(test_file);
+        assert!(!reloaded.is_unlocked("tank_buster"));
+        let unlocked = reloaded.handle(&TANK_DESTROYED);
+        assert_eq!(ids(&unlocked), ["tank_buster"]);
+
+        // Unlocks are saved right away
+        let reloaded = AchievementManager::new(test_file);
+        assert!(reloaded.is_unlocked("tank_buster"));
+        assert_eq!(reloaded.progress(&ACHIEVEMENTS[0]), None);
+
+        let _ = std::fs::remove_file(test_file);
+    }
+}
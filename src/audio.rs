@@ -0,0 +1,660 @@
+//! Audio mixer.
+//!
+//! [`AudioManager`] owns every [`Sound`] of the game and plays it through
+//! one of three [`Bus`]es (music, sound effects, interface), each with its
+//! own volume under the master volume. The decisions live in [`Mixer`],
+//! which doesn't touch the audio device and can be tested on its own:
+//!
+//! - global mute
+//! - ducking: the music drops for a moment under big explosions
+//! - instance limiting: only a few plays of the same effect at once
+//! - fades: music tracks fade out and in when the track changes
+//!
+//! Music tracks are numbered in the order they are added; the
+//! [`music`](crate::music) playlists decide which one plays.
+//!
+//! Sound effects are panned by the screen position of what made them. The
+//! audio device has no pan control, so every effect is loaded as a
+//! [`PannedSound`]: one copy on the left and one on the right speaker,
+//! played together with the volumes of [`pan_gains`].
+//!
+//! Effects come in two [`SoundPack`]s: the sample files and the sounds of
+//! the [`synth`](crate::synth). An effect missing from the sample pack
+//! plays its synthesized version instead.
+
+use crate::constants::*;
+use crate::settings::Settings;
+use crate::wav::Pcm;
+use macroquad::audio::{
+    load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound,
+};
+use serde::{Deserialize, Serialize};
+
+/// A group of sounds sharing a volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    Music,
+    Sfx,
+    Ui,
+}
+
+/// A sound effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Clip {
+    Shoot,
+    Hit,
+    /// Buzz of the flying bee
+    Bee,
+    /// The bee blowing up
+    Explosion,
+    /// The gun getting stronger
+    PowerUp,
+    /// Interface click
+    Ui,
+}
+
+impl Clip {
+    /// All sound effects.
+    pub const ALL: [Self; 6] = [
+        Self::Shoot,
+        Self::Hit,
+        Self::Bee,
+        Self::Explosion,
+        Self::PowerUp,
+        Self::Ui,
+    ];
+}
+
+/// Where the sound effects come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SoundPack {
+    /// The sample files in `resources`
+    #[default]
+    Samples,
+    /// Synthesized chiptune sounds only
+    Retro,
+}
+
+impl SoundPack {
+    /// The other pack.
+    #[must_use]
+    pub const fn toggled(self) -> Self {
+        match self {
+            Self::Samples => Self::Retro,
+            Self::Retro => Self::Samples,
+        }
+    }
+}
+
+/// Stereo position of a sound at screen x: -1 (left) to 1 (right),
+/// narrowed by `STEREO_WIDTH` so nothing plays on one speaker only.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::audio::pan_from_x;
+/// use bumblebees::constants::{SCREEN_WIDTH, STEREO_WIDTH};
+///
+/// assert_eq!(pan_from_x(SCREEN_WIDTH / 2.0), 0.0);
+/// assert_eq!(pan_from_x(0.0), -STEREO_WIDTH);
+/// assert_eq!(pan_from_x(SCREEN_WIDTH * 2.0), STEREO_WIDTH);
+/// ```
+#[must_use]
+pub fn pan_from_x(x: f32) -> f32 {
+    (x / SCREEN_WIDTH * 2.0 - 1.0).clamp(-1.0, 1.0) * STEREO_WIDTH
+}
+
+/// Left and right speaker volumes for a pan. A centered sound plays at
+/// full volume on both sides, as it did before panning; moving it to one
+/// side turns the other side down.
+#[must_use]
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = pan.clamp(-1.0, 1.0);
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+}
+
+/// A sound effect that can be played anywhere between the speakers.
+pub enum PannedSound {
+    /// One copy per speaker
+    Split { left: Sound, right: Sound },
+    /// A file that couldn't be split; always plays centered
+    Centered(Sound),
+}
+
+impl PannedSound {
+    /// Load a sound effect from WAV data. Files other than 16-bit PCM play
+    /// centered; None if the data can't be played at all.
+    pub async fn load(bytes: &[u8]) -> Option<Self> {
+        match Pcm::parse(bytes) {
+            Ok(pcm) => {
+                let left = load_sound_from_bytes(&pcm.on_one_side(true).to_wav()).await;
+                let right = load_sound_from_bytes(&pcm.on_one_side(false).to_wav()).await;
+                Some(Self::Split {
+                    left: left.ok()?,
+                    right: right.ok()?,
+                })
+            }
+            Err(err) => {
+                log::warn!("Sound effect can't be panned: {}", err);
+                load_sound_from_bytes(bytes).await.ok().map(Self::Centered)
+            }
+        }
+    }
+
+    fn play(&self, volume: f32, pan: f32, looped: bool) {
+        match self {
+            Self::Split { left, right } => {
+                let (left_gain, right_gain) = pan_gains(pan);
+                for (sound, gain) in [(left, left_gain), (right, right_gain)] {
+                    play_sound(
+                        sound,
+                        PlaySoundParams {
+                            looped,
+                            volume: volume * gain,
+                        },
+                    );
+                }
+            }
+            Self::Centered(sound) => play_sound(sound, PlaySoundParams { looped, volume }),
+        }
+    }
+
+    /// Change the volume and position of every playing instance.
+    fn set_volume(&self, volume: f32, pan: f32) {
+        match self {
+            Self::Split { left, right } => {
+                let (left_gain, right_gain) = pan_gains(pan);
+                set_sound_volume(left, volume * left_gain);
+                set_sound_volume(right, volume * right_gain);
+            }
+            Self::Centered(sound) => set_sound_volume(sound, volume),
+        }
+    }
+
+    fn stop(&self) {
+        match self {
+            Self::Split { left, right } => {
+                stop_sound(left);
+                stop_sound(right);
+            }
+            Self::Centered(sound) => stop_sound(sound),
+        }
+    }
+}
+
+/// Volumes, fades, ducking and instance limits, without playing anything.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::audio::{Bus, Clip, Mixer};
+///
+/// let mut mixer = Mixer::new();
+/// let intro = mixer.add_track(0.7);
+/// assert!(mixer.play_music(Some(intro)));
+/// // A new track fades in from silence
+/// assert_eq!(mixer.music_volume(intro), 0.0);
+/// mixer.update(10.0);
+/// assert_eq!(mixer.music_volume(intro), 0.7);
+///
+/// mixer.toggle_mute();
+/// assert_eq!(mixer.volume(Bus::Sfx), 0.0);
+/// assert!(!mixer.try_play(Clip::Shoot));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Mixer {
+    master: f32,
+    /// Volume of the music, effects and interface buses
+    buses: [f32; 3],
+    muted: bool,
+    /// Seconds since the mixer was created
+    time: f32,
+    /// Volume of each music track at full music volume
+    tracks: Vec<f32>,
+    /// Track that should be heard; the others fade out
+    current: Option<usize>,
+    /// Fade gain of each track, 0 (silent) to 1
+    fades: Vec<f32>,
+    /// Music multiplier from ducking, `DUCK_LEVEL` to 1
+    duck: f32,
+    /// Time at which the music starts coming back up
+    duck_until: f32,
+    /// Start times of the recent plays of each clip
+    recent: [Vec<f32>; Clip::ALL.len()],
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mixer {
+    /// A mixer with every volume at full.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            master: 1.0,
+            buses: [1.0; 3],
+            muted: false,
+            time: 0.0,
+            tracks: Vec::new(),
+            current: None,
+            fades: Vec::new(),
+            duck: 1.0,
+            duck_until: 0.0,
+            recent: Default::default(),
+        }
+    }
+
+    /// Take the master and bus volumes from the settings.
+    pub fn set_volumes(&mut self, settings: &Settings) {
+        self.master = settings.master_volume;
+        self.buses = [
+            settings.music_volume,
+            settings.sfx_volume,
+            settings.ui_volume,
+        ];
+    }
+
+    #[must_use]
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// Mute or unmute everything; returns whether it is muted now.
+    pub fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.muted
+    }
+
+    /// Effective volume of a bus, 0 when muted.
+    #[must_use]
+    pub fn volume(&self, bus: Bus) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master * self.buses[bus as usize]
+        }
+    }
+
+    /// Add a music track playing at `volume` at full music volume;
+    /// returns its number.
+    pub fn add_track(&mut self, volume: f32) -> usize {
+        self.tracks.push(volume);
+        self.fades.push(0.0);
+        self.tracks.len() - 1
+    }
+
+    /// Volume a track should play at right now.
+    #[must_use]
+    pub fn music_volume(&self, track: usize) -> f32 {
+        self.tracks[track] * self.volume(Bus::Music) * self.duck * self.fades[track]
+    }
+
+    /// Volume of the march beat; ducks with the music.
+    #[must_use]
+    pub fn march_volume(&self) -> f32 {
+        MARCH_VOLUME * self.volume(Bus::Music) * self.duck
+    }
+
+    /// Switch to another track (None for silence): the old one fades out,
+    /// the new one fades in. Returns whether the track changed.
+    pub fn play_music(&mut self, track: Option<usize>) -> bool {
+        if self.current == track {
+            return false;
+        }
+        self.current = track;
+        true
+    }
+
+    /// Whether a track has faded out completely and can be stopped.
+    #[must_use]
+    pub fn is_faded_out(&self, track: usize) -> bool {
+        self.current != Some(track) && self.fades[track] == 0.0
+    }
+
+    /// Drop the music for a moment, e.g. under a big explosion.
+    pub fn duck(&mut self) {
+        self.duck = DUCK_LEVEL;
+        self.duck_until = self.time + DUCK_HOLD;
+    }
+
+    /// Whether `clip` may start now. Counts the play if it may; nothing
+    /// plays while muted or when the clip already plays too often.
+    pub fn try_play(&mut self, clip: Clip) -> bool {
+        let recent = &mut self.recent[clip as usize];
+        if self.muted || recent.len() >= SFX_MAX_INSTANCES {
+            return false;
+        }
+        recent.push(self.time);
+        true
+    }
+
+    /// Advance fades, ducking and instance windows by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+
+        let fade_step = dt / MUSIC_FADE_TIME;
+        for (track, fade) in self.fades.iter_mut().enumerate() {
+            *fade = if self.current == Some(track) {
+                (*fade + fade_step).min(1.0)
+            } else {
+                (*fade - fade_step).max(0.0)
+            };
+        }
+
+        if self.time >= self.duck_until {
+            self.duck = (self.duck + dt * (1.0 - DUCK_LEVEL) / DUCK_RELEASE).min(1.0);
+        }
+
+        let time = self.time;
+        for recent in &mut self.recent {
+            recent.retain(|&start| time - start < SFX_INSTANCE_WINDOW);
+        }
+    }
+}
+
+/// All sounds of the game, played through the [`Mixer`].
+///
+/// Missing sounds (files that failed to load) are skipped silently.
+pub struct AudioManager {
+    mixer: Mixer,
+    /// Music tracks by number
+    music: Vec<Option<Sound>>,
+    /// Effects of each sound pack
+    clips: [[Option<PannedSound>; Clip::ALL.len()]; 2],
+    /// Looping buzz of the flying bee in each pack, a copy of its own so
+    /// changing its volume doesn't touch the one-shot effects
+    buzz: [Option<PannedSound>; 2],
+    pack: SoundPack,
+    /// Whether each track is running on the audio device
+    playing: Vec<bool>,
+    /// Volume last set on each running track
+    applied: Vec<f32>,
+    /// Volume and pan last set on the buzz, None while it is silent
+    buzz_applied: Option<(f32, f32)>,
+    /// Notes of the march beat
+    march: Vec<Sound>,
+}
+
+impl AudioManager {
+    /// A manager without sounds.
+    #[must_use]
+    pub fn new(settings: &Settings) -> Self {
+        let mut mixer = Mixer::new();
+        mixer.set_volumes(settings);
+        Self {
+            mixer,
+            music: Vec::new(),
+            clips: Default::default(),
+            buzz: [None, None],
+            pack: settings.sound_pack,
+            playing: Vec::new(),
+            applied: Vec::new(),
+            buzz_applied: None,
+            march: Vec::new(),
+        }
+    }
+
+    /// Add `sound` as the next music track, playing at `volume` at full
+    /// music volume. Tracks are numbered from 0 in the order they are added.
+    #[must_use]
+    pub fn with_track(mut self, volume: f32, sound: Option<Sound>) -> Self {
+        self.mixer.add_track(volume);
+        self.music.push(sound);
+        self.playing.push(false);
+        self.applied.push(0.0);
+        self
+    }
+
+    /// Use `sound` for a sound effect of a pack.
+    #[must_use]
+    pub fn with_clip(mut self, pack: SoundPack, clip: Clip, sound: Option<PannedSound>) -> Self {
+        self.clips[pack as usize][clip as usize] = sound;
+        self
+    }
+
+    /// Use `sound` for the buzz of the flying bee in a pack.
+    #[must_use]
+    pub fn with_buzz(mut self, pack: SoundPack, sound: Option<PannedSound>) -> Self {
+        self.buzz[pack as usize] = sound;
+        self
+    }
+
+    /// Use `notes` for the march beat.
+    #[must_use]
+    pub fn with_march(mut self, notes: Vec<Sound>) -> Self {
+        self.march = notes;
+        self
+    }
+
+    #[must_use]
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    pub fn mixer_mut(&mut self) -> &mut Mixer {
+        &mut self.mixer
+    }
+
+    /// Switch the effects to another pack. A running buzz stops and starts
+    /// again in the new pack on the next [`buzz_at`](Self::buzz_at).
+    pub fn set_pack(&mut self, pack: SoundPack) {
+        if pack == self.pack {
+            return;
+        }
+        if self.buzz_applied.take().is_some() {
+            if let Some(sound) = self.buzz_sound() {
+                sound.stop();
+            }
+        }
+        self.pack = pack;
+    }
+
+    /// The effect in the current pack, or its synthesized version if the
+    /// pack lacks it.
+    fn clip(&self, clip: Clip) -> Option<&PannedSound> {
+        self.clips[self.pack as usize][clip as usize]
+            .as_ref()
+            .or(self.clips[SoundPack::Retro as usize][clip as usize].as_ref())
+    }
+
+    fn buzz_sound(&self) -> Option<&PannedSound> {
+        self.buzz[self.pack as usize]
+            .as_ref()
+            .or(self.buzz[SoundPack::Retro as usize].as_ref())
+    }
+
+    /// Play a sound effect once, centered, on the effects or interface bus.
+    pub fn play(&mut self, clip: Clip, bus: Bus) {
+        self.play_at(clip, bus, SCREEN_WIDTH / 2.0);
+    }
+
+    /// Play a sound effect once, panned to screen position `x`.
+    pub fn play_at(&mut self, clip: Clip, bus: Bus, x: f32) {
+        if self.clip(clip).is_none() || !self.mixer.try_play(clip) {
+            return;
+        }
+        if let Some(sound) = self.clip(clip) {
+            sound.play(self.mixer.volume(bus), pan_from_x(x), false);
+        }
+    }
+
+    /// Play note `index` of the march on the music bus.
+    pub fn play_march(&mut self, index: usize) {
+        if let Some(sound) = self.march.get(index).filter(|_| !self.mixer.is_muted()) {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume: self.mixer.march_volume(),
+                },
+            );
+        }
+    }
+
+    /// Keep the bee buzzing at screen position `x`, or silence it with
+    /// None. Called every frame so the buzz follows the bee.
+    pub fn buzz_at(&mut self, x: Option<f32>) {
+        let Some(sound) = self.buzz_sound() else {
+            return;
+        };
+        let target = x.map(|x| (self.mixer.volume(Bus::Sfx) * BEE_BUZZ_VOLUME, pan_from_x(x)));
+        match (self.buzz_applied, target) {
+            (None, Some((volume, pan))) => sound.play(volume, pan, true),
+            (Some(_), None) => sound.stop(),
+            (Some(applied), Some((volume, pan))) if applied != (volume, pan) => {
+                sound.set_volume(volume, pan);
+            }
+            _ => {}
+        }
+        self.buzz_applied = target;
+    }
+
+    /// Fade over to a music track, or fade out all music with None. A
+    /// track that doesn't loop starts from the beginning every time.
+    pub fn play_music(&mut self, track: Option<usize>, looped: bool) {
+        if !self.mixer.play_music(track) {
+            return;
+        }
+        let Some(track) = track else {
+            return;
+        };
+        let Some(sound) = self.music.get(track).and_then(Option::as_ref) else {
+            return;
+        };
+        if self.playing[track] && !looped {
+            stop_sound(sound);
+            self.playing[track] = false;
+        }
+        if !self.playing[track] {
+            let volume = self.mixer.music_volume(track);
+            play_sound(sound, PlaySoundParams { looped, volume });
+            self.playing[track] = true;
+            self.applied[track] = volume;
+        }
+    }
+
+    /// Advance the mixer and apply the new music volumes; tracks that
+    /// faded out are stopped.
+    pub fn update(&mut self, dt: f32) {
+        self.mixer.update(dt);
+        for (track, music) in self.music.iter().enumerate() {
+            let Some(sound) = music else {
+                continue;
+            };
+            if !self.playing[track] {
+                continue;
+            }
+            if self.mixer.is_faded_out(track) {
+                stop_sound(sound);
+                self.playing[track] = false;
+                continue;
+            }
+            let volume = self.mixer.music_volume(track);
+            if volume != self.applied[track] {
+                set_sound_volume(sound, volume);
+                self.applied[track] = volume;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buses_follow_settings_and_mute() {
+        let mut mixer = Mixer::new();
+        let settings = Settings {
+            master_volume: 0.5,
+            sfx_volume: 0.4,
+            ui_volume: 0.0,
+            ..Settings::default()
+        };
+        mixer.set_volumes(&settings);
+        assert_eq!(mixer.volume(Bus::Sfx), 0.2);
+        assert_eq!(mixer.volume(Bus::Music), 0.5);
+        assert_eq!(mixer.volume(Bus::Ui), 0.0);
+
+        assert!(mixer.toggle_mute());
+        assert_eq!(mixer.volume(Bus::Music), 0.0);
+        assert!(!mixer.toggle_mute());
+        assert_eq!(mixer.volume(Bus::Sfx), 0.2);
+    }
+
+    #[test]
+    fn test_pan_follows_screen_position() {
+        assert_eq!(pan_gains(0.0), (1.0, 1.0));
+        assert_eq!(pan_gains(-1.0), (1.0, 0.0));
+        assert_eq!(pan_gains(0.5), (0.5, 1.0));
+
+        // The bee sweeps from right to left across the screen
+        let (left, right) = pan_gains(pan_from_x(SCREEN_WIDTH * 0.75));
+        assert!(left < right);
+        let (left, right) = pan_gains(pan_from_x(SCREEN_WIDTH * 0.25));
+        assert!(left > right);
+        assert!(right >= 1.0 - STEREO_WIDTH);
+    }
+
+    #[test]
+    fn test_music_crossfades() {
+        let mut mixer = Mixer::new();
+        let intro = mixer.add_track(INTRO_VOLUME);
+        let background = mixer.add_track(MUSIC_VOLUME);
+        mixer.play_music(Some(intro));
+        mixer.update(MUSIC_FADE_TIME);
+        assert_eq!(mixer.music_volume(intro), INTRO_VOLUME);
+        // Asking for the same track again changes nothing
+        assert!(!mixer.play_music(Some(intro)));
+
+        assert!(mixer.play_music(Some(background)));
+        mixer.update(MUSIC_FADE_TIME / 2.0);
+        assert!((mixer.music_volume(intro) - INTRO_VOLUME / 2.0).abs() < 1e-6);
+        assert!((mixer.music_volume(background) - MUSIC_VOLUME / 2.0).abs() < 1e-6);
+        assert!(!mixer.is_faded_out(intro));
+
+        mixer.update(MUSIC_FADE_TIME / 2.0);
+        assert!(mixer.is_faded_out(intro));
+        assert_eq!(mixer.music_volume(background), MUSIC_VOLUME);
+    }
+
+    #[test]
+    fn test_ducking_recovers() {
+        let mut mixer = Mixer::new();
+        let background = mixer.add_track(MUSIC_VOLUME);
+        mixer.play_music(Some(background));
+        mixer.update(MUSIC_FADE_TIME);
+
+        mixer.duck();
+        assert_eq!(mixer.music_volume(background), MUSIC_VOLUME * DUCK_LEVEL);
+        // Held down, then back to full after the release
+        mixer.update(DUCK_HOLD / 2.0);
+        assert_eq!(mixer.music_volume(background), MUSIC_VOLUME * DUCK_LEVEL);
+        mixer.update(DUCK_HOLD / 2.0);
+        mixer.update(DUCK_RELEASE);
+        assert_eq!(mixer.music_volume(background), MUSIC_VOLUME);
+    }
+
+    #[test]
+    fn test_instances_are_limited() {
+        let mut mixer = Mixer::new();
+        for _ in 0..SFX_MAX_INSTANCES {
+            assert!(mixer.try_play(Clip::Shoot));
+        }
+        assert!(!mixer.try_play(Clip::Shoot));
+        // Other clips have their own limit
+        assert!(mixer.try_play(Clip::Hit));
+
+        mixer.update(SFX_INSTANCE_WINDOW);
+        assert!(mixer.try_play(Clip::Shoot));
+    }
+}
@@ -0,0 +1,375 @@
+//! Reference leaderboard server for BumbleBees.
+//!
+//! Stores submitted runs in a local JSON file and serves the global top-N
+//! list over the protocol described in `bumblebees::leaderboard`. It is meant
+//! for running the online leaderboard offline on one machine or a LAN:
+//!
+//! ```text
+//! cargo run --bin leaderboard-server -- --addr 127.0.0.1:8787 --data leaderboard.json
+//! BUMBLEBEES_LEADERBOARD=127.0.0.1:8787 cargo run --bin bumblebees
+//! ```
+//!
+//! Requests are handled one at a time; the expected load is a handful of
+//! players, not the internet.
+
+use bumblebees::leaderboard::{ScoreRecord, SubmitResponse, DEFAULT_LEADERBOARD_PORT};
+use bumblebees::MAX_NAME_LEN;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Maximum number of scores kept by the server.
+const MAX_STORED_SCORES: usize = 1000;
+
+/// Maximum accepted request body size in bytes.
+const MAX_BODY_SIZE: usize = 4096;
+
+/// Number of scores returned by `GET /scores` without a `limit` parameter.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Persistent, sorted score list backed by a JSON file.
+struct LeaderboardStore {
+    path: PathBuf,
+    scores: Vec<ScoreRecord>,
+}
+
+impl LeaderboardStore {
+    /// Open the store at `path`, starting empty if the file is missing or unreadable.
+    fn open(path: &Path) -> Self {
+        let mut scores: Vec<ScoreRecord> = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        scores.sort_by_key(|record| std::cmp::Reverse(record.score));
+        scores.truncate(MAX_STORED_SCORES);
+
+        Self {
+            path: path.to_path_buf(),
+            scores,
+        }
+    }
+
+    /// Insert a run and persist the store.
+    ///
+    /// Returns the 1-based rank of the new score. Ties rank below earlier
+    /// submissions with the same score.
+    fn submit(&mut self, record: ScoreRecord) -> usize {
+        let index = self
+            .scores
+            .partition_point(|existing| existing.score >= record.score);
+        self.scores.insert(index, record);
+        self.scores.truncate(MAX_STORED_SCORES);
+        self.save();
+        index + 1
+    }
+
+    /// Highest `n` scores.
+    fn top(&self, n: usize) -> &[ScoreRecord] {
+        &self.scores[..n.min(self.scores.len())]
+    }
+
+    /// Write the store to disk via a temporary file so a crash never leaves
+    /// a half-written leaderboard behind.
+    fn save(&self) {
+        let Ok(json) = serde_json::to_string_pretty(&self.scores) else {
+            return;
+        };
+        let tmp_path = self.path.with_extension("tmp");
+        if std::fs::write(&tmp_path, json).is_err()
+            || std::fs::rename(&tmp_path, &self.path).is_err()
+        {
+            eprintln!("Failed to write leaderboard to {}", self.path.display());
+        }
+    }
+}
+
+/// A parsed HTTP request line and body.
+struct Request {
+    method: String,
+    target: String,
+    body: String,
+}
+
+/// Read a single HTTP/1.1 request from the stream.
+fn read_request(stream: &TcpStream) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|err| err.to_string())?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("empty request")?.to_string();
+    let target = parts.next().ok_or("missing request target")?.to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|err| err.to_string())? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().map_err(|_| "bad content length")?;
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err("request body too large".to_string());
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|err| err.to_string())?;
+    let body = String::from_utf8(body).map_err(|_| "body is not UTF-8")?;
+
+    Ok(Request {
+        method,
+        target,
+        body,
+    })
+}
+
+/// Route a request and return the status line and JSON body of the response.
+fn handle_request(store: &mut LeaderboardStore, request: &Request) -> (&'static str, String) {
+    let (path, query) = request
+        .target
+        .split_once('?')
+        .unwrap_or((request.target.as_str(), ""));
+
+    match (request.method.as_str(), path) {
+        ("GET", "/scores") => {
+            let limit = query
+                .split('&')
+                .filter_map(|pair| pair.strip_prefix("limit="))
+                .find_map(|value| value.parse::<usize>().ok())
+                .unwrap_or(DEFAULT_LIMIT);
+            let json = serde_json::to_string(store.top(limit)).unwrap_or_else(|_| "[]".into());
+            ("200 OK", json)
+        }
+        ("POST", "/scores") => match serde_json::from_str::<ScoreRecord>(&request.body) {
+            Ok(record) if is_valid_name(&record.name) => {
+                println!("Score submitted: {} - {}", record.name, record.score);
+                let rank = store.submit(record);
+                let json =
+                    serde_json::to_string(&SubmitResponse { rank }).unwrap_or_else(|_| "{}".into());
+                ("201 Created", json)
+            }
+            Ok(_) => ("400 Bad Request", r#"{"error":"invalid name"}"#.to_string()),
+            Err(_) => ("400 Bad Request", r#"{"error":"invalid body"}"#.to_string()),
+        },
+        _ => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
+    }
+}
+
+/// Names follow the in-game rules: 1-20 alphanumeric characters.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_NAME_LEN
+        && name.chars().all(char::is_alphanumeric)
+}
+
+/// Serve one connection: read a request, answer it, close.
+fn handle_connection(store: &mut LeaderboardStore, mut stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let _ = stream.set_write_timeout(Some(Duration::from_secs(5)));
+
+    let (status, body) = match read_request(&stream) {
+        Ok(request) => handle_request(store, &request),
+        Err(err) => ("400 Bad Request", format!(r#"{{"error":"{}"}}"#, err)),
+    };
+
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.flush();
+}
+
+/// Accept connections forever, serving them sequentially.
+fn serve(listener: TcpListener, mut store: LeaderboardStore) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => handle_connection(&mut store, stream),
+            Err(err) => eprintln!("Connection failed: {}", err),
+        }
+    }
+}
+
+fn main() {
+    let mut addr = format!("127.0.0.1:{}", DEFAULT_LEADERBOARD_PORT);
+    let mut data = PathBuf::from("leaderboard.json");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--addr" => addr = args.next().unwrap_or(addr),
+            "--data" => data = args.next().map(PathBuf::from).unwrap_or(data),
+            "-h" | "--help" => {
+                println!("Usage: leaderboard-server [--addr HOST:PORT] [--data FILE]");
+                return;
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let store = LeaderboardStore::open(&data);
+    let listener = match TcpListener::bind(&addr) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Failed to bind {}: {}", addr, err);
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "Leaderboard server listening on {} ({} scores in {})",
+        addr,
+        store.scores.len(),
+        data.display()
+    );
+    serve(listener, store);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bumblebees::leaderboard::{LeaderboardClient, LeaderboardError};
+
+    fn temp_store(name: &str) -> LeaderboardStore {
+        let path = PathBuf::from(name);
+        let _ = std::fs::remove_file(&path);
+        LeaderboardStore::open(&path)
+    }
+
+    #[test]
+    fn test_store_ranks_and_sorts_submissions() {
+        let mut store = temp_store("test_server_rank.json");
+
+        assert_eq!(store.submit(ScoreRecord::new("Alice", 100)), 1);
+        assert_eq!(store.submit(ScoreRecord::new("Bob", 300)), 1);
+        assert_eq!(store.submit(ScoreRecord::new("Carol", 200)), 2);
+        // Ties rank below the earlier submission
+        assert_eq!(store.submit(ScoreRecord::new("Dave", 200)), 3);
+
+        let names: Vec<_> = store.top(10).iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Bob", "Carol", "Dave", "Alice"]);
+        assert_eq!(store.top(2).len(), 2);
+
+        let _ = std::fs::remove_file("test_server_rank.json");
+    }
+
+    #[test]
+    fn test_store_persists_to_file() {
+        let mut store = temp_store("test_server_persist.json");
+        store.submit(ScoreRecord::new("Alice", 100));
+        store.submit(ScoreRecord::new("Bob", 300));
+
+        let reopened = LeaderboardStore::open(Path::new("test_server_persist.json"));
+        assert_eq!(reopened.top(10), store.top(10));
+
+        let _ = std::fs::remove_file("test_server_persist.json");
+    }
+
+    #[test]
+    fn test_name_validation() {
+        assert!(is_valid_name("Player1"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("has space"));
+        assert!(!is_valid_name("ABCDEFGHIJKLMNOPQRSTU"));
+        assert!(is_valid_name(&"ü".repeat(MAX_NAME_LEN)));
+        assert!(!is_valid_name(&"ü".repeat(MAX_NAME_LEN + 1)));
+    }
+
+    #[test]
+    fn test_client_round_trip() {
+        let store = temp_store("test_server_round_trip.json");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || serve(listener, store));
+
+        let client = LeaderboardClient::new(&addr);
+        assert_eq!(client.submit(&ScoreRecord::new("Alice", 100)).unwrap(), 1);
+        assert_eq!(client.submit(&ScoreRecord::new("Bob", 500)).unwrap(), 1);
+        assert_eq!(client.submit(&ScoreRecord::new("Carol", 300)).unwrap(), 2);
+
+        let top = client.fetch_top(2).unwrap();
+        assert_eq!(
+            top,
+            vec![ScoreRecord::new("Bob", 500), ScoreRecord::new("Carol", 300)]
+        );
+
+        // Invalid names are rejected
+        assert!(matches!(
+            client.submit(&ScoreRecord::new("bad name", 1)),
+            Err(LeaderboardError::Status(400))
+        ));
+
+        let _ = std::fs::remove_file("test_server_round_trip.json");
+    }
+
+    /// Run a leaderboard sync to the end, returning the runs still queued
+    fn sync_now(manager: &bumblebees::highscore::HighscoreManager) -> usize {
+        let job = manager.start_sync(5).expect("leaderboard attached");
+        loop {
+            if let Some(outcome) = job.poll() {
+                return manager.finish_sync(&outcome);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_rejected_submission_does_not_block_queue() {
+        use bumblebees::highscore::HighscoreManager;
+
+        let queue = "test_server_rejected_queue.txt";
+        let remove_queue = || {
+            let _ = std::fs::remove_file(queue);
+            let _ = std::fs::remove_file(Path::new(queue).with_extension("pending"));
+            let _ = std::fs::remove_file(Path::new(queue).with_extension("key"));
+        };
+        remove_queue();
+
+        // Queue a run the server rejects, then a valid one, while offline
+        let offline =
+            HighscoreManager::new(queue).with_leaderboard(LeaderboardClient::new("127.0.0.1:1"));
+        offline.save_highscore("bad name", 100);
+        offline.save_highscore("Alice", 200);
+        assert_eq!(sync_now(&offline), 2);
+
+        let store = temp_store("test_server_rejected.json");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || serve(listener, store));
+
+        // The rejected run is dropped and the valid one still gets through
+        let online = HighscoreManager::new(queue).with_leaderboard(LeaderboardClient::new(&addr));
+        assert_eq!(sync_now(&online), 0);
+        assert!(online.pending_submissions().is_empty());
+        let top = LeaderboardClient::new(&addr).fetch_top(10).unwrap();
+        assert_eq!(
+            top,
+            vec![ScoreRecord {
+                timestamp: Some(online.load_highscores()[0].timestamp),
+                ..ScoreRecord::new("Alice", 200)
+            }]
+        );
+
+        remove_queue();
+        let _ = std::fs::remove_file("test_server_rejected.json");
+    }
+}
@@ -0,0 +1,523 @@
+//! Rebindable keyboard controls.
+//!
+//! [`Bindings`] maps keys to [`Action`]s; an action can have several keys,
+//! but a key belongs to one action only. [`ControlsManager`] keeps one set
+//! of bindings per player name, stored like the profiles: a JSON file on
+//! desktop and a localStorage entry on WASM. [`ControlsScreen`] is the
+//! menu screen that edits them.
+
+use crate::input::{Action, InputFrame, KEY_BINDINGS};
+use crate::storage;
+use macroquad::input::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Keys that can be bound to actions.
+pub const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Space,
+    KeyCode::Enter,
+    KeyCode::Escape,
+    KeyCode::Backspace,
+    KeyCode::Tab,
+    KeyCode::Insert,
+    KeyCode::Delete,
+    KeyCode::Home,
+    KeyCode::End,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::LeftShift,
+    KeyCode::RightShift,
+    KeyCode::LeftControl,
+    KeyCode::RightControl,
+    KeyCode::LeftAlt,
+    KeyCode::RightAlt,
+    KeyCode::Comma,
+    KeyCode::Period,
+    KeyCode::Slash,
+    KeyCode::Backslash,
+    KeyCode::Semicolon,
+    KeyCode::Apostrophe,
+    KeyCode::Minus,
+    KeyCode::Equal,
+    KeyCode::LeftBracket,
+    KeyCode::RightBracket,
+    KeyCode::GraveAccent,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+    KeyCode::KpEnter,
+];
+
+/// Name of a key as shown on screen and saved to storage.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::bindings::{key_from_name, key_name};
+/// use macroquad::input::KeyCode;
+///
+/// assert_eq!(key_name(KeyCode::Key1), "1");
+/// assert_eq!(key_from_name("Space"), Some(KeyCode::Space));
+/// ```
+#[must_use]
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match name.strip_prefix("Key") {
+        Some(digit) => digit.to_string(),
+        None => name,
+    }
+}
+
+/// The bindable key called `name`, see [`key_name`].
+#[must_use]
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|&key| key_name(key) == name)
+}
+
+/// Why a key could not be bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindError {
+    /// The key is not in [`BINDABLE_KEYS`]
+    NotBindable(KeyCode),
+    /// The key already belongs to another action
+    Taken(KeyCode, Action),
+}
+
+impl fmt::Display for BindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBindable(key) => write!(f, "{:?} can't be bound", key),
+            Self::Taken(key, action) => {
+                write!(
+                    f,
+                    "{} is already used for {}",
+                    key_name(*key),
+                    action.label()
+                )
+            }
+        }
+    }
+}
+
+/// Keys mapped to actions, grouped by action.
+///
+/// Saved as a map from action to key names. Actions missing from a saved
+/// map (added in a later version) get their default keys, if those are
+/// still free.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::bindings::{BindError, Bindings};
+/// use bumblebees::input::Action;
+/// use macroquad::input::KeyCode;
+///
+/// let mut bindings = Bindings::default();
+/// bindings.bind(KeyCode::A, Action::MoveLeft).unwrap();
+/// assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::Left, KeyCode::A]);
+/// assert_eq!(
+///     bindings.bind(KeyCode::A, Action::Fire),
+///     Err(BindError::Taken(KeyCode::A, Action::MoveLeft))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    from = "BTreeMap<Action, Vec<String>>",
+    into = "BTreeMap<Action, Vec<String>>"
+)]
+pub struct Bindings {
+    keys: Vec<(KeyCode, Action)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let mut bindings = Self { keys: Vec::new() };
+        for &(key, action) in KEY_BINDINGS {
+            let _ = bindings.bind(key, action);
+        }
+        bindings
+    }
+}
+
+impl Bindings {
+    /// All key bindings.
+    pub fn iter(&self) -> impl Iterator<Item = (KeyCode, Action)> + '_ {
+        self.keys.iter().copied()
+    }
+
+    /// Keys bound to `action`, in the order they were added.
+    #[must_use]
+    pub fn keys(&self, action: Action) -> Vec<KeyCode> {
+        self.iter()
+            .filter(|&(_, bound)| bound == action)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Action bound to `key`, if any.
+    #[must_use]
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.iter()
+            .find(|&(bound, _)| bound == key)
+            .map(|(_, action)| action)
+    }
+
+    /// Add `key` to the keys of `action`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the key can't be bound or belongs to another action.
+    pub fn bind(&mut self, key: KeyCode, action: Action) -> Result<(), BindError> {
+        if !BINDABLE_KEYS.contains(&key) {
+            return Err(BindError::NotBindable(key));
+        }
+        match self.action(key) {
+            Some(bound) if bound == action => Ok(()),
+            Some(bound) => Err(BindError::Taken(key, bound)),
+            None => {
+                // After the action's other keys, to keep actions together
+                let at = self
+                    .keys
+                    .iter()
+                    .rposition(|&(_, bound)| bound <= action)
+                    .map_or(0, |index| index + 1);
+                self.keys.insert(at, (key, action));
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove the most recently added key of `action`. The last key of an
+    /// action is kept so every action stays reachable; returns whether a key
+    /// was removed.
+    pub fn unbind_last(&mut self, action: Action) -> bool {
+        let keys = self.keys(action);
+        if keys.len() < 2 {
+            return false;
+        }
+        let last = keys[keys.len() - 1];
+        self.keys.retain(|&(key, _)| key != last);
+        true
+    }
+}
+
+impl From<BTreeMap<Action, Vec<String>>> for Bindings {
+    fn from(saved: BTreeMap<Action, Vec<String>>) -> Self {
+        let mut bindings = Self { keys: Vec::new() };
+        for (&action, names) in &saved {
+            for name in names {
+                match key_from_name(name) {
+                    Some(key) => {
+                        let _ = bindings.bind(key, action);
+                    }
+                    None => log::warn!("Ignoring unknown key {:?} for {:?}", name, action),
+                }
+            }
+        }
+        for &(key, action) in KEY_BINDINGS {
+            if !saved.contains_key(&action) || bindings.keys(action).is_empty() {
+                let _ = bindings.bind(key, action);
+            }
+        }
+        bindings
+    }
+}
+
+impl From<Bindings> for BTreeMap<Action, Vec<String>> {
+    fn from(bindings: Bindings) -> Self {
+        let mut saved = Self::new();
+        for (key, action) in bindings.iter() {
+            saved
+                .entry(action)
+                .or_insert_with(Vec::new)
+                .push(key_name(key));
+        }
+        saved
+    }
+}
+
+/// Cross-platform persistence of key bindings per player name.
+///
+/// Players without saved bindings use the defaults. Unreadable or corrupted
+/// storage starts with defaults for everyone, so the game always starts.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bumblebees::bindings::{Bindings, ControlsManager};
+/// use bumblebees::input::Action;
+/// use macroquad::input::KeyCode;
+///
+/// let mut controls = ControlsManager::new("controls.json");
+/// let mut bindings = controls.bindings("PLAYER1");
+/// bindings.bind(KeyCode::Q, Action::MoveLeft).unwrap();
+/// controls.set("PLAYER1", &bindings);
+/// ```
+pub struct ControlsManager {
+    /// Storage key: filename (desktop) or localStorage key (WASM)
+    storage_key: String,
+    store: BTreeMap<String, Bindings>,
+}
+
+impl ControlsManager {
+    /// Load bindings from the given storage key.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        let store = storage::load(key)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            storage_key: key.to_string(),
+            store,
+        }
+    }
+
+    /// Bindings of the named player, or the defaults.
+    #[must_use]
+    pub fn bindings(&self, name: &str) -> Bindings {
+        self.store.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Save the named player's bindings; defaults aren't stored.
+    pub fn set(&mut self, name: &str, bindings: &Bindings) {
+        if *bindings == Bindings::default() {
+            self.store.remove(name);
+        } else {
+            self.store.insert(name.to_string(), bindings.clone());
+        }
+        if let Ok(json) = serde_json::to_string(&self.store) {
+            storage::save(&self.storage_key, &json);
+        }
+    }
+}
+
+/// State of the controls screen: a list of actions, one selected.
+///
+/// Confirm waits for a key to add to the selected action (Escape cancels),
+/// Erase removes its newest key and Restart goes back to the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ControlsScreen {
+    /// Index into [`Action::ALL`]
+    pub selected: usize,
+    /// Waiting for the key to bind
+    pub capturing: bool,
+    /// Result of the last change (conflicts and the like)
+    pub message: Option<String>,
+}
+
+impl ControlsScreen {
+    /// The selected action.
+    #[must_use]
+    pub fn action(&self) -> Action {
+        Action::ALL[self.selected]
+    }
+
+    /// Apply one frame of input to the screen and `bindings`; returns
+    /// whether the bindings changed.
+    pub fn handle(&mut self, frame: &InputFrame, bindings: &mut Bindings) -> bool {
+        if self.capturing {
+            let Some(key) = frame.key else {
+                return false;
+            };
+            self.capturing = false;
+            if key == KeyCode::Escape {
+                self.message = None;
+                return false;
+            }
+            return match bindings.bind(key, self.action()) {
+                Ok(()) => {
+                    self.message = None;
+                    true
+                }
+                Err(err) => {
+                    self.message = Some(err.to_string());
+                    false
+                }
+            };
+        }
+
+        let count = Action::ALL.len();
+        if frame.is_pressed(Action::Up) {
+            self.selected = (self.selected + count - 1) % count;
+            self.message = None;
+        }
+        if frame.is_pressed(Action::Down) {
+            self.selected = (self.selected + 1) % count;
+            self.message = None;
+        }
+        if frame.is_pressed(Action::Confirm) {
+            self.capturing = true;
+            self.message = None;
+            return false;
+        }
+        if frame.is_pressed(Action::Erase) {
+            if bindings.unbind_last(self.action()) {
+                self.message = None;
+                return true;
+            }
+            self.message = Some(format!("{} needs at least one key", self.action().label()));
+        }
+        if frame.is_pressed(Action::Restart) {
+            *bindings = Bindings::default();
+            self.message = Some("Default controls restored".to_string());
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_with(action: Action, key: KeyCode) -> InputFrame {
+        let mut frame = InputFrame::default();
+        frame.press(action);
+        frame.key = Some(key);
+        frame
+    }
+
+    #[test]
+    fn test_bind_detects_conflicts() {
+        let mut bindings = Bindings::default();
+        assert_eq!(bindings.action(KeyCode::Space), Some(Action::Fire));
+        assert_eq!(
+            bindings.bind(KeyCode::Space, Action::MoveLeft),
+            Err(BindError::Taken(KeyCode::Space, Action::Fire))
+        );
+        assert_eq!(bindings.bind(KeyCode::Space, Action::Fire), Ok(()));
+        assert_eq!(
+            bindings.bind(KeyCode::Unknown, Action::Fire),
+            Err(BindError::NotBindable(KeyCode::Unknown))
+        );
+
+        bindings.bind(KeyCode::Z, Action::Fire).unwrap();
+        assert_eq!(bindings.keys(Action::Fire), [KeyCode::Space, KeyCode::Z]);
+        assert!(bindings.unbind_last(Action::Fire));
+        // The last key stays
+        assert!(!bindings.unbind_last(Action::Fire));
+        assert_eq!(bindings.keys(Action::Fire), [KeyCode::Space]);
+    }
+
+    #[test]
+    fn test_bindings_round_trip_and_fill_in_defaults() {
+        let mut bindings = Bindings::default();
+        bindings.bind(KeyCode::Q, Action::MoveLeft).unwrap();
+        let json = serde_json::to_string(&bindings).unwrap();
+        assert!(json.contains(r#""MoveLeft":["Left","Q"]"#));
+        assert_eq!(serde_json::from_str::<Bindings>(&json).unwrap(), bindings);
+
+        // Unknown keys are dropped and missing actions get their defaults
+        let saved: Bindings = serde_json::from_str(r#"{"Fire":["Nope","X"]}"#).unwrap();
+        assert_eq!(saved.keys(Action::Fire), [KeyCode::X]);
+        assert_eq!(saved.keys(Action::MoveLeft), [KeyCode::Left]);
+        assert_eq!(saved.action(KeyCode::Space), None);
+    }
+
+    #[test]
+    fn test_controls_screen_rebinds_selected_action() {
+        let mut screen = ControlsScreen::default();
+        let mut bindings = Bindings::default();
+
+        // Enter starts listening; the key of that frame isn't bound
+        assert!(!screen.handle(&frame_with(Action::Confirm, KeyCode::Enter), &mut bindings));
+        assert!(screen.capturing);
+        assert!(screen.handle(&frame_with(Action::Fire, KeyCode::Q), &mut bindings));
+        assert_eq!(bindings.keys(Action::MoveLeft), [KeyCode::Left, KeyCode::Q]);
+
+        // A taken key is refused with a message
+        screen.handle(&frame_with(Action::Confirm, KeyCode::Enter), &mut bindings);
+        assert!(!screen.handle(&frame_with(Action::Fire, KeyCode::Space), &mut bindings));
+        assert!(screen.message.as_deref().unwrap().contains("Fire"));
+
+        // Escape cancels
+        screen.handle(&frame_with(Action::Confirm, KeyCode::Enter), &mut bindings);
+        assert!(!screen.handle(&frame_with(Action::Back, KeyCode::Escape), &mut bindings));
+        assert!(!screen.capturing);
+
+        assert!(screen.handle(&frame_with(Action::Restart, KeyCode::R), &mut bindings));
+        assert_eq!(bindings, Bindings::default());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_controls_persist_per_player() {
+        let test_file = "test_controls_persist.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut controls = ControlsManager::new(test_file);
+        let mut bindings = controls.bindings("Alice");
+        bindings.bind(KeyCode::A, Action::MoveLeft).unwrap();
+        controls.set("Alice", &bindings);
+
+        let reloaded = ControlsManager::new(test_file);
+        assert_eq!(reloaded.bindings("Alice"), bindings);
+        assert_eq!(reloaded.bindings("Bob"), Bindings::default());
+
+        let _ = std::fs::remove_file(test_file);
+    }
+}
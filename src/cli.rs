@@ -0,0 +1,443 @@
+//! Command-line interface for the desktop build.
+//!
+//! Without a subcommand the game starts, optionally with flags such as
+//! `--wave 5 --difficulty hard` or `--headless 3600`. Subcommands run a one-off task and exit without
+//! opening a window:
+//!
+//! ```text
+//! bumblebees scores export FILE   Write the local leaderboard to FILE
+//! bumblebees scores import FILE   Replace the local leaderboard with FILE
+//! bumblebees scores merge FILE    Merge FILE into the local leaderboard
+//! bumblebees telemetry analyze [FILE...]
+//!                                 Summarize telemetry logs per wave
+//! ```
+//!
+//! `--save-dir DIR` works with the subcommands too.
+
+use crate::constants::MAX_NAME_LEN;
+use crate::difficulty::Difficulty;
+use crate::highscore::ImportMode;
+use crate::logger::parse_level;
+use log::LevelFilter;
+use std::path::{Path, PathBuf};
+
+/// Usage text printed for `--help` and on argument errors.
+pub const USAGE: &str = "\
+Usage: bumblebees [OPTIONS]
+       bumblebees COMMAND [--save-dir DIR]
+
+Commands:
+  scores export FILE   Write the local leaderboard to FILE (JSON)
+  scores import FILE   Replace the local leaderboard with the scores in FILE
+  scores merge FILE    Add the scores in FILE to the local leaderboard
+  telemetry analyze [FILE...]
+                       Print per-wave death rates and average scores
+                       from telemetry logs (default: the game's own log)
+  help                 Show this message
+
+Without a command the game starts.
+
+Options:
+  --wave N             Start at wave N (practice, not recorded)
+  --seed N             Random seed of the run
+  --difficulty LEVEL   easy, normal or hard
+  --name NAME          Play as NAME (up to 20 letters and digits),
+                       skipping the menu
+  --fullscreen         Start in fullscreen
+  --windowed           Start in a window (default)
+  --scale FACTOR       Window size as a multiple of 1024x575 (up to 4)
+  --save-dir DIR       Keep scores, profiles and other saves in DIR
+  --mute               Start without music and sound effects
+  --replay FILE        Play back a recorded run (see replay_last.json)
+  --bot                Let a simple bot play (not recorded)
+  --headless FRAMES    Simulate up to FRAMES frames without a window
+                       and print the result (with --replay: its inputs,
+                       with --bot: the bot's)
+  --log-level LEVEL    off, error, warn, info, debug or trace
+                       (default: $BUMBLEBEES_LOG or info)
+  --log-file FILE      Also append log messages to FILE
+  --debug              Enable the debug overlay (F1) in release builds";
+
+/// Settings for starting the game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayOptions {
+    /// Wave the run starts at
+    pub wave: Option<u32>,
+    /// Random seed; `None` picks one from the clock
+    pub seed: Option<u64>,
+    /// Difficulty instead of the menu default
+    pub difficulty: Option<Difficulty>,
+    /// Player name; starts the run right away
+    pub name: Option<String>,
+    /// `Some(true)` for fullscreen, `Some(false)` for a window
+    pub fullscreen: Option<bool>,
+    /// Window size multiplier
+    pub scale: Option<f32>,
+    /// Directory for saved files instead of the working directory
+    pub save_dir: Option<PathBuf>,
+    /// Start with audio off
+    pub mute: bool,
+    /// Recording to play back
+    pub replay: Option<PathBuf>,
+    /// Let the bot play
+    pub bot: bool,
+    /// Run this many frames without a window
+    pub headless: Option<u64>,
+    /// Log level; `None` falls back to the environment variable
+    pub log_level: Option<LevelFilter>,
+    /// File that log messages are appended to
+    pub log_file: Option<PathBuf>,
+    /// Allow the debug overlay in release builds
+    pub debug: bool,
+}
+
+/// What the program was asked to do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Start the game
+    Play(PlayOptions),
+    /// Export the local leaderboard to a file
+    ExportScores {
+        path: PathBuf,
+        save_dir: Option<PathBuf>,
+    },
+    /// Import a leaderboard file, merging with or replacing local scores
+    ImportScores {
+        path: PathBuf,
+        mode: ImportMode,
+        save_dir: Option<PathBuf>,
+    },
+    /// Aggregate telemetry logs into a per-wave report; no paths means the
+    /// game's own log
+    AnalyzeTelemetry {
+        paths: Vec<PathBuf>,
+        save_dir: Option<PathBuf>,
+    },
+    /// Print usage and exit
+    Help,
+}
+
+impl Command {
+    /// Directory for saved files given with `--save-dir`, if any.
+    #[must_use]
+    pub fn save_dir(&self) -> Option<&Path> {
+        match self {
+            Self::Play(PlayOptions { save_dir, .. })
+            | Self::ExportScores { save_dir, .. }
+            | Self::ImportScores { save_dir, .. }
+            | Self::AnalyzeTelemetry { save_dir, .. } => save_dir.as_deref(),
+            Self::Help => None,
+        }
+    }
+}
+
+/// Parse command-line arguments (without the program name).
+///
+/// # Errors
+///
+/// Returns a message describing the first unrecognized or missing argument.
+pub fn parse_args<I, S>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let args: Vec<String> = args.into_iter().map(Into::into).collect();
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        [] => return Ok(Command::Play(PlayOptions::default())),
+        ["help" | "-h" | "--help", ..] => return Ok(Command::Help),
+        [flag, ..] if flag.starts_with('-') => return parse_play_options(&args).map(Command::Play),
+        _ => {}
+    }
+
+    // Subcommands take `--save-dir DIR` anywhere after the command
+    let save_dir = match args.iter().position(|arg| *arg == "--save-dir") {
+        Some(index) => {
+            let dir = args
+                .get(index + 1)
+                .ok_or_else(|| "'--save-dir' needs a value".to_string())?;
+            let dir = PathBuf::from(dir);
+            args.drain(index..=index + 1);
+            Some(dir)
+        }
+        None => None,
+    };
+
+    match args.as_slice() {
+        ["scores", "export", path] => Ok(Command::ExportScores {
+            path: PathBuf::from(path),
+            save_dir,
+        }),
+        ["scores", "import", path] => Ok(Command::ImportScores {
+            path: PathBuf::from(path),
+            mode: ImportMode::Replace,
+            save_dir,
+        }),
+        ["scores", "merge", path] => Ok(Command::ImportScores {
+            path: PathBuf::from(path),
+            mode: ImportMode::Merge,
+            save_dir,
+        }),
+        ["scores", action @ ("export" | "import" | "merge")] => {
+            Err(format!("'scores {}' needs a FILE argument", action))
+        }
+        ["scores", ..] => Err("expected 'scores export|import|merge FILE'".to_string()),
+        ["telemetry", "analyze", paths @ ..] => Ok(Command::AnalyzeTelemetry {
+            paths: paths.iter().map(PathBuf::from).collect(),
+            save_dir,
+        }),
+        ["telemetry", ..] => Err("expected 'telemetry analyze [FILE...]'".to_string()),
+        [other, ..] => Err(format!("unknown argument '{}'", other)),
+        [] => Err("missing command".to_string()),
+    }
+}
+
+/// Parse the flags given when starting the game.
+fn parse_play_options(args: &[&str]) -> Result<PlayOptions, String> {
+    let mut options = PlayOptions::default();
+    let mut args = args.iter().copied();
+
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("'{}' needs a value", flag))
+        };
+        match flag {
+            "--wave" => match number::<u32>(flag, value()?)? {
+                0 => return Err("waves start at 1".to_string()),
+                wave => options.wave = Some(wave),
+            },
+            "--seed" => options.seed = Some(number(flag, value()?)?),
+            "--difficulty" => {
+                let name = value()?;
+                options.difficulty = Some(
+                    Difficulty::from_name(name)
+                        .ok_or_else(|| format!("unknown difficulty '{}'", name))?,
+                );
+            }
+            "--name" => match value()?.trim() {
+                "" => return Err("'--name' needs a non-empty NAME".to_string()),
+                name if is_valid_name(name) => options.name = Some(name.to_string()),
+                name => {
+                    return Err(format!(
+                        "'{}' is not a valid name: use up to {} letters and digits",
+                        name, MAX_NAME_LEN
+                    ))
+                }
+            },
+            "--fullscreen" => options.fullscreen = Some(true),
+            "--windowed" => options.fullscreen = Some(false),
+            "--scale" => match number::<f32>(flag, value()?)? {
+                scale if scale > 0.0 && scale <= 4.0 => options.scale = Some(scale),
+                _ => return Err("'--scale' must be greater than 0 and at most 4".to_string()),
+            },
+            "--save-dir" => options.save_dir = Some(PathBuf::from(value()?)),
+            "--mute" => options.mute = true,
+            "--replay" => options.replay = Some(PathBuf::from(value()?)),
+            "--bot" => options.bot = true,
+            "--headless" => options.headless = Some(number(flag, value()?)?),
+            "--log-level" => {
+                let level = value()?;
+                options.log_level = Some(
+                    parse_level(level).ok_or_else(|| format!("unknown log level '{}'", level))?,
+                );
+            }
+            "--log-file" => options.log_file = Some(PathBuf::from(value()?)),
+            "--debug" => options.debug = true,
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Same rules as typing a name in the menu: letters and digits only, at
+/// most `MAX_NAME_LEN` long.
+fn is_valid_name(name: &str) -> bool {
+    name.chars().count() <= MAX_NAME_LEN && name.chars().all(char::is_alphanumeric)
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{}' expects a number, got '{}'", flag, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_arguments_starts_game() {
+        assert_eq!(
+            parse_args(Vec::<String>::new()),
+            Ok(Command::Play(PlayOptions::default()))
+        );
+    }
+
+    #[test]
+    fn test_log_options() {
+        assert_eq!(
+            parse_args(["--log-level", "debug", "--log-file", "game.log"]),
+            Ok(Command::Play(PlayOptions {
+                log_level: Some(LevelFilter::Debug),
+                log_file: Some(PathBuf::from("game.log")),
+                ..PlayOptions::default()
+            }))
+        );
+        assert!(parse_args(["--log-level", "loud"]).is_err());
+        assert!(parse_args(["--log-level"]).is_err());
+        assert!(parse_args(["--log-file"]).is_err());
+        assert_eq!(
+            parse_args(["--debug"]),
+            Ok(Command::Play(PlayOptions {
+                debug: true,
+                ..PlayOptions::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_run_options() {
+        assert_eq!(
+            parse_args([
+                "--wave",
+                "5",
+                "--seed",
+                "1234",
+                "--difficulty",
+                "HARD",
+                "--name",
+                "Ada"
+            ]),
+            Ok(Command::Play(PlayOptions {
+                wave: Some(5),
+                seed: Some(1234),
+                difficulty: Some(Difficulty::Hard),
+                name: Some("Ada".to_string()),
+                ..PlayOptions::default()
+            }))
+        );
+        assert!(parse_args(["--wave", "0"]).is_err());
+        assert!(parse_args(["--wave", "five"]).is_err());
+        assert!(parse_args(["--difficulty", "nightmare"]).is_err());
+        assert!(parse_args(["--name", " "]).is_err());
+        assert!(parse_args(["--name", "a,b"]).is_err());
+        assert!(parse_args(["--name", "has space"]).is_err());
+        assert!(parse_args(["--name", "ABCDEFGHIJKLMNOPQRSTU"]).is_err());
+        assert!(parse_args(["--name", "ABCDEFGHIJKLMNOPQRST"]).is_ok());
+        // The limit counts characters, not bytes
+        assert!(parse_args(["--name", &"ü".repeat(MAX_NAME_LEN)]).is_ok());
+        assert!(parse_args(["--name", &"ü".repeat(MAX_NAME_LEN + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_window_and_system_options() {
+        assert_eq!(
+            parse_args([
+                "--fullscreen",
+                "--scale",
+                "1.5",
+                "--save-dir",
+                "saves",
+                "--mute",
+                "--replay",
+                "run.json",
+                "--bot",
+                "--headless",
+                "600"
+            ]),
+            Ok(Command::Play(PlayOptions {
+                fullscreen: Some(true),
+                scale: Some(1.5),
+                save_dir: Some(PathBuf::from("saves")),
+                mute: true,
+                replay: Some(PathBuf::from("run.json")),
+                bot: true,
+                headless: Some(600),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse_args(["--fullscreen", "--windowed"]),
+            Ok(Command::Play(PlayOptions {
+                fullscreen: Some(false),
+                ..PlayOptions::default()
+            }))
+        );
+        assert!(parse_args(["--scale", "0"]).is_err());
+        assert!(parse_args(["--scale", "8"]).is_err());
+        assert!(parse_args(["--headless"]).is_err());
+    }
+
+    #[test]
+    fn test_scores_subcommands() {
+        assert_eq!(
+            parse_args(["scores", "export", "out.json"]),
+            Ok(Command::ExportScores {
+                path: PathBuf::from("out.json"),
+                save_dir: None
+            })
+        );
+        assert_eq!(
+            parse_args(["scores", "import", "in.json"]),
+            Ok(Command::ImportScores {
+                path: PathBuf::from("in.json"),
+                mode: ImportMode::Replace,
+                save_dir: None
+            })
+        );
+        assert_eq!(
+            parse_args(["scores", "merge", "in.json"]),
+            Ok(Command::ImportScores {
+                path: PathBuf::from("in.json"),
+                mode: ImportMode::Merge,
+                save_dir: None
+            })
+        );
+        // The save directory can go before or after the file
+        for args in [
+            ["scores", "merge", "in.json", "--save-dir", "saves"],
+            ["scores", "merge", "--save-dir", "saves", "in.json"],
+        ] {
+            let command = parse_args(args).unwrap();
+            assert_eq!(command.save_dir(), Some(Path::new("saves")));
+        }
+        assert!(parse_args(["scores", "export", "out.json", "--save-dir"]).is_err());
+    }
+
+    #[test]
+    fn test_telemetry_analyze() {
+        assert_eq!(
+            parse_args(["telemetry", "analyze", "a.jsonl", "b.jsonl"]),
+            Ok(Command::AnalyzeTelemetry {
+                paths: vec![PathBuf::from("a.jsonl"), PathBuf::from("b.jsonl")],
+                save_dir: None
+            })
+        );
+        assert_eq!(
+            parse_args(["telemetry", "analyze", "--save-dir", "saves", "a.jsonl"]),
+            Ok(Command::AnalyzeTelemetry {
+                paths: vec![PathBuf::from("a.jsonl")],
+                save_dir: Some(PathBuf::from("saves"))
+            })
+        );
+        assert_eq!(
+            parse_args(["telemetry", "analyze"]),
+            Ok(Command::AnalyzeTelemetry {
+                paths: Vec::new(),
+                save_dir: None
+            })
+        );
+        assert!(parse_args(["telemetry"]).is_err());
+    }
+
+    #[test]
+    fn test_help_and_errors() {
+        assert_eq!(parse_args(["--help"]), Ok(Command::Help));
+        assert!(parse_args(["scores", "export"]).is_err());
+        assert!(parse_args(["scores", "delete", "x"]).is_err());
+        assert!(parse_args(["--bogus"]).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+//! Developer console.
+//!
+//! [`Console`] holds the drop-down console's input line and output, and
+//! [`ConsoleCommand::parse`] turns a typed line into a command. The game
+//! executes commands on its own state, and runs that used one are kept off
+//! the leaderboard.
+
+use crate::entities::EnemyType;
+use std::collections::VecDeque;
+
+/// Output lines kept by the console.
+const MAX_OUTPUT_LINES: usize = 100;
+
+/// Help text printed by the `help` command.
+pub const HELP: &[&str] = &[
+    "wave <n>              jump to wave n",
+    "spawn <type> <x> <y>  add an enemy (standard, fast, tank, swooper)",
+    "god                   toggle invulnerability at the defender line",
+    "bee                   send the bee now",
+    "speed <mult>          game speed multiplier (1 = normal)",
+    "score <n>             set the score",
+    "upgrade               upgrade the player's gun",
+    "clear                 destroy the current wave",
+];
+
+/// A parsed console command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleCommand {
+    /// Print the command list
+    Help,
+    /// Jump to the given wave
+    Wave(u32),
+    /// Add an enemy at a position
+    Spawn {
+        enemy_type: EnemyType,
+        x: f32,
+        y: f32,
+    },
+    /// Toggle god mode
+    God,
+    /// Spawn the flying bee
+    Bee,
+    /// Set the game speed multiplier
+    Speed(f32),
+    /// Set the score
+    Score(u32),
+    /// Upgrade the player
+    Upgrade,
+    /// Remove all enemies of the current wave
+    Clear,
+}
+
+impl ConsoleCommand {
+    /// Whether running the command changes gameplay (and so counts as a cheat).
+    #[must_use]
+    pub const fn is_cheat(self) -> bool {
+        !matches!(self, Self::Help)
+    }
+
+    /// Parse a console line such as `spawn tank 300 200`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for unknown commands and missing or invalid arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::console::ConsoleCommand;
+    ///
+    /// assert_eq!(ConsoleCommand::parse("wave 12"), Ok(ConsoleCommand::Wave(12)));
+    /// assert!(ConsoleCommand::parse("wave twelve").is_err());
+    /// ```
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["help"] => Ok(Self::Help),
+            ["wave", n] => match number::<u32>(n)? {
+                0 => Err("waves start at 1".to_string()),
+                wave => Ok(Self::Wave(wave)),
+            },
+            ["spawn", enemy_type, x, y] => Ok(Self::Spawn {
+                enemy_type: parse_enemy_type(enemy_type)?,
+                x: number(x)?,
+                y: number(y)?,
+            }),
+            ["god"] => Ok(Self::God),
+            ["bee"] => Ok(Self::Bee),
+            ["speed", multiplier] => match number::<f32>(multiplier)? {
+                multiplier if multiplier > 0.0 && multiplier.is_finite() => {
+                    Ok(Self::Speed(multiplier))
+                }
+                _ => Err("speed must be a positive number".to_string()),
+            },
+            ["score", n] => Ok(Self::Score(number(n)?)),
+            ["upgrade"] => Ok(Self::Upgrade),
+            ["clear"] => Ok(Self::Clear),
+            [] => Err("type 'help' for a list of commands".to_string()),
+            ["wave" | "spawn" | "speed" | "score", ..] => {
+                Err(format!("wrong arguments for '{}', see 'help'", words[0]))
+            }
+            [other, ..] => Err(format!("unknown command '{}'", other)),
+        }
+    }
+}
+
+fn number<T: std::str::FromStr>(word: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("'{}' is not a valid number", word))
+}
+
+fn parse_enemy_type(name: &str) -> Result<EnemyType, String> {
+    match name.to_lowercase().as_str() {
+        "standard" => Ok(EnemyType::Standard),
+        "fast" => Ok(EnemyType::Fast),
+        "tank" => Ok(EnemyType::Tank),
+        "swooper" => Ok(EnemyType::Swooper),
+        _ => Err(format!("unknown enemy type '{}'", name)),
+    }
+}
+
+/// Input line, output and command history of the drop-down console.
+#[derive(Debug, Clone, Default)]
+pub struct Console {
+    open: bool,
+    input: String,
+    output: VecDeque<String>,
+    history: Vec<String>,
+    /// Position while browsing the history with Up/Down
+    history_index: Option<usize>,
+}
+
+impl Console {
+    /// Create a closed, empty console.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the console is shown (and takes the keyboard).
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Show or hide the console.
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// The line being typed.
+    #[must_use]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Append a typed character; control characters are ignored.
+    pub fn push_char(&mut self, ch: char) {
+        if !ch.is_control() {
+            self.input.push(ch);
+        }
+    }
+
+    /// Delete the last typed character.
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// Replace the input with the previous (`step < 0`) or next command
+    /// from the history.
+    pub fn browse_history(&mut self, step: isize) {
+        if self.history.is_empty() {
+            return;
+        }
+        let last = self.history.len() - 1;
+        let index = match (self.history_index, step < 0) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index < last => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        self.history_index = index;
+        self.input = index.map_or_else(String::new, |index| self.history[index].clone());
+    }
+
+    /// Take the typed line, echo it to the output and remember it in the
+    /// history. Returns `None` for an empty line.
+    pub fn submit(&mut self) -> Option<String> {
+        let line = std::mem::take(&mut self.input).trim().to_string();
+        self.history_index = None;
+        if line.is_empty() {
+            return None;
+        }
+        self.print(format!("> {}", line));
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        Some(line)
+    }
+
+    /// Add a line to the output.
+    pub fn print(&mut self, line: impl Into<String>) {
+        if self.output.len() == MAX_OUTPUT_LINES {
+            self.output.pop_front();
+        }
+        self.output.push_back(line.into());
+    }
+
+    /// The last `count` output lines, oldest first.
+    pub fn output(&self, count: usize) -> impl Iterator<Item = &str> {
+        let skip = self.output.len().saturating_sub(count);
+        self.output.iter().skip(skip).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(ConsoleCommand::parse("  help "), Ok(ConsoleCommand::Help));
+        assert_eq!(
+            ConsoleCommand::parse("spawn Tank 300 200.5"),
+            Ok(ConsoleCommand::Spawn {
+                enemy_type: EnemyType::Tank,
+                x: 300.0,
+                y: 200.5
+            })
+        );
+        assert_eq!(ConsoleCommand::parse("god"), Ok(ConsoleCommand::God));
+        assert_eq!(ConsoleCommand::parse("bee"), Ok(ConsoleCommand::Bee));
+        assert_eq!(
+            ConsoleCommand::parse("speed 0.5"),
+            Ok(ConsoleCommand::Speed(0.5))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("score 99999"),
+            Ok(ConsoleCommand::Score(99999))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("upgrade"),
+            Ok(ConsoleCommand::Upgrade)
+        );
+        assert_eq!(ConsoleCommand::parse("clear"), Ok(ConsoleCommand::Clear));
+        assert!(!ConsoleCommand::Help.is_cheat());
+        assert!(ConsoleCommand::Clear.is_cheat());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(ConsoleCommand::parse("").is_err());
+        assert!(ConsoleCommand::parse("wave 0").is_err());
+        assert!(ConsoleCommand::parse("wave").is_err());
+        assert!(ConsoleCommand::parse("spawn dragon 1 1").is_err());
+        assert!(ConsoleCommand::parse("spawn tank 1").is_err());
+        assert!(ConsoleCommand::parse("speed -1").is_err());
+        assert!(ConsoleCommand::parse("speed NaN").is_err());
+        assert!(ConsoleCommand::parse("fly").is_err());
+    }
+
+    #[test]
+    fn test_submit_and_history() {
+        let mut console = Console::new();
+        for ch in "wave 5\n".chars() {
+            console.push_char(ch);
+        }
+        assert_eq!(console.input(), "wave 5");
+        assert_eq!(console.submit().as_deref(), Some("wave 5"));
+        assert_eq!(console.input(), "");
+        assert_eq!(console.submit(), None);
+
+        console.push_char('g');
+        console.push_char('o');
+        console.push_char('d');
+        console.submit();
+        assert_eq!(
+            console.output(10).collect::<Vec<_>>(),
+            ["> wave 5", "> god"]
+        );
+
+        console.browse_history(-1);
+        assert_eq!(console.input(), "god");
+        console.browse_history(-1);
+        assert_eq!(console.input(), "wave 5");
+        console.browse_history(-1);
+        assert_eq!(console.input(), "wave 5");
+        console.browse_history(1);
+        assert_eq!(console.input(), "god");
+        console.browse_history(1);
+        assert_eq!(console.input(), "");
+    }
+}
@@ -24,6 +24,12 @@ pub const DEFENDER_LINE: f32 = 100.0;
 /// Collision detection radius in pixels
 pub const COLLISION_RADIUS: f32 = 20.0;
 
+/// Distance from the screen edges at which the formation turns around
+pub const FORMATION_EDGE_MARGIN: f32 = 20.0;
+
+/// How far the formation steps down after reaching an edge, in pixels
+pub const FORMATION_DESCENT_STEP: f32 = 40.0;
+
 /// Enemy speed increase per wave in pixels per second
 /// Note: Points are now determined by enemy type (Standard:10, Fast:20, Tank:50, Swooper:30)
 pub const SPEED_INCREASE_PER_WAVE: f32 = 50.0;
@@ -46,5 +52,159 @@ pub const BEE_SPAWN_MIN_TIME: f32 = 10.0;
 /// Maximum time between bee spawns in seconds
 pub const BEE_SPAWN_MAX_TIME: f32 = 30.0;
 
+/// Radius of the flying bee's hit circle (half of its 100x100 sprite)
+pub const BEE_RADIUS: f32 = 50.0;
+
 /// Points awarded for shooting the flying bee
 pub const BEE_POINTS: u32 = 1000;
+
+/// Seconds a menu status message (e.g. export/import result) stays visible
+pub const STATUS_MESSAGE_DURATION: f32 = 4.0;
+
+/// File written by the menu's highscore export (browser download name on web)
+pub const HIGHSCORE_EXPORT_FILE: &str = "highscores_export.json";
+
+/// File read by the menu's highscore import on desktop (web uses a file picker)
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const HIGHSCORE_IMPORT_FILE: &str = "highscores_import.json";
+
+/// Runs per player kept on the all-time local leaderboard
+pub const MAX_RUNS_PER_PLAYER: usize = 3;
+
+/// Longest player name, in characters (menu, `--name`, imports and the server)
+pub const MAX_NAME_LEN: usize = 20;
+
+/// Storage for player profiles (file on desktop, localStorage key on web)
+pub const PROFILES_FILE: &str = "profiles.json";
+
+/// Storage for key bindings per player (file on desktop, localStorage key on web)
+pub const CONTROLS_FILE: &str = "controls.json";
+
+/// Storage for the player's settings (file on desktop, localStorage key on web)
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Storage for achievement unlocks (file on desktop, localStorage key on web)
+pub const ACHIEVEMENTS_FILE: &str = "achievements.json";
+
+/// Seconds an achievement unlock notification stays visible
+pub const ACHIEVEMENT_TOAST_DURATION: f32 = 3.0;
+
+/// Opt-in telemetry log (JSONL file on desktop, localStorage key on web)
+pub const TELEMETRY_FILE: &str = "telemetry.jsonl";
+
+/// Log lines shown by the in-game log overlay
+pub const LOG_OVERLAY_LINES: usize = 16;
+
+/// Frames of history in the debug overlay's frame time graph
+pub const DEBUG_GRAPH_SAMPLES: usize = 120;
+
+/// Recording of the last finished run (file on desktop, localStorage key on web)
+pub const REPLAY_FILE: &str = "replay_last.json";
+
+/// Seconds per frame of a headless simulation without a recording (60 FPS)
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const HEADLESS_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Pixels the bot lines up with its target before it fires
+pub const BOT_AIM_TOLERANCE: f32 = 10.0;
+
+/// Bullets in the air at which the bot holds its fire
+pub const BOT_MAX_BULLETS: usize = 6;
+
+/// Stick tilt below which a gamepad stick counts as centered
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const GAMEPAD_DEADZONE: f32 = 0.2;
+
+/// Stick tilt that counts as a press when navigating menus
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const GAMEPAD_NAV_THRESHOLD: f32 = 0.6;
+
+/// Range and step of the touch zone size, relative to the default size
+pub const TOUCH_ZONE_SCALE_MIN: f32 = 0.6;
+pub const TOUCH_ZONE_SCALE_MAX: f32 = 1.4;
+pub const TOUCH_ZONE_SCALE_STEP: f32 = 0.1;
+
+/// Default visibility of the touch zones and the step of its setting
+pub const TOUCH_OPACITY_DEFAULT: f32 = 0.4;
+pub const TOUCH_OPACITY_STEP: f32 = 0.1;
+
+/// Radius of the virtual joystick and the fire button at default size
+pub const TOUCH_STICK_RADIUS: f32 = 90.0;
+pub const TOUCH_FIRE_RADIUS: f32 = 70.0;
+
+/// Distance of the joystick and fire button from the screen edges
+pub const TOUCH_MARGIN: f32 = 30.0;
+
+/// Joystick deflection below which the player doesn't move
+pub const TOUCH_STICK_DEADZONE: f32 = 0.15;
+
+/// Seconds between shots while dragging with auto-fire
+pub const TOUCH_AUTOFIRE_INTERVAL: f32 = 0.25;
+
+/// Volume of the background music and the menu music at full music volume
+pub const MUSIC_VOLUME: f32 = 0.5;
+pub const INTRO_VOLUME: f32 = 0.7;
+
+/// Step of the volume settings
+pub const VOLUME_STEP: f32 = 0.1;
+
+/// Largest window size setting and its step, as multiples of the game area
+pub const WINDOW_SCALE_MAX: f32 = 4.0;
+pub const WINDOW_SCALE_STEP: f32 = 0.25;
+
+/// Step of the screen effects setting
+pub const EFFECTS_STEP: f32 = 0.25;
+
+/// Brightness above which the bloom pass makes pixels glow
+pub const POSTFX_BLOOM_THRESHOLD: f32 = 0.6;
+
+/// Music tracks and playlists
+pub const MUSIC_MANIFEST_FILE: &str = "resources/music.json";
+
+/// Folder for the player's own music (desktop) and how many tracks of it
+/// are loaded
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const USER_MUSIC_DIR: &str = "music";
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const USER_MUSIC_MAX_TRACKS: usize = 8;
+
+/// Seconds a music track takes to fade in or out
+pub const MUSIC_FADE_TIME: f32 = 1.0;
+
+/// Music volume while ducked under a big explosion, how long it stays
+/// down and how many seconds it takes to come back
+pub const DUCK_LEVEL: f32 = 0.35;
+pub const DUCK_HOLD: f32 = 0.4;
+pub const DUCK_RELEASE: f32 = 0.8;
+
+/// At most this many plays of the same sound effect within
+/// `SFX_INSTANCE_WINDOW` seconds; more are dropped so rapid fire doesn't clip
+pub const SFX_MAX_INSTANCES: usize = 3;
+pub const SFX_INSTANCE_WINDOW: f32 = 0.2;
+
+/// How far sounds at the screen edges are panned towards one speaker
+/// (1 = only that speaker)
+pub const STEREO_WIDTH: f32 = 0.8;
+
+/// Volume of the buzz of the flying bee relative to the other effects
+pub const BEE_BUZZ_VOLUME: f32 = 0.35;
+
+/// Seconds between the beats of the march with a full formation at the top
+/// of the screen, and with the last enemy at the defender line
+pub const MARCH_SLOWEST_INTERVAL: f32 = 0.9;
+pub const MARCH_FASTEST_INTERVAL: f32 = 0.15;
+
+/// How much the remaining enemy count drives the march tempo (0 to 1); the
+/// rest comes from the lowest enemy's distance to the defender line
+pub const MARCH_COUNT_WEIGHT: f32 = 0.5;
+
+/// Notes of the march loop in Hz (G2, F2, E2, D2) and their length in seconds
+pub const MARCH_NOTES: [f32; 4] = [98.0, 87.31, 82.41, 73.42];
+pub const MARCH_NOTE_LENGTH: f32 = 0.12;
+
+/// Volume of the march at full music volume
+pub const MARCH_VOLUME: f32 = 0.6;
+
+/// Play the march instead of the background music during a run (false
+/// plays both)
+pub const MARCH_REPLACES_MUSIC: bool = false;
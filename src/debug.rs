@@ -0,0 +1,129 @@
+//! Data behind the debug overlay.
+//!
+//! The overlay itself is drawn by the game; this module keeps the frame
+//! time history for its graphs and measures the enemy formation.
+
+use crate::constants::DEBUG_GRAPH_SAMPLES;
+use crate::entities::Enemy;
+use macroquad::math::Rect;
+use std::collections::VecDeque;
+
+/// The last [`DEBUG_GRAPH_SAMPLES`] frame times, oldest first.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::debug::FrameTimes;
+///
+/// let mut frames = FrameTimes::new();
+/// frames.push(0.016);
+/// frames.push(0.017);
+/// assert!((frames.average() - 0.0165).abs() < 1e-6);
+/// assert_eq!(frames.max(), 0.017);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrameTimes {
+    samples: VecDeque<f32>,
+}
+
+impl FrameTimes {
+    /// Create an empty history.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(DEBUG_GRAPH_SAMPLES),
+        }
+    }
+
+    /// Add the duration of a frame in seconds, dropping the oldest sample
+    /// once the history is full.
+    pub fn push(&mut self, dt: f32) {
+        if self.samples.len() == DEBUG_GRAPH_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(dt);
+    }
+
+    /// Frame times in seconds, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Average frame time in seconds (0 without samples).
+    #[must_use]
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    /// Longest frame time in seconds (0 without samples).
+    #[must_use]
+    pub fn max(&self) -> f32 {
+        self.samples.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Frames per second over the history (0 without samples).
+    #[must_use]
+    pub fn fps(&self) -> f32 {
+        let average = self.average();
+        if average > 0.0 {
+            1.0 / average
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Rectangle spanned by the enemies' centers, `None` without enemies.
+#[must_use]
+pub fn formation_bounds(enemies: &[Enemy]) -> Option<Rect> {
+    let first = enemies.first()?;
+    let (mut left, mut right, mut top, mut bottom) = (first.x, first.x, first.y, first.y);
+    for enemy in &enemies[1..] {
+        left = left.min(enemy.x);
+        right = right.max(enemy.x);
+        top = top.min(enemy.y);
+        bottom = bottom.max(enemy.y);
+    }
+    Some(Rect::new(left, top, right - left, bottom - top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::EnemyType;
+
+    #[test]
+    fn test_frame_times_keep_last_samples() {
+        let mut frames = FrameTimes::new();
+        assert_eq!(frames.fps(), 0.0);
+
+        for _ in 0..DEBUG_GRAPH_SAMPLES {
+            frames.push(0.1);
+        }
+        frames.push(0.02);
+        frames.push(0.02);
+
+        assert_eq!(frames.samples().count(), DEBUG_GRAPH_SAMPLES);
+        assert_eq!(frames.samples().last(), Some(0.02));
+        assert_eq!(frames.max(), 0.1);
+        assert!(frames.fps() > 10.0);
+    }
+
+    #[test]
+    fn test_formation_bounds() {
+        assert_eq!(formation_bounds(&[]), None);
+
+        let enemies = [
+            Enemy::new(100.0, 50.0, 1.0, EnemyType::Standard),
+            Enemy::new(300.0, 120.0, 1.0, EnemyType::Tank),
+            Enemy::new(200.0, 80.0, 1.0, EnemyType::Fast),
+        ];
+        assert_eq!(
+            formation_bounds(&enemies),
+            Some(Rect::new(100.0, 50.0, 200.0, 70.0))
+        );
+    }
+}
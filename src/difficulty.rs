@@ -0,0 +1,98 @@
+//! Difficulty levels.
+
+use serde::{Deserialize, Serialize};
+
+/// Difficulty chosen in the menu before a run.
+///
+/// Scales how fast the enemy formation moves, both at the start and in the
+/// per-wave speed increase.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// All levels, easiest first.
+    pub const ALL: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    /// Display name.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "EASY",
+            Self::Normal => "NORMAL",
+            Self::Hard => "HARD",
+        }
+    }
+
+    /// Level with the given name, ignoring case (`easy`, `normal`, `hard`).
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Multiplier applied to enemy speed and its per-wave increase.
+    #[must_use]
+    pub const fn enemy_speed_multiplier(self) -> f32 {
+        match self {
+            Self::Easy => 0.75,
+            Self::Normal => 1.0,
+            Self::Hard => 1.3,
+        }
+    }
+
+    /// Next harder level, staying on the hardest.
+    #[must_use]
+    pub const fn harder(self) -> Self {
+        match self {
+            Self::Easy => Self::Normal,
+            Self::Normal | Self::Hard => Self::Hard,
+        }
+    }
+
+    /// Next easier level, staying on the easiest.
+    #[must_use]
+    pub const fn easier(self) -> Self {
+        match self {
+            Self::Hard => Self::Normal,
+            Self::Normal | Self::Easy => Self::Easy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_steps_are_clamped() {
+        assert_eq!(Difficulty::Easy.easier(), Difficulty::Easy);
+        assert_eq!(Difficulty::Easy.harder(), Difficulty::Normal);
+        assert_eq!(Difficulty::Hard.harder(), Difficulty::Hard);
+        assert_eq!(Difficulty::Hard.easier(), Difficulty::Normal);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Difficulty::from_name("hard"), Some(Difficulty::Hard));
+        assert_eq!(Difficulty::from_name(" Easy "), Some(Difficulty::Easy));
+        assert_eq!(Difficulty::from_name("nightmare"), None);
+    }
+
+    #[test]
+    fn test_harder_levels_are_faster() {
+        let speeds: Vec<f32> = Difficulty::ALL
+            .iter()
+            .map(|difficulty| difficulty.enemy_speed_multiplier())
+            .collect();
+        assert!(speeds.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -1,9 +1,10 @@
 //! Enemy entity implementation.
 
 use crate::constants::{DEFENDER_LINE, SCREEN_HEIGHT};
+use serde::{Deserialize, Serialize};
 
 /// Enemy type determines behavior, appearance, health, and point value.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EnemyType {
     /// Standard enemy - 1 hit, normal speed, 10 points
     Standard,
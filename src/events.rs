@@ -0,0 +1,123 @@
+//! Gameplay event stream.
+//!
+//! Systems describe what happened during a frame by pushing [`GameEvent`]s
+//! into an [`EventQueue`]. Once the frame's systems have run, the game
+//! drains the queue and hands every event to its consumers (audio,
+//! explosions, score, stats, achievements), so a new consumer only has to
+//! look at the events instead of hooking into each system.
+
+use crate::entities::EnemyType;
+use crate::systems::FormationType;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened during gameplay.
+///
+/// Positions are screen coordinates of the entity's center.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// A new run started
+    RunStarted,
+    /// The player fired; `bullets` is the spread size of the shot
+    ShotFired { x: f32, bullets: usize },
+    /// Bullets left the screen without hitting anything
+    ShotMissed { bullets: usize },
+    /// A bullet hit an enemy (followed by `EnemyDestroyed` if it died)
+    EnemyHit {
+        enemy_type: EnemyType,
+        x: f32,
+        y: f32,
+    },
+    /// An enemy was destroyed and awards `points`
+    EnemyDestroyed {
+        enemy_type: EnemyType,
+        x: f32,
+        y: f32,
+        points: u32,
+    },
+    /// The flying bee was shot down by `bullets` bullets and awards `points`
+    BeeDestroyed {
+        x: f32,
+        y: f32,
+        points: u32,
+        bullets: usize,
+    },
+    /// The formation finished stepping down after reaching a screen edge;
+    /// `lowest_y` is the bottom edge of the lowest enemy
+    FormationDescended { lowest_y: f32 },
+    /// The last enemy of a wave was destroyed
+    WaveCleared { wave: u32, formation: FormationType },
+    /// An enemy crossed the defender line, ending the run
+    DefenderBreached { x: f32, y: f32 },
+}
+
+impl GameEvent {
+    /// Points the event awards.
+    #[must_use]
+    pub const fn points(&self) -> u32 {
+        match *self {
+            Self::EnemyDestroyed { points, .. } | Self::BeeDestroyed { points, .. } => points,
+            _ => 0,
+        }
+    }
+}
+
+/// Events of the current frame, in the order they were emitted.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::events::{EventQueue, GameEvent};
+///
+/// let mut events = EventQueue::new();
+/// events.emit(GameEvent::ShotFired { x: 100.0, bullets: 1 });
+/// events.emit(GameEvent::ShotMissed { bullets: 1 });
+///
+/// let frame: Vec<GameEvent> = events.drain().collect();
+/// assert_eq!(frame.len(), 2);
+/// assert_eq!(events.drain().count(), 0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventQueue {
+    events: Vec<GameEvent>,
+}
+
+impl EventQueue {
+    /// Create an empty queue.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event.
+    pub fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// Number of pending events.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether no events are pending.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Pending events in emission order, without removing them.
+    pub fn iter(&self) -> std::slice::Iter<'_, GameEvent> {
+        self.events.iter()
+    }
+
+    /// Drop all pending events.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Remove and return all pending events in emission order.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, GameEvent> {
+        self.events.drain(..)
+    }
+}
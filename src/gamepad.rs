@@ -0,0 +1,221 @@
+//! Gamepad input (desktop only).
+//!
+//! [`GamepadInput`] is an [`InputSource`] backed by gilrs. One controller
+//! is active at a time: the first one found, or whichever had a button
+//! pressed last. Controllers can be plugged in and out while the game runs;
+//! the game learns about it through [`GamepadInput::take_change`].
+
+use crate::constants::{GAMEPAD_DEADZONE, GAMEPAD_NAV_THRESHOLD};
+use crate::input::{analog_speed, Action, InputFrame, InputSource};
+use gilrs::{Axis, Button, Event, EventType, GamepadId, Gilrs};
+
+/// Default controller layout (standard gamepad names, south = A on Xbox).
+pub const GAMEPAD_BINDINGS: &[(Button, Action)] = &[
+    (Button::DPadLeft, Action::MoveLeft),
+    (Button::DPadRight, Action::MoveRight),
+    (Button::DPadUp, Action::Up),
+    (Button::DPadDown, Action::Down),
+    (Button::South, Action::Fire),
+    (Button::South, Action::Confirm),
+    (Button::East, Action::Back),
+    (Button::Select, Action::Back),
+    (Button::Start, Action::Pause),
+    (Button::Start, Action::Restart),
+];
+
+/// A controller was plugged in or out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GamepadChange {
+    /// Controller name
+    Connected(String),
+    /// Name of the controller that was in use
+    Disconnected(String),
+}
+
+/// Share of full speed for a stick position, 0 inside the dead zone and
+/// rising to 1 at full tilt.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::gamepad::stick_speed;
+///
+/// assert_eq!(stick_speed(0.1), 0.0);
+/// assert_eq!(stick_speed(-1.0), 1.0);
+/// assert!(stick_speed(0.6) > 0.0 && stick_speed(0.6) < 1.0);
+/// ```
+#[must_use]
+pub fn stick_speed(value: f32) -> f32 {
+    analog_speed(value, GAMEPAD_DEADZONE)
+}
+
+/// Direction of a stick axis for menu navigation: -1, 0 or 1.
+fn stick_direction(value: f32) -> i8 {
+    if value <= -GAMEPAD_NAV_THRESHOLD {
+        -1
+    } else if value >= GAMEPAD_NAV_THRESHOLD {
+        1
+    } else {
+        0
+    }
+}
+
+/// Buttons and the left stick of the active controller.
+pub struct GamepadInput {
+    /// None if gamepads aren't supported on this system
+    gilrs: Option<Gilrs>,
+    active: Option<GamepadId>,
+    /// Unreported connect or disconnect
+    change: Option<GamepadChange>,
+    /// Stick direction last frame, to turn tilts into menu presses
+    stick: (i8, i8),
+}
+
+impl GamepadInput {
+    /// Start listening for controllers.
+    #[must_use]
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(err) => {
+                log::warn!("Gamepads unavailable: {}", err);
+                None
+            }
+        };
+        // Controllers plugged in before the start don't send a connect event
+        let active = gilrs
+            .as_ref()
+            .and_then(|gilrs| gilrs.gamepads().next())
+            .map(|(id, gamepad)| {
+                log::info!("Using controller {}", gamepad.name());
+                id
+            });
+        Self {
+            gilrs,
+            active,
+            change: None,
+            stick: (0, 0),
+        }
+    }
+
+    /// Name of the controller in use.
+    #[must_use]
+    pub fn active_name(&self) -> Option<String> {
+        let gilrs = self.gilrs.as_ref()?;
+        self.active.map(|id| gilrs.gamepad(id).name().to_string())
+    }
+
+    /// The last connect or disconnect since the previous call.
+    pub fn take_change(&mut self) -> Option<GamepadChange> {
+        self.change.take()
+    }
+}
+
+impl Default for GamepadInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputSource for GamepadInput {
+    fn poll(&mut self, frame: &mut InputFrame) {
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return;
+        };
+
+        let mut pressed = Vec::new();
+        while let Some(Event { id, event, .. }) = gilrs.next_event() {
+            match event {
+                EventType::Connected => {
+                    let name = gilrs.gamepad(id).name().to_string();
+                    log::info!("Controller connected: {}", name);
+                    self.active.get_or_insert(id);
+                    self.change = Some(GamepadChange::Connected(name));
+                }
+                EventType::Disconnected => {
+                    let name = gilrs.gamepad(id).name().to_string();
+                    log::info!("Controller disconnected: {}", name);
+                    if self.active == Some(id) {
+                        // Fall back to another controller that is still there
+                        self.active = gilrs
+                            .gamepads()
+                            .map(|(id, _)| id)
+                            .find(|&other| other != id);
+                        self.change = Some(GamepadChange::Disconnected(name));
+                    }
+                }
+                EventType::ButtonPressed(button, _) => {
+                    if self.active != Some(id) {
+                        log::info!("Switched to controller {}", gilrs.gamepad(id).name());
+                        self.active = Some(id);
+                        pressed.clear();
+                    }
+                    pressed.push(button);
+                }
+                _ => {}
+            }
+        }
+
+        let Some(id) = self.active else {
+            return;
+        };
+        let gamepad = gilrs.gamepad(id);
+
+        for &(button, action) in GAMEPAD_BINDINGS {
+            if pressed.contains(&button) {
+                frame.press(action);
+            } else if gamepad.is_pressed(button) {
+                frame.hold(action);
+            }
+        }
+
+        // Left stick: tilting past the threshold steps through menus
+        let x = gamepad.value(Axis::LeftStickX);
+        let y = gamepad.value(Axis::LeftStickY);
+        let stick = (stick_direction(x), stick_direction(y));
+        if stick.0 != self.stick.0 {
+            match stick.0 {
+                -1 => frame.press(Action::MoveLeft),
+                1 => frame.press(Action::MoveRight),
+                _ => {}
+            }
+        }
+        if stick.1 != self.stick.1 {
+            match stick.1 {
+                1 => frame.press(Action::Up),
+                -1 => frame.press(Action::Down),
+                _ => {}
+            }
+        }
+        self.stick = stick;
+
+        // ...and moves the player at a speed scaled by the tilt, unless a
+        // key or the d-pad already moves at full speed
+        let speed = stick_speed(x);
+        if speed > 0.0 && !frame.is_held(Action::MoveLeft) && !frame.is_held(Action::MoveRight) {
+            frame.hold(if x < 0.0 {
+                Action::MoveLeft
+            } else {
+                Action::MoveRight
+            });
+            frame.move_speed = Some(speed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stick_speed_scales_past_dead_zone() {
+        assert_eq!(stick_speed(0.0), 0.0);
+        assert_eq!(stick_speed(-GAMEPAD_DEADZONE), 0.0);
+        assert!(
+            (stick_speed(0.6) - (0.6 - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)).abs() < 1e-6
+        );
+        assert_eq!(stick_speed(1.2), 1.0);
+        assert_eq!(stick_direction(-0.9), -1);
+        assert_eq!(stick_direction(0.3), 0);
+    }
+}
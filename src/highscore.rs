@@ -1,28 +1,68 @@
 //! Highscore management with cross-platform storage.
 //!
-//! Uses file I/O on desktop and LocalStorage on WASM.
-
+//! Uses file I/O on desktop and LocalStorage on WASM. Every saved entry
+//! carries an HMAC-SHA256 signature made with a per-install key, so edits
+//! to the leaderboard outside the game are detected on load.
+//!
+//! Each leaderboard has a [`LeaderboardPolicy`] deciding which runs it keeps:
+//! all of them, or only each player's best, optionally restricted to runs
+//! from today or this week.
+
+use crate::constants::MAX_NAME_LEN;
+use crate::leaderboard::{LeaderboardClient, ScoreRecord, SyncJob, SyncOutcome};
+use crate::stats::RunStats;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::cell::OnceCell;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::cell::RefCell;
 
+type HmacSha256 = Hmac<Sha256>;
+
 /// Maximum size of localStorage data to prevent memory exhaustion (1MB)
-#[cfg(target_arch = "wasm32")]
-const MAX_LOCALSTORAGE_SIZE: usize = 1024 * 1024;
+///
+/// Longer values are not read back at all.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) const MAX_LOCALSTORAGE_SIZE: usize = 1024 * 1024;
 
 /// Maximum number of highscore entries to prevent DoS attacks (1000 entries)
-#[cfg(target_arch = "wasm32")]
 const MAX_HIGHSCORE_ENTRIES: usize = 1000;
 
 /// Maximum number of highscores persisted on disk/browser storage.
 const MAX_SAVED_SCORES: usize = 50;
 
+/// Length of the per-install signing key in bytes.
+const INSTALL_KEY_LEN: usize = 32;
+
+/// Maximum number of runs waiting for upload to the online leaderboard.
+const MAX_PENDING_SUBMISSIONS: usize = 100;
+
+/// Maximum size of an imported leaderboard file (1MB)
+const MAX_IMPORT_SIZE: usize = 1024 * 1024;
+
+/// Format identifier written into exported leaderboard files.
+const EXPORT_FORMAT: &str = "bumblebees-highscores";
+
+/// Current version of the export file format.
+const EXPORT_VERSION: u32 = 1;
+
+/// Placeholder signature marking the candidate entry in `qualifying_rank()`.
+const RANK_PREVIEW_MARKER: &str = "preview";
+
+/// Seconds per day, used for the time-window boundaries.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
 /// A single highscore entry containing player name and score.
 ///
 /// This struct is serialized to JSON for WASM localStorage storage
 /// and to CSV format for desktop file storage.
 ///
+/// Entries loaded from storage whose signature is missing or does not
+/// match the install key are kept, but have `verified` set to `false`
+/// so the UI can flag them.
+///
 /// # Examples
 ///
 /// ```
@@ -38,6 +78,19 @@ pub struct HighscoreEntry {
     pub name: String,
     /// Player score (points earned from destroying enemies)
     pub score: u32,
+    /// When the run was saved (Unix seconds); 0 for entries saved before
+    /// timestamps were recorded
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Hex-encoded HMAC-SHA256 over name, score and timestamp (set when saved)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Whether the signature matched the install key when loaded
+    #[serde(skip)]
+    pub verified: bool,
+    /// Statistics of the run, if recorded (not covered by the signature)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<RunStats>,
 }
 
 impl HighscoreEntry {
@@ -50,13 +103,169 @@ impl HighscoreEntry {
     ///
     /// # Returns
     ///
-    /// A new `HighscoreEntry` with the given name and score
+    /// A new, unsigned `HighscoreEntry` with the given name and score.
+    /// Entries created in memory are trusted until they round-trip
+    /// through storage.
     #[must_use]
     pub fn new(name: String, score: u32) -> Self {
-        Self { name, score }
+        Self {
+            name,
+            score,
+            timestamp: 0,
+            signature: None,
+            verified: true,
+            stats: None,
+        }
+    }
+
+    /// Bytes covered by the entry signature.
+    ///
+    /// Entries without a timestamp keep the original `name\nscore` message
+    /// so signatures written before timestamps existed stay valid.
+    fn signed_message(&self) -> Vec<u8> {
+        if self.timestamp == 0 {
+            format!("{}\n{}", self.name, self.score).into_bytes()
+        } else {
+            format!("{}\n{}\n{}", self.name, self.score, self.timestamp).into_bytes()
+        }
+    }
+}
+
+/// Which runs a leaderboard keeps per player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Retention {
+    /// Every run is kept, so one player may fill the whole board
+    #[default]
+    AllRuns,
+    /// Only each player's highest score
+    BestPerPlayer,
+    /// Each player's N highest scores
+    BestNPerPlayer(usize),
+}
+
+/// Which period of time a leaderboard covers.
+///
+/// Days and weeks are calendar periods in UTC; weeks start on Monday.
+/// Entries without a timestamp only appear on all-time leaderboards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeWindow {
+    /// Runs from the current day
+    Today,
+    /// Runs from the current week
+    ThisWeek,
+    /// Every run regardless of age
+    #[default]
+    AllTime,
+}
+
+impl TimeWindow {
+    /// Whether a run saved at `timestamp` falls inside this window at `now`
+    /// (both Unix seconds).
+    #[must_use]
+    pub fn contains(self, timestamp: u64, now: u64) -> bool {
+        let day = |secs: u64| secs / SECONDS_PER_DAY;
+        // 1970-01-01 was a Thursday; shift by 3 days so weeks start on Monday
+        let week = |secs: u64| (day(secs) + 3) / 7;
+
+        match self {
+            Self::AllTime => true,
+            _ if timestamp == 0 => false,
+            Self::Today => day(timestamp) == day(now),
+            Self::ThisWeek => week(timestamp) == week(now),
+        }
+    }
+}
+
+/// Retention rules of a single leaderboard.
+///
+/// Applied when scores are saved (dropped runs are not persisted) and again
+/// when they are queried, so time windows roll over without a new save.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::highscore::{HighscoreManager, LeaderboardPolicy, Retention, TimeWindow};
+///
+/// let weekly = HighscoreManager::new("weekly_highscores.txt").with_policy(LeaderboardPolicy {
+///     retention: Retention::BestPerPlayer,
+///     window: TimeWindow::ThisWeek,
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LeaderboardPolicy {
+    /// Runs kept per player
+    pub retention: Retention,
+    /// Period of time covered
+    pub window: TimeWindow,
+}
+
+impl LeaderboardPolicy {
+    /// Filter `entries` in place: sort by score (highest first, ties keep
+    /// their order), drop runs outside the time window, then drop runs
+    /// beyond the per-player limit. Players are matched by exact name.
+    pub fn apply(&self, entries: &mut Vec<HighscoreEntry>, now: u64) {
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        entries.retain(|entry| self.window.contains(entry.timestamp, now));
+
+        let per_player = match self.retention {
+            Retention::AllRuns => return,
+            Retention::BestPerPlayer => 1,
+            Retention::BestNPerPlayer(n) => n,
+        };
+
+        let mut kept: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+        entries.retain(|entry| {
+            let count = kept.entry(entry.name.clone()).or_insert(0);
+            *count += 1;
+            *count <= per_player
+        });
     }
 }
 
+/// Portable leaderboard file for moving scores between installs.
+///
+/// Signatures are tied to the install key of the machine that wrote them,
+/// so they are not exported. Imported scores are therefore stored unsigned
+/// and show up as unverified.
+///
+/// ```json
+/// {
+///   "format": "bumblebees-highscores",
+///   "version": 1,
+///   "scores": [{ "name": "Alice", "score": 1500, "timestamp": 1760000000 }]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighscoreExport {
+    /// Always `"bumblebees-highscores"`
+    pub format: String,
+    /// File format version
+    pub version: u32,
+    /// Scores, highest first
+    pub scores: Vec<ScoreRecord>,
+}
+
+/// How an imported leaderboard is combined with the local one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Add imported scores to the local list, skipping exact duplicates
+    Merge,
+    /// Discard local scores and keep only the imported ones
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    Replace,
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Imported scores that were new to the local leaderboard
+    pub added: usize,
+    /// Imported scores skipped because the same name and score already existed
+    pub duplicates: usize,
+    /// Size of the leaderboard after the import (capped at the save limit)
+    pub total: usize,
+}
+
 /// Cross-platform highscore persistence manager.
 ///
 /// Provides transparent storage of highscores using the appropriate
@@ -75,6 +284,14 @@ impl HighscoreEntry {
 /// This prevents demo scores from being saved as real highscores while
 /// still showing users example scores on their first play.
 ///
+/// # Online Leaderboard
+///
+/// With a [`LeaderboardClient`] attached via `with_leaderboard()`, every
+/// saved score is also queued for upload. The queue is persisted next to
+/// the local scores, so runs finished while offline are retried later:
+/// `start_sync()` uploads them in the background and `finish_sync()` takes
+/// the uploaded runs off the queue.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -91,8 +308,14 @@ impl HighscoreEntry {
 pub struct HighscoreManager {
     /// Storage key: filename (desktop) or localStorage key (WASM)
     storage_key: String,
+    /// Per-install HMAC key, loaded or generated on first use
+    install_key: OnceCell<Vec<u8>>,
     #[cfg(not(target_arch = "wasm32"))]
     cache: RefCell<Option<Vec<HighscoreEntry>>>,
+    /// Online leaderboard client; `None` keeps scores on this device
+    leaderboard: Option<LeaderboardClient>,
+    /// Which runs this leaderboard keeps
+    policy: LeaderboardPolicy,
 }
 
 impl HighscoreManager {
@@ -111,11 +334,39 @@ impl HighscoreManager {
     pub fn new(key: &str) -> Self {
         Self {
             storage_key: key.to_string(),
+            install_key: OnceCell::new(),
             #[cfg(not(target_arch = "wasm32"))]
             cache: RefCell::new(None),
+            leaderboard: None,
+            policy: LeaderboardPolicy::default(),
         }
     }
 
+    /// Set the retention policy of this leaderboard.
+    ///
+    /// The default keeps all runs of all time, up to the save limit.
+    #[must_use]
+    pub fn with_policy(mut self, policy: LeaderboardPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Attach an online leaderboard client.
+    ///
+    /// Scores saved afterwards are queued for upload in addition to being
+    /// stored locally.
+    #[must_use]
+    pub fn with_leaderboard(mut self, client: LeaderboardClient) -> Self {
+        self.leaderboard = Some(client);
+        self
+    }
+
+    /// Whether an online leaderboard client is attached.
+    #[must_use]
+    pub fn has_leaderboard(&self) -> bool {
+        self.leaderboard.is_some()
+    }
+
     /// Load highscores from storage, sorted by score (highest first)
     pub fn load_highscores(&self) -> Vec<HighscoreEntry> {
         #[cfg(target_arch = "wasm32")]
@@ -130,15 +381,76 @@ impl HighscoreManager {
     }
 
     /// Save a new highscore
-    pub fn save_highscore(&self, name: &str, score: u32) {
+    ///
+    /// # Returns
+    ///
+    /// The 1-based rank the score achieved on this leaderboard, or `None`
+    /// if the policy or the save limit left it off the board.
+    pub fn save_highscore(&self, name: &str, score: u32) -> Option<usize> {
+        self.save_highscore_with_stats(name, score, None)
+    }
+
+    /// Save a new highscore together with the statistics of the run.
+    ///
+    /// Same as `save_highscore()`; the stats are stored with the entry and
+    /// come back from `load_highscores()`.
+    pub fn save_highscore_with_stats(
+        &self,
+        name: &str,
+        score: u32,
+        stats: Option<&RunStats>,
+    ) -> Option<usize> {
         // Load existing scores WITHOUT demo data
         let mut entries = self.load_highscores_for_saving();
 
-        // Add new entry
-        entries.push(HighscoreEntry::new(name.to_string(), score));
+        // Add new entry, timestamped and signed with the install key
+        let mut entry = HighscoreEntry::new(name.to_string(), score);
+        entry.timestamp = unix_time_now();
+        entry.stats = stats.cloned();
+        let signature = self.sign(&entry);
+        entry.signature = Some(signature.clone());
+        let timestamp = entry.timestamp;
+        entries.push(entry);
+
+        let stored = self.store_entries(entries);
+        let rank = rank_of(&stored, &signature);
+
+        if self.leaderboard.is_some() {
+            let mut pending = self.pending_submissions();
+            pending.push(ScoreRecord {
+                timestamp: Some(timestamp),
+                ..ScoreRecord::new(name, score)
+            });
+            self.save_pending_submissions(&pending);
+        }
+
+        rank
+    }
+
+    /// Rank a score would achieve if saved now, without saving it.
+    ///
+    /// Applies the same policy and save limit as `save_highscore()`, so
+    /// `None` means the run would not make the board. Ties rank below
+    /// existing entries with the same score.
+    #[must_use]
+    pub fn qualifying_rank(&self, name: &str, score: u32) -> Option<usize> {
+        let mut entries = self.load_highscores_for_saving();
 
-        // Sort by score, highest first
-        entries.sort_by(|a, b| b.score.cmp(&a.score));
+        let mut candidate = HighscoreEntry::new(name.to_string(), score);
+        candidate.timestamp = unix_time_now();
+        // Not valid hex, so it can't collide with a real signature
+        candidate.signature = Some(RANK_PREVIEW_MARKER.to_string());
+        entries.push(candidate);
+
+        self.policy.apply(&mut entries, unix_time_now());
+        entries.truncate(MAX_SAVED_SCORES);
+        rank_of(&entries, RANK_PREVIEW_MARKER)
+    }
+
+    /// Sort entries (highest first), apply the leaderboard policy and the
+    /// `MAX_SAVED_SCORES` cap, and persist them. Returns the stored entries.
+    fn store_entries(&self, mut entries: Vec<HighscoreEntry>) -> Vec<HighscoreEntry> {
+        self.policy.apply(&mut entries, unix_time_now());
         entries.truncate(MAX_SAVED_SCORES);
 
         #[cfg(target_arch = "wasm32")]
@@ -151,6 +463,270 @@ impl HighscoreManager {
             self.update_cache(&entries);
             self.save_to_file(&entries);
         }
+
+        entries
+    }
+
+    /// Serialize the saved leaderboard (never demo scores) as a portable
+    /// [`HighscoreExport`] JSON document.
+    pub fn export_json(&self) -> String {
+        let export = HighscoreExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            scores: self
+                .load_highscores_for_saving()
+                .iter()
+                .map(|entry| ScoreRecord {
+                    timestamp: (entry.timestamp != 0).then_some(entry.timestamp),
+                    ..ScoreRecord::new(&entry.name, entry.score)
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&export).unwrap_or_default()
+    }
+
+    /// Import a [`HighscoreExport`] JSON document.
+    ///
+    /// Uses the same sort order and `MAX_SAVED_SCORES` cap as
+    /// `save_highscore()`. In [`ImportMode::Merge`], an imported score is
+    /// skipped when an entry with the same name and score already exists,
+    /// so importing the same file twice is harmless. Imported scores carry
+    /// no valid signature and are stored as unverified.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the document is too large,
+    /// isn't valid JSON, has the wrong format/version, or contains names
+    /// longer than `MAX_NAME_LEN` characters.
+    pub fn import_json(&self, json: &str, mode: ImportMode) -> Result<ImportSummary, String> {
+        if json.len() > MAX_IMPORT_SIZE {
+            return Err("import file is too large".to_string());
+        }
+
+        let export: HighscoreExport =
+            serde_json::from_str(json).map_err(|err| format!("invalid import file: {}", err))?;
+
+        if export.format != EXPORT_FORMAT {
+            return Err(format!("unknown import format '{}'", export.format));
+        }
+        if export.version > EXPORT_VERSION {
+            return Err(format!(
+                "import file version {} is newer than supported version {}",
+                export.version, EXPORT_VERSION
+            ));
+        }
+        if export.scores.len() > MAX_HIGHSCORE_ENTRIES {
+            return Err("import file has too many scores".to_string());
+        }
+        if export
+            .scores
+            .iter()
+            .any(|record| record.name.chars().count() > MAX_NAME_LEN)
+        {
+            return Err(format!(
+                "import file contains names longer than {} characters",
+                MAX_NAME_LEN
+            ));
+        }
+
+        let mut entries = match mode {
+            ImportMode::Merge => self.load_highscores_for_saving(),
+            ImportMode::Replace => Vec::new(),
+        };
+
+        let mut added = 0;
+        let mut duplicates = 0;
+        for record in export.scores {
+            let exists = entries
+                .iter()
+                .any(|entry| entry.name == record.name && entry.score == record.score);
+            if exists {
+                duplicates += 1;
+                continue;
+            }
+
+            let mut entry = HighscoreEntry::new(record.name, record.score);
+            entry.timestamp = record.timestamp.unwrap_or(0);
+            entry.verified = false;
+            entries.push(entry);
+            added += 1;
+        }
+
+        let total = self.store_entries(entries).len();
+        log::info!(
+            "Imported highscores ({:?}): {} added, {} duplicates, {} total",
+            mode,
+            added,
+            duplicates,
+            total
+        );
+
+        Ok(ImportSummary {
+            added,
+            duplicates,
+            total,
+        })
+    }
+
+    /// Write the leaderboard export to a file. Returns the number of scores written.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the I/O error if the file can't be written.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_to_file(&self, path: &std::path::Path) -> Result<usize, String> {
+        let json = self.export_json();
+        std::fs::write(path, &json)
+            .map_err(|err| format!("cannot write {}: {}", path.display(), err))?;
+        Ok(self.load_highscores_for_saving().len())
+    }
+
+    /// Import a leaderboard export from a file. See `import_json()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the problem if the file can't be read or
+    /// isn't a valid export.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_from_file(
+        &self,
+        path: &std::path::Path,
+        mode: ImportMode,
+    ) -> Result<ImportSummary, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+        self.import_json(&json, mode)
+    }
+
+    /// Offer the leaderboard export to the browser as a file download via
+    /// the JS bridge (`js_download_file`, see game.html).
+    #[cfg(target_arch = "wasm32")]
+    pub fn export_to_download(&self, filename: &str) {
+        use std::ffi::CString;
+        use std::os::raw::c_char;
+
+        extern "C" {
+            fn js_download_file(name: *const c_char, contents: *const c_char);
+        }
+
+        if let (Ok(name), Ok(contents)) = (CString::new(filename), CString::new(self.export_json()))
+        {
+            unsafe {
+                js_download_file(name.as_ptr(), contents.as_ptr());
+            }
+        }
+    }
+
+    /// Ask the browser to let the player pick an export file to merge
+    /// (`js_pick_import_file`, see game.html). The chosen file's contents
+    /// are placed in localStorage and picked up by `take_browser_import()`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn request_browser_import(&self) {
+        use std::ffi::CString;
+        use std::os::raw::c_char;
+
+        extern "C" {
+            fn js_pick_import_file(key: *const c_char);
+        }
+
+        if let Ok(key) = CString::new(format!("{}_import", self.storage_key)) {
+            unsafe {
+                js_pick_import_file(key.as_ptr());
+            }
+        }
+    }
+
+    /// Take an import file delivered by the browser, if one is waiting.
+    #[cfg(target_arch = "wasm32")]
+    pub fn take_browser_import(&self) -> Option<String> {
+        let key = format!("{}_import", self.storage_key);
+        let json = localstorage_get(&key).filter(|json| !json.is_empty())?;
+        localstorage_set(&key, "");
+        Some(json)
+    }
+
+    /// Upload queued runs to the online leaderboard, oldest first, then
+    /// fetch the global top `n` scores, all in the background. Pass the
+    /// outcome of the job to `finish_sync()`.
+    ///
+    /// The upload stops when the server is offline or failing and the
+    /// remaining runs stay queued for the next sync. Runs the server
+    /// rejects for good (e.g. an invalid name) are dropped so they don't
+    /// hold up the rest.
+    ///
+    /// Returns `None` if no leaderboard is attached.
+    #[must_use]
+    pub fn start_sync(&self, n: usize) -> Option<SyncJob> {
+        let client = self.leaderboard.clone()?;
+        Some(SyncJob::start(client, self.pending_submissions(), n))
+    }
+
+    /// Take the runs a finished sync job settled off the upload queue.
+    ///
+    /// # Returns
+    ///
+    /// The number of runs still waiting for upload.
+    pub fn finish_sync(&self, outcome: &SyncOutcome) -> usize {
+        self.settle_submissions(&outcome.settled)
+    }
+
+    /// Remove `settled` from the upload queue. Runs queued since the
+    /// upload started stay.
+    fn settle_submissions(&self, settled: &[ScoreRecord]) -> usize {
+        let mut pending = self.pending_submissions();
+        if settled.is_empty() {
+            return pending.len();
+        }
+
+        // Compare whole records, timestamp included, so two runs with the
+        // same name and score are settled one at a time
+        for record in settled {
+            if let Some(index) = pending.iter().position(|queued| queued == record) {
+                pending.remove(index);
+            }
+        }
+        self.save_pending_submissions(&pending);
+        pending.len()
+    }
+
+    /// Runs saved locally that have not reached the online leaderboard yet.
+    pub fn pending_submissions(&self) -> Vec<ScoreRecord> {
+        #[cfg(target_arch = "wasm32")]
+        let json = localstorage_get(&format!("{}_pending", self.storage_key));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let json = std::fs::read_to_string(
+            std::path::Path::new(&self.storage_key).with_extension("pending"),
+        )
+        .ok();
+
+        json.and_then(|json| serde_json::from_str::<Vec<ScoreRecord>>(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the upload queue, keeping only the newest
+    /// `MAX_PENDING_SUBMISSIONS` runs. An empty queue removes the stored copy.
+    fn save_pending_submissions(&self, pending: &[ScoreRecord]) {
+        let start = pending.len().saturating_sub(MAX_PENDING_SUBMISSIONS);
+        let Ok(json) = serde_json::to_string(&pending[start..]) else {
+            return;
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            localstorage_set(&format!("{}_pending", self.storage_key), &json);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = std::path::Path::new(&self.storage_key).with_extension("pending");
+            if pending.is_empty() {
+                let _ = std::fs::remove_file(path);
+            } else if std::fs::write(path, json).is_err() {
+                log::warn!("Failed to persist pending leaderboard submissions");
+            }
+        }
     }
 
     /// Load highscores for saving operations (excludes demo data).
@@ -188,7 +764,8 @@ impl HighscoreManager {
             return cached.clone();
         }
 
-        let scores = self.load_from_file();
+        let mut scores = self.load_from_file();
+        self.verify_entries(&mut scores);
         self.update_cache(&scores);
         scores
     }
@@ -198,26 +775,127 @@ impl HighscoreManager {
         *self.cache.borrow_mut() = Some(entries.to_vec());
     }
 
-    /// Get top N highscores
+    /// Get top N highscores allowed by the leaderboard policy
     pub fn get_top_scores(&self, n: usize) -> Vec<HighscoreEntry> {
         let mut scores = self.load_highscores();
+        self.policy.apply(&mut scores, unix_time_now());
         scores.truncate(n);
         scores
     }
 
+    /// Compute the hex-encoded HMAC-SHA256 signature for an entry.
+    fn sign(&self, entry: &HighscoreEntry) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.install_key())
+            .expect("HMAC accepts keys of any length");
+        mac.update(&entry.signed_message());
+        encode_hex(&mac.finalize().into_bytes())
+    }
+
+    /// Check each entry's signature against the install key and set its
+    /// `verified` flag. Entries without a signature are never verified.
+    fn verify_entries(&self, entries: &mut [HighscoreEntry]) {
+        for entry in entries.iter_mut() {
+            entry.verified = match entry.signature.as_deref().and_then(decode_hex) {
+                Some(signature) => {
+                    let mut mac = HmacSha256::new_from_slice(self.install_key())
+                        .expect("HMAC accepts keys of any length");
+                    mac.update(&entry.signed_message());
+                    mac.verify_slice(&signature).is_ok()
+                }
+                None => false,
+            };
+
+            if !entry.verified {
+                log::warn!(
+                    "Highscore entry failed verification: {} - {}",
+                    entry.name,
+                    entry.score
+                );
+            }
+        }
+    }
+
+    /// Per-install signing key, loaded from storage or generated on first use.
+    fn install_key(&self) -> &[u8] {
+        self.install_key
+            .get_or_init(|| self.load_or_create_install_key())
+    }
+
+    /// Load the install key from disk, creating it if missing or malformed.
+    ///
+    /// The key lives next to the highscore file with a `.key` extension
+    /// (e.g. `highscores.key`). Losing the key marks every previously
+    /// saved entry as unverified.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_or_create_install_key(&self) -> Vec<u8> {
+        use std::fs;
+
+        let key_path = std::path::Path::new(&self.storage_key).with_extension("key");
+
+        if let Ok(contents) = fs::read_to_string(&key_path) {
+            if let Some(key) = decode_hex(contents.trim()) {
+                if key.len() == INSTALL_KEY_LEN {
+                    return key;
+                }
+            }
+        }
+
+        log::info!("Generating new highscore install key");
+        let key = rand::random::<[u8; INSTALL_KEY_LEN]>().to_vec();
+        if fs::write(&key_path, encode_hex(&key)).is_err() {
+            log::warn!("Failed to persist highscore install key");
+        }
+        key
+    }
+
+    /// Load the install key from localStorage, creating it if missing or malformed.
+    ///
+    /// Stored under `<storage_key>_key`.
+    #[cfg(target_arch = "wasm32")]
+    fn load_or_create_install_key(&self) -> Vec<u8> {
+        use macroquad::rand::RandGenerator;
+
+        let key_name = format!("{}_key", self.storage_key);
+
+        if let Some(contents) = localstorage_get(&key_name) {
+            if let Some(key) = decode_hex(contents.trim()) {
+                if key.len() == INSTALL_KEY_LEN {
+                    return key;
+                }
+            }
+        }
+
+        // The global macroquad RNG is unseeded, so seed a private generator
+        // from the wall clock instead of touching gameplay randomness.
+        let rng = RandGenerator::new();
+        rng.srand(macroquad::miniquad::date::now().to_bits());
+        let key: Vec<u8> = (0..INSTALL_KEY_LEN)
+            .map(|_| rng.gen_range(0u32, 256) as u8)
+            .collect();
+        localstorage_set(&key_name, &encode_hex(&key));
+        key
+    }
+
     /// Load highscores from desktop file storage (CSV format).
     ///
-    /// Reads highscores from a CSV file with format: `name, score`
+    /// Reads highscores from a CSV file with format: `name, score, signature, timestamp, stats`
     /// Returns an empty vector if the file doesn't exist or cannot be read.
     ///
     /// # File Format
     ///
     /// ```text
-    /// PLAYER1, 5000
-    /// PLAYER2, 4500
-    /// PLAYER3, 4000
+    /// PLAYER1, 5000, 3f1c...e9, 1760000000, {"shots_fired":120,...}
+    /// PLAYER2, 4500, 81ab...04, 1760000000
+    /// PLAYER3, 4000, 81ab...04
+    /// PLAYER4, 3500
     /// ```
     ///
+    /// The signature, timestamp and stats columns are optional so files
+    /// written before they were introduced still load. Entries without a
+    /// signature are flagged as unverified; entries without a timestamp only
+    /// show on all-time leaderboards. The stats column is JSON and always
+    /// comes last, so its commas don't split it.
+    ///
     /// # Error Handling
     ///
     /// Silently ignores:
@@ -249,21 +927,37 @@ impl HighscoreManager {
         let mut entries = Vec::new();
 
         for line in reader.lines().map_while(Result::ok) {
-            if let Some((name, score_str)) = line.split_once(',') {
+            let mut fields = line.splitn(5, ',');
+            if let (Some(name), Some(score_str)) = (fields.next(), fields.next()) {
                 if let Ok(score) = score_str.trim().parse::<u32>() {
-                    entries.push(HighscoreEntry::new(name.trim().to_string(), score));
+                    let mut entry = HighscoreEntry::new(name.trim().to_string(), score);
+                    entry.signature = fields
+                        .next()
+                        .map(|signature| signature.trim().to_string())
+                        .filter(|signature| !signature.is_empty());
+                    entry.timestamp = fields
+                        .next()
+                        .and_then(|timestamp| timestamp.trim().parse().ok())
+                        .unwrap_or(0);
+                    // Last column holds the run stats as JSON (may contain commas)
+                    entry.stats = fields
+                        .next()
+                        .and_then(|stats| serde_json::from_str(stats.trim()).ok());
+                    entries.push(entry);
                 }
             }
         }
 
-        entries.sort_by(|a, b| b.score.cmp(&a.score));
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
         entries
     }
 
     /// Save highscores to desktop file storage (CSV format).
     ///
-    /// Writes all highscore entries to a CSV file with format: `name, score`
-    /// Creates the file if it doesn't exist, overwrites if it does.
+    /// Writes all highscore entries to a CSV file with format: `name, score, signature, timestamp, stats`
+    /// Creates the file if it doesn't exist, overwrites if it does. Missing
+    /// trailing columns are omitted, a missing signature before a later
+    /// column is left empty.
     ///
     /// # Arguments
     ///
@@ -285,7 +979,27 @@ impl HighscoreManager {
             .open(&self.storage_key)
         {
             for entry in entries {
-                let _ = writeln!(file, "{}, {}", entry.name, entry.score);
+                let signature = entry.signature.as_deref().unwrap_or("");
+                let stats = entry
+                    .stats
+                    .as_ref()
+                    .and_then(|stats| serde_json::to_string(stats).ok());
+                let _ = match (signature, entry.timestamp, stats) {
+                    (signature, timestamp, Some(stats)) => writeln!(
+                        file,
+                        "{}, {}, {}, {}, {}",
+                        entry.name, entry.score, signature, timestamp, stats
+                    ),
+                    ("", 0, None) => writeln!(file, "{}, {}", entry.name, entry.score),
+                    (signature, 0, None) => {
+                        writeln!(file, "{}, {}, {}", entry.name, entry.score, signature)
+                    }
+                    (signature, timestamp, None) => writeln!(
+                        file,
+                        "{}, {}, {}, {}",
+                        entry.name, entry.score, signature, timestamp
+                    ),
+                };
             }
         }
     }
@@ -335,32 +1049,15 @@ impl HighscoreManager {
 
     /// Load highscores directly from WASM localStorage without demo fallback.
     ///
-    /// This is the raw FFI bridge to JavaScript localStorage. It deserializes
-    /// JSON data from localStorage into Rust structs.
-    ///
-    /// # FFI Bridge
-    ///
-    /// Calls JavaScript functions via `extern "C"`:
-    /// - `js_localstorage_get(key)`: Retrieves JSON string from localStorage
-    /// - `js_free_string(ptr)`: Frees JavaScript-allocated C string
-    ///
-    /// These functions must be provided by the WASM host (see game.html).
-    ///
-    /// # Safety
-    ///
-    /// Uses `unsafe` for FFI calls with proper error handling:
-    /// - Validates CString creation
-    /// - Checks for null pointers
-    /// - Validates UTF-8 encoding
-    /// - Frees JavaScript-allocated memory
-    /// - Returns empty vector on any error
+    /// Deserializes the JSON stored under the manager's key and checks each
+    /// entry's signature against the install key.
     ///
     /// # Returns
     ///
     /// A vector of real highscore entries from localStorage, or empty if:
     /// - localStorage is empty (first launch)
     /// - Key doesn't exist
-    /// - Data is malformed JSON
+    /// - Data is malformed JSON or has too many entries
     /// - FFI bridge fails
     ///
     /// # Note
@@ -369,77 +1066,31 @@ impl HighscoreManager {
     /// Testing is performed manually in browser builds.
     #[cfg(target_arch = "wasm32")]
     fn load_from_localstorage_raw(&self) -> Vec<HighscoreEntry> {
-        use std::ffi::CString;
-        use std::os::raw::c_char;
-
-        extern "C" {
-            fn js_localstorage_get(key: *const c_char) -> *mut c_char;
-            fn js_free_string(ptr: *mut c_char);
-        }
-
-        unsafe {
-            let key = match CString::new(self.storage_key.as_str()) {
-                Ok(k) => k,
-                Err(_) => return Vec::new(),
-            };
-
-            let value_ptr = js_localstorage_get(key.as_ptr());
-            if value_ptr.is_null() {
-                return Vec::new();
-            }
-
-            let c_str = std::ffi::CStr::from_ptr(value_ptr);
-            let json_str = match c_str.to_str() {
-                Ok(s) if s.len() <= MAX_LOCALSTORAGE_SIZE => s,
-                Ok(_) => {
-                    // Data too large, reject to prevent memory exhaustion
-                    js_free_string(value_ptr);
-                    return Vec::new();
-                }
-                Err(_) => {
-                    js_free_string(value_ptr);
-                    return Vec::new();
-                }
-            };
+        let json_str = match localstorage_get(&self.storage_key) {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
 
-            let entries = match serde_json::from_str::<Vec<HighscoreEntry>>(json_str) {
-                Ok(e) if e.len() <= MAX_HIGHSCORE_ENTRIES => e,
-                Ok(_) => {
-                    // Too many entries, reject to prevent DoS
-                    js_free_string(value_ptr);
-                    return Vec::new();
-                }
-                Err(_) => Vec::new(),
-            };
+        let mut entries = match serde_json::from_str::<Vec<HighscoreEntry>>(&json_str) {
+            Ok(e) if e.len() <= MAX_HIGHSCORE_ENTRIES => e,
+            // Too many entries, reject to prevent DoS
+            Ok(_) => return Vec::new(),
+            Err(_) => return Vec::new(),
+        };
 
-            js_free_string(value_ptr);
-            entries
-        }
+        self.verify_entries(&mut entries);
+        entries
     }
 
     /// Save highscores to WASM localStorage via FFI bridge.
     ///
-    /// Serializes highscore entries to JSON and stores them in browser
-    /// localStorage via JavaScript FFI.
-    ///
-    /// # FFI Bridge
-    ///
-    /// Calls JavaScript function via `extern "C"`:
-    /// - `js_localstorage_set(key, value)`: Stores JSON string in localStorage
-    ///
-    /// This function must be provided by the WASM host (see game.html).
+    /// Serializes highscore entries (including signatures) to JSON and
+    /// stores them in browser localStorage.
     ///
     /// # Arguments
     ///
     /// * `entries` - Slice of highscore entries to save (assumed pre-sorted)
     ///
-    /// # Safety
-    ///
-    /// Uses `unsafe` for FFI calls with proper error handling:
-    /// - Validates CString creation for key and value
-    /// - Returns early on any serialization/FFI error
-    /// - JavaScript function handles localStorage quota/errors
-    ///
     /// # Error Handling
     ///
     /// Silently fails on errors to ensure game continues running:
@@ -453,35 +1104,135 @@ impl HighscoreManager {
     /// Testing is performed manually in browser builds.
     #[cfg(target_arch = "wasm32")]
     fn save_to_localstorage(&self, entries: &[HighscoreEntry]) {
-        use std::ffi::CString;
-        use std::os::raw::c_char;
+        if let Ok(json_str) = serde_json::to_string(entries) {
+            localstorage_set(&self.storage_key, &json_str);
+        }
+    }
+}
 
-        extern "C" {
-            fn js_localstorage_set(key: *const c_char, value: *const c_char);
+/// Read a string value from browser localStorage via the FFI bridge.
+///
+/// # FFI Bridge
+///
+/// Calls JavaScript functions via `extern "C"`:
+/// - `js_localstorage_get(key)`: Retrieves string from localStorage
+/// - `js_free_string(ptr)`: Frees JavaScript-allocated C string
+///
+/// These functions must be provided by the WASM host (see game.html).
+///
+/// # Safety
+///
+/// Uses `unsafe` for FFI calls with proper error handling:
+/// - Validates CString creation
+/// - Checks for null pointers
+/// - Validates UTF-8 encoding and size
+/// - Frees JavaScript-allocated memory
+/// - Returns `None` on any error
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn localstorage_get(key: &str) -> Option<String> {
+    use std::ffi::CString;
+    use std::os::raw::c_char;
+
+    extern "C" {
+        fn js_localstorage_get(key: *const c_char) -> *mut c_char;
+        fn js_free_string(ptr: *mut c_char);
+    }
+
+    let key = CString::new(key).ok()?;
+
+    unsafe {
+        let value_ptr = js_localstorage_get(key.as_ptr());
+        if value_ptr.is_null() {
+            return None;
         }
 
-        if let Ok(json_str) = serde_json::to_string(entries) { unsafe {
-            let key = match CString::new(self.storage_key.as_str()) {
-                Ok(k) => k,
-                Err(_) => return,
-            };
+        let c_str = std::ffi::CStr::from_ptr(value_ptr);
+        let value = match c_str.to_str() {
+            Ok(s) if s.len() <= MAX_LOCALSTORAGE_SIZE => Some(s.to_string()),
+            // Data too large or not UTF-8, reject to prevent memory exhaustion
+            _ => None,
+        };
 
-            let value = match CString::new(json_str.as_str()) {
-                Ok(v) => v,
-                Err(_) => return,
-            };
+        js_free_string(value_ptr);
+        value
+    }
+}
 
+/// Write a string value to browser localStorage via the FFI bridge.
+///
+/// Calls `js_localstorage_set(key, value)`, which must be provided by the
+/// WASM host (see game.html). Silently does nothing if either string
+/// contains an interior NUL byte.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn localstorage_set(key: &str, value: &str) {
+    use std::ffi::CString;
+    use std::os::raw::c_char;
+
+    extern "C" {
+        fn js_localstorage_set(key: *const c_char, value: *const c_char);
+    }
+
+    if let (Ok(key), Ok(value)) = (CString::new(key), CString::new(value)) {
+        unsafe {
             js_localstorage_set(key.as_ptr(), value.as_ptr());
-        } }
+        }
     }
 }
 
+/// 1-based position of the entry carrying `signature`, if present.
+fn rank_of(entries: &[HighscoreEntry], signature: &str) -> Option<usize> {
+    entries
+        .iter()
+        .position(|entry| entry.signature.as_deref() == Some(signature))
+        .map(|index| index + 1)
+}
+
+/// Current wall-clock time in Unix seconds (0 if the clock is unavailable).
+pub(crate) fn unix_time_now() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        macroquad::miniquad::date::now() as u64
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+}
+
+/// Encode bytes as a lowercase hex string.
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decode a hex string into bytes. Returns `None` on odd length or
+/// non-hex characters.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
 mod tests {
     use super::*;
     use std::fs;
 
+    /// Remove a test highscore file together with its install key and upload queue.
+    fn remove_test_files(test_file: &str) {
+        let _ = fs::remove_file(test_file);
+        let _ = fs::remove_file(std::path::Path::new(test_file).with_extension("key"));
+        let _ = fs::remove_file(std::path::Path::new(test_file).with_extension("pending"));
+    }
+
     #[test]
     fn test_highscore_entry_creation() {
         let entry = HighscoreEntry::new("Alice".to_string(), 1000);
@@ -495,7 +1246,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save some scores
         manager.save_highscore("Alice", 1000);
@@ -511,7 +1262,7 @@ mod tests {
         assert_eq!(scores[1].score, 1000);
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -520,7 +1271,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save multiple scores
         for i in 1..=15 {
@@ -534,7 +1285,7 @@ mod tests {
         assert_eq!(top_10[9].score, 600);
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -543,7 +1294,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save only 3 scores
         manager.save_highscore("Alice", 100);
@@ -558,7 +1309,7 @@ mod tests {
         assert_eq!(top_scores[2].score, 100); // Alice
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -567,7 +1318,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save multiple scores for same player
         manager.save_highscore("Alice", 100);
@@ -579,7 +1330,7 @@ mod tests {
         assert_eq!(scores.len(), 3);
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -588,7 +1339,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save a score of zero
         manager.save_highscore("NoPoints", 0);
@@ -601,7 +1352,7 @@ mod tests {
         assert_eq!(scores[1].name, "NoPoints");
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -610,7 +1361,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save a very large score (near u32::MAX)
         manager.save_highscore("MaxScore", u32::MAX);
@@ -622,7 +1373,7 @@ mod tests {
         assert_eq!(scores[0].name, "MaxScore");
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -631,7 +1382,7 @@ mod tests {
         let manager = HighscoreManager::new(test_file);
 
         // Clean up before test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
 
         // Save a score with empty name (game shouldn't allow this, but test robustness)
         manager.save_highscore("", 500);
@@ -642,7 +1393,7 @@ mod tests {
         assert_eq!(scores[1].name, ""); // Empty name should be preserved
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -679,7 +1430,7 @@ mod tests {
         assert_eq!(scores[2].score, 300);
 
         // Clean up after test
-        let _ = fs::remove_file(test_file);
+        remove_test_files(test_file);
     }
 
     #[test]
@@ -697,4 +1448,605 @@ mod tests {
         // Verify it was created properly (internal check)
         assert_eq!(manager.storage_key, "test.txt");
     }
+
+    #[test]
+    fn test_saved_scores_are_signed_and_verified() {
+        let test_file = "test_signed_scores.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+
+        // A fresh manager has to read the file and key back from disk
+        let reloaded = HighscoreManager::new(test_file);
+        let scores = reloaded.load_highscores();
+        assert_eq!(scores.len(), 1);
+        assert!(scores[0].signature.is_some());
+        assert!(scores[0].verified);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_tampered_score_is_flagged() {
+        let test_file = "test_tampered_scores.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+        manager.save_highscore("Bob", 500);
+
+        // Bump Bob's score by hand, keeping his original signature
+        let contents = fs::read_to_string(test_file).unwrap();
+        fs::write(test_file, contents.replace("Bob, 500,", "Bob, 4294967295,")).unwrap();
+
+        let reloaded = HighscoreManager::new(test_file);
+        let scores = reloaded.load_highscores();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].name, "Bob");
+        assert!(!scores[0].verified);
+        assert_eq!(scores[1].name, "Alice");
+        assert!(scores[1].verified);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_unsigned_entries_are_flagged_and_stay_flagged() {
+        let test_file = "test_unsigned_scores.txt";
+        remove_test_files(test_file);
+
+        // Legacy file written before signing existed
+        fs::write(test_file, "OldPlayer, 2000\n").unwrap();
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("NewPlayer", 100);
+
+        let reloaded = HighscoreManager::new(test_file);
+        let scores = reloaded.load_highscores();
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].name, "OldPlayer");
+        assert!(
+            !scores[0].verified,
+            "Saving must not re-sign unverified entries"
+        );
+        assert!(scores[1].verified);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_entries_fail_verification_with_new_key() {
+        let test_file = "test_lost_key_scores.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+
+        // Losing the install key invalidates every signature
+        let _ = fs::remove_file(std::path::Path::new(test_file).with_extension("key"));
+
+        let reloaded = HighscoreManager::new(test_file);
+        let scores = reloaded.load_highscores();
+        assert_eq!(scores.len(), 1);
+        assert!(!scores[0].verified);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = [0x00, 0x7f, 0xff, 0x10];
+        let hex = encode_hex(&bytes);
+        assert_eq!(hex, "007fff10");
+        assert_eq!(decode_hex(&hex), Some(bytes.to_vec()));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+
+    /// Run a leaderboard sync to the end, returning the runs still queued
+    fn sync_now(manager: &HighscoreManager) -> usize {
+        let job = manager.start_sync(5).expect("leaderboard attached");
+        loop {
+            if let Some(outcome) = job.poll() {
+                return manager.finish_sync(&outcome);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_offline_submissions_are_queued() {
+        let test_file = "test_offline_queue.txt";
+        remove_test_files(test_file);
+
+        // Nothing listens on port 1, so every upload fails
+        let manager = HighscoreManager::new(test_file)
+            .with_leaderboard(LeaderboardClient::new("127.0.0.1:1"));
+        manager.save_highscore("Alice", 1000);
+        manager.save_highscore("Bob", 500);
+
+        // Runs are still saved locally and queued in order, with the time
+        // they were saved
+        let saved = manager.load_highscores();
+        assert_eq!(saved.len(), 2);
+        let pending = manager.pending_submissions();
+        assert_eq!(
+            pending,
+            vec![
+                ScoreRecord {
+                    timestamp: Some(saved[0].timestamp),
+                    ..ScoreRecord::new("Alice", 1000)
+                },
+                ScoreRecord {
+                    timestamp: Some(saved[1].timestamp),
+                    ..ScoreRecord::new("Bob", 500)
+                }
+            ]
+        );
+        assert!(pending.iter().all(|record| record.timestamp > Some(0)));
+        assert_eq!(sync_now(&manager), 2);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_sync_uploads_queued_submissions() {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+
+        let test_file = "test_sync_queue.txt";
+        remove_test_files(test_file);
+
+        // Queue a run while offline
+        let offline = HighscoreManager::new(test_file)
+            .with_leaderboard(LeaderboardClient::new("127.0.0.1:1"));
+        offline.save_highscore("Alice", 1000);
+        assert_eq!(offline.pending_submissions().len(), 1);
+
+        // Minimal one-shot server that accepts the submission
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("Content-Length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 10\r\n\r\n{\"rank\":1}")
+                .unwrap();
+            (request_line, String::from_utf8(body).unwrap())
+        });
+
+        let online =
+            HighscoreManager::new(test_file).with_leaderboard(LeaderboardClient::new(&addr));
+        assert_eq!(sync_now(&online), 0);
+        assert!(online.pending_submissions().is_empty());
+
+        let (request_line, body) = server.join().unwrap();
+        assert!(request_line.starts_with("POST /scores "));
+        assert_eq!(
+            serde_json::from_str::<ScoreRecord>(&body).unwrap(),
+            ScoreRecord {
+                timestamp: Some(online.load_highscores()[0].timestamp),
+                ..ScoreRecord::new("Alice", 1000)
+            }
+        );
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_settle_keeps_runs_with_same_name_and_score_apart() {
+        let test_file = "test_settle_same_score.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file)
+            .with_leaderboard(LeaderboardClient::new("127.0.0.1:1"));
+        let run = |timestamp| ScoreRecord {
+            timestamp: Some(timestamp),
+            ..ScoreRecord::new("Alice", 1000)
+        };
+        manager.save_pending_submissions(&[run(100), run(200)]);
+
+        // Only the run that was actually uploaded leaves the queue
+        assert_eq!(manager.settle_submissions(&[run(200)]), 1);
+        assert_eq!(manager.pending_submissions(), vec![run(100)]);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_local_only_manager_never_queues() {
+        let test_file = "test_local_only.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+        assert!(!manager.has_leaderboard());
+        assert!(manager.pending_submissions().is_empty());
+        assert!(manager.start_sync(5).is_none());
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let source_file = "test_export_source.txt";
+        let target_file = "test_export_target.txt";
+        remove_test_files(source_file);
+        remove_test_files(target_file);
+
+        let source = HighscoreManager::new(source_file);
+        source.save_highscore("Alice", 1000);
+        source.save_highscore("Bob", 2000);
+
+        let json = source.export_json();
+        let export: HighscoreExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(export.format, "bumblebees-highscores");
+        let exported: Vec<_> = export
+            .scores
+            .iter()
+            .map(|record| (record.name.as_str(), record.score))
+            .collect();
+        assert_eq!(exported, [("Bob", 2000), ("Alice", 1000)]);
+        assert!(export
+            .scores
+            .iter()
+            .all(|record| record.timestamp.is_some()));
+
+        let target = HighscoreManager::new(target_file);
+        let summary = target.import_json(&json, ImportMode::Replace).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 2,
+                duplicates: 0,
+                total: 2
+            }
+        );
+
+        // Imported scores keep their timestamp but can't carry this install's signature
+        let scores = target.get_top_scores(10);
+        assert_eq!(scores[0].name, "Bob");
+        assert_eq!(scores[0].timestamp, export.scores[0].timestamp.unwrap());
+        assert!(scores.iter().all(|entry| !entry.verified));
+
+        remove_test_files(source_file);
+        remove_test_files(target_file);
+    }
+
+    #[test]
+    fn test_merge_skips_duplicates_and_respects_cap() {
+        let test_file = "test_import_merge.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+
+        let mut scores = vec![ScoreRecord::new("Alice", 1000)];
+        scores.extend((0..60).map(|i| ScoreRecord::new(&format!("P{}", i), i * 10)));
+        let json = serde_json::to_string(&HighscoreExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            scores,
+        })
+        .unwrap();
+
+        let summary = manager.import_json(&json, ImportMode::Merge).unwrap();
+        assert_eq!(summary.added, 60);
+        assert_eq!(summary.duplicates, 1);
+        assert_eq!(summary.total, MAX_SAVED_SCORES);
+
+        // Local signed entry survives the merge and stays verified
+        let top = manager.get_top_scores(1);
+        assert_eq!(top[0].name, "Alice");
+        assert!(top[0].verified);
+
+        // Importing the same file again only re-offers the entries that fell
+        // off the capped list; the kept ones are recognized as duplicates
+        let again = manager.import_json(&json, ImportMode::Merge).unwrap();
+        assert_eq!(again.duplicates, MAX_SAVED_SCORES);
+        assert_eq!(again.total, MAX_SAVED_SCORES);
+        assert_eq!(manager.get_top_scores(1)[0].name, "Alice");
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_files() {
+        let test_file = "test_import_invalid.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+
+        assert!(manager.import_json("not json", ImportMode::Merge).is_err());
+        assert!(manager
+            .import_json(
+                r#"{"format":"other","version":1,"scores":[]}"#,
+                ImportMode::Replace
+            )
+            .is_err());
+        assert!(manager
+            .import_json(
+                r#"{"format":"bumblebees-highscores","version":99,"scores":[]}"#,
+                ImportMode::Replace
+            )
+            .is_err());
+        assert!(manager
+            .import_json(
+                r#"{"format":"bumblebees-highscores","version":1,"scores":[{"name":"ABCDEFGHIJKLMNOPQRSTUVWXYZ","score":1}]}"#,
+                ImportMode::Replace
+            )
+            .is_err());
+
+        let too_long = format!(
+            r#"{{"format":"bumblebees-highscores","version":1,"scores":[{{"name":"{}","score":1}}]}}"#,
+            "ü".repeat(MAX_NAME_LEN + 1)
+        );
+        assert!(manager.import_json(&too_long, ImportMode::Replace).is_err());
+
+        // Failed imports leave the local leaderboard untouched
+        assert_eq!(manager.get_top_scores(10).len(), 1);
+
+        // The limit counts characters, so a long non-ASCII name still fits
+        let longest = format!(
+            r#"{{"format":"bumblebees-highscores","version":1,"scores":[{{"name":"{}","score":1}}]}}"#,
+            "ü".repeat(MAX_NAME_LEN)
+        );
+        assert!(manager.import_json(&longest, ImportMode::Merge).is_ok());
+
+        remove_test_files(test_file);
+    }
+
+    /// Monday 2025-01-06 00:00:00 UTC
+    const MONDAY: u64 = 1_736_121_600;
+
+    fn timestamped(name: &str, score: u32, timestamp: u64) -> HighscoreEntry {
+        let mut entry = HighscoreEntry::new(name.to_string(), score);
+        entry.timestamp = timestamp;
+        entry
+    }
+
+    fn names(entries: &[HighscoreEntry]) -> Vec<(&str, u32)> {
+        entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.score))
+            .collect()
+    }
+
+    #[test]
+    fn test_retention_best_per_player() {
+        let mut entries = vec![
+            timestamped("Alice", 100, MONDAY),
+            timestamped("Bob", 150, MONDAY),
+            timestamped("Alice", 300, MONDAY),
+            timestamped("Alice", 200, MONDAY),
+        ];
+
+        let best = LeaderboardPolicy {
+            retention: Retention::BestPerPlayer,
+            window: TimeWindow::AllTime,
+        };
+        let mut kept = entries.clone();
+        best.apply(&mut kept, MONDAY);
+        assert_eq!(names(&kept), [("Alice", 300), ("Bob", 150)]);
+
+        let best_two = LeaderboardPolicy {
+            retention: Retention::BestNPerPlayer(2),
+            window: TimeWindow::AllTime,
+        };
+        best_two.apply(&mut entries, MONDAY);
+        assert_eq!(
+            names(&entries),
+            [("Alice", 300), ("Alice", 200), ("Bob", 150)]
+        );
+    }
+
+    #[test]
+    fn test_time_windows() {
+        let now = MONDAY + 3 * SECONDS_PER_DAY + 3600; // Thursday 01:00
+
+        assert!(TimeWindow::Today.contains(now - 3600, now));
+        assert!(!TimeWindow::Today.contains(now - 7200, now)); // Wednesday 23:00
+        assert!(TimeWindow::ThisWeek.contains(MONDAY, now));
+        assert!(!TimeWindow::ThisWeek.contains(MONDAY - 1, now)); // Sunday night
+        assert!(TimeWindow::AllTime.contains(1, now));
+
+        // Entries from before timestamps existed only count for all time
+        assert!(TimeWindow::AllTime.contains(0, now));
+        assert!(!TimeWindow::ThisWeek.contains(0, now));
+    }
+
+    #[test]
+    fn test_windowed_policy_drops_old_runs() {
+        let mut entries = vec![
+            timestamped("Alice", 500, MONDAY - SECONDS_PER_DAY),
+            timestamped("Bob", 100, MONDAY + 60),
+            timestamped("Carol", 50, 0),
+        ];
+
+        let weekly = LeaderboardPolicy {
+            retention: Retention::AllRuns,
+            window: TimeWindow::ThisWeek,
+        };
+        weekly.apply(&mut entries, MONDAY + 120);
+        assert_eq!(names(&entries), [("Bob", 100)]);
+    }
+
+    #[test]
+    fn test_manager_applies_policy_on_save() {
+        let test_file = "test_policy_save.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file).with_policy(LeaderboardPolicy {
+            retention: Retention::BestPerPlayer,
+            window: TimeWindow::Today,
+        });
+        manager.save_highscore("Alice", 100);
+        manager.save_highscore("Alice", 300);
+        manager.save_highscore("Bob", 200);
+
+        let scores = manager.get_top_scores(10);
+        assert_eq!(names(&scores), [("Alice", 300), ("Bob", 200)]);
+        assert!(scores.iter().all(|entry| entry.timestamp > 0));
+
+        // Dropped runs are not persisted either
+        let reloaded = HighscoreManager::new(test_file);
+        assert_eq!(reloaded.load_highscores().len(), 2);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_timestamp_is_persisted_and_signed() {
+        let test_file = "test_timestamp_signed.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore("Alice", 1000);
+        let saved = manager.load_highscores()[0].timestamp;
+        assert!(saved > 0);
+
+        let reloaded = HighscoreManager::new(test_file);
+        let entry = &reloaded.load_highscores()[0];
+        assert_eq!(entry.timestamp, saved);
+        assert!(entry.verified);
+
+        // Back-dating a run invalidates its signature
+        let contents = std::fs::read_to_string(test_file).unwrap();
+        std::fs::write(
+            test_file,
+            contents.replace(&saved.to_string(), &(saved - 1).to_string()),
+        )
+        .unwrap();
+        let tampered = HighscoreManager::new(test_file);
+        assert!(!tampered.load_highscores()[0].verified);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_signature_without_timestamp_stays_valid() {
+        let test_file = "test_legacy_signature.txt";
+        remove_test_files(test_file);
+
+        // Entries signed before timestamps existed use the old message format
+        let manager = HighscoreManager::new(test_file);
+        let legacy = HighscoreEntry::new("Alice".to_string(), 1000);
+        let signature = manager.sign(&legacy);
+        std::fs::write(test_file, format!("Alice, 1000, {}\n", signature)).unwrap();
+
+        let reloaded = HighscoreManager::new(test_file);
+        let entry = &reloaded.load_highscores()[0];
+        assert_eq!(entry.timestamp, 0);
+        assert!(entry.verified);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_save_returns_rank() {
+        let test_file = "test_save_rank.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        assert_eq!(manager.save_highscore("Alice", 100), Some(1));
+        assert_eq!(manager.save_highscore("Bob", 300), Some(1));
+        assert_eq!(manager.save_highscore("Carol", 200), Some(2));
+        // Ties rank below the existing score
+        assert_eq!(manager.save_highscore("Dave", 200), Some(3));
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_qualifying_rank_matches_save_without_saving() {
+        let test_file = "test_qualifying_rank.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file);
+        for i in 1..=MAX_SAVED_SCORES as u32 {
+            manager.save_highscore(&format!("P{}", i), i * 100);
+        }
+
+        // Beats all but two scores
+        let rank = manager.qualifying_rank("New", 4850);
+        assert_eq!(rank, Some(3));
+        assert_eq!(manager.load_highscores().len(), MAX_SAVED_SCORES);
+        assert!(manager
+            .load_highscores()
+            .iter()
+            .all(|entry| entry.name != "New"));
+        assert_eq!(manager.save_highscore("New", 4850), rank);
+
+        // Below the lowest score on a full board
+        assert_eq!(manager.qualifying_rank("Low", 50), None);
+        assert_eq!(manager.save_highscore("Low", 50), None);
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_rank_respects_retention() {
+        let test_file = "test_rank_retention.txt";
+        remove_test_files(test_file);
+
+        let manager = HighscoreManager::new(test_file).with_policy(LeaderboardPolicy {
+            retention: Retention::BestPerPlayer,
+            window: TimeWindow::AllTime,
+        });
+        manager.save_highscore("Alice", 500);
+
+        // Not Alice's best, so it doesn't make the board
+        assert_eq!(manager.qualifying_rank("Alice", 300), None);
+        assert_eq!(manager.save_highscore("Alice", 300), None);
+        // Another player's run does
+        assert_eq!(manager.qualifying_rank("Bob", 300), Some(2));
+
+        remove_test_files(test_file);
+    }
+
+    #[test]
+    fn test_run_stats_persist_with_entry() {
+        let test_file = "test_run_stats.txt";
+        remove_test_files(test_file);
+
+        let mut stats = RunStats::new();
+        stats.record_shot(12);
+        stats.record_hits(7);
+        stats.record_kill(crate::entities::EnemyType::Tank);
+        stats.finish(50);
+
+        let manager = HighscoreManager::new(test_file);
+        manager.save_highscore_with_stats("Alice", 50, Some(&stats));
+        manager.save_highscore("Bob", 20);
+
+        let reloaded = HighscoreManager::new(test_file);
+        let scores = reloaded.load_highscores();
+        let loaded = scores[0].stats.as_ref().unwrap();
+        assert_eq!(loaded.shots_fired, 12);
+        assert_eq!(loaded.hits, 7);
+        assert_eq!(loaded.kills.tank, 1);
+        assert_eq!(loaded.score_samples, stats.score_samples);
+        assert!(scores[0].verified);
+        assert!(scores[1].stats.is_none());
+
+        remove_test_files(test_file);
+    }
 }
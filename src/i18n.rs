@@ -0,0 +1,266 @@
+//! Interface languages.
+//!
+//! Texts are looked up by [`Text`] key with [`Language::text`], so a missing
+//! translation is a compile error rather than a blank label.
+
+use serde::{Deserialize, Serialize};
+
+/// Language of the menus and the HUD.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+/// A translated interface text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    EnterName,
+    TypeName,
+    TapName,
+    StartGame,
+    Options,
+    TouchControls,
+    Paused,
+    Continue,
+    QuitToMenu,
+    GameOver,
+    Score,
+    EnemyRound,
+    Back,
+    On,
+    Off,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    UiVolume,
+    SoundPack,
+    PackSamples,
+    PackRetro,
+    Fullscreen,
+    WindowScale,
+    Difficulty,
+    ControlScheme,
+    Effects,
+    PostFx,
+    Language,
+    Scheme,
+    Hand,
+    LeftHand,
+    RightHand,
+    ZoneSize,
+    Opacity,
+    Done,
+    Muted,
+    FinalScore,
+    NewHighScore,
+    MadeLeaderboard,
+    RunNotRecorded,
+    ReasonReplay,
+    ReasonBot,
+    ReasonCheats,
+    ReasonPractice,
+    ReturnToMenu,
+    TapToReturn,
+    Shots,
+    Hits,
+    Accuracy,
+    Kills,
+    EnemyStandard,
+    EnemyFast,
+    EnemyTank,
+    EnemySwooper,
+    Bees,
+    BestCombo,
+    ClosestCall,
+    Time,
+    Waves,
+    WaveShort,
+    ScoreOverTime,
+    InitialsLetter,
+    InitialsMove,
+    InitialsDone,
+    InitialsTapHelp,
+    Ok,
+}
+
+impl Language {
+    /// All languages, in the order the options cycle through them.
+    pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+    /// Name of the language in itself.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+
+    /// `text` in this language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::i18n::{Language, Text};
+    ///
+    /// assert_eq!(Language::English.text(Text::StartGame), "START GAME");
+    /// assert_eq!(Language::German.text(Text::StartGame), "SPIEL STARTEN");
+    /// ```
+    #[must_use]
+    pub const fn text(self, text: Text) -> &'static str {
+        match self {
+            Self::English => match text {
+                Text::EnterName => "Enter Your Name:",
+                Text::TypeName => "Type your name...",
+                Text::TapName => "Tap to enter name...",
+                Text::StartGame => "START GAME",
+                Text::Options => "OPTIONS",
+                Text::TouchControls => "TOUCH CONTROLS",
+                Text::Paused => "PAUSED",
+                Text::Continue => "continue",
+                Text::QuitToMenu => "quit to the menu",
+                Text::GameOver => "GAME OVER",
+                Text::Score => "Score",
+                Text::EnemyRound => "Enemy round",
+                Text::Back => "BACK",
+                Text::On => "On",
+                Text::Off => "Off",
+                Text::MasterVolume => "Master volume",
+                Text::MusicVolume => "Music volume",
+                Text::SfxVolume => "Effects volume",
+                Text::UiVolume => "Interface volume",
+                Text::SoundPack => "Sound effects",
+                Text::PackSamples => "Samples",
+                Text::PackRetro => "Pure retro",
+                Text::Fullscreen => "Fullscreen",
+                Text::WindowScale => "Window size",
+                Text::Difficulty => "Difficulty",
+                Text::ControlScheme => "Touch controls",
+                Text::Effects => "Screen effects",
+                Text::PostFx => "Shaders",
+                Text::Language => "Language",
+                Text::Scheme => "Scheme",
+                Text::Hand => "Hand",
+                Text::LeftHand => "Left",
+                Text::RightHand => "Right",
+                Text::ZoneSize => "Zone size",
+                Text::Opacity => "Opacity",
+                Text::Done => "DONE",
+                Text::Muted => "MUTED",
+                Text::FinalScore => "Final Score",
+                Text::NewHighScore => "NEW HIGH SCORE!",
+                Text::MadeLeaderboard => "YOU MADE THE LEADERBOARD - RANK",
+                Text::RunNotRecorded => "RUN NOT RECORDED",
+                Text::ReasonReplay => "replay",
+                Text::ReasonBot => "bot",
+                Text::ReasonCheats => "cheats used",
+                Text::ReasonPractice => "practice start wave",
+                Text::ReturnToMenu => "return to the menu",
+                Text::TapToReturn => "Tap to return to the menu",
+                Text::Shots => "Shots",
+                Text::Hits => "Hits",
+                Text::Accuracy => "Accuracy",
+                Text::Kills => "Kills",
+                Text::EnemyStandard => "Std",
+                Text::EnemyFast => "Fast",
+                Text::EnemyTank => "Tank",
+                Text::EnemySwooper => "Swoop",
+                Text::Bees => "Bees",
+                Text::BestCombo => "Best combo",
+                Text::ClosestCall => "Closest call",
+                Text::Time => "Time",
+                Text::Waves => "Waves",
+                Text::WaveShort => "W",
+                Text::ScoreOverTime => "SCORE OVER TIME",
+                Text::InitialsLetter => "letter",
+                Text::InitialsMove => "move",
+                Text::InitialsDone => "done",
+                Text::InitialsTapHelp => "Tap top/bottom of a letter to change it",
+                Text::Ok => "OK",
+            },
+            Self::German => match text {
+                Text::EnterName => "Dein Name:",
+                Text::TypeName => "Namen eingeben...",
+                Text::TapName => "Tippen zum Eingeben...",
+                Text::StartGame => "SPIEL STARTEN",
+                Text::Options => "OPTIONEN",
+                Text::TouchControls => "TOUCH-STEUERUNG",
+                Text::Paused => "PAUSE",
+                Text::Continue => "weiter",
+                Text::QuitToMenu => "zum Menü",
+                Text::GameOver => "SPIEL VORBEI",
+                Text::Score => "Punkte",
+                Text::EnemyRound => "Gegnerwelle",
+                Text::Back => "ZURÜCK",
+                Text::On => "An",
+                Text::Off => "Aus",
+                Text::MasterVolume => "Gesamtlautstärke",
+                Text::MusicVolume => "Musik",
+                Text::SfxVolume => "Effekte",
+                Text::UiVolume => "Oberfläche",
+                Text::SoundPack => "Soundeffekte",
+                Text::PackSamples => "Samples",
+                Text::PackRetro => "Reiner Retro-Klang",
+                Text::Fullscreen => "Vollbild",
+                Text::WindowScale => "Fenstergröße",
+                Text::Difficulty => "Schwierigkeit",
+                Text::ControlScheme => "Touch-Steuerung",
+                Text::Effects => "Bildeffekte",
+                Text::PostFx => "Shader",
+                Text::Language => "Sprache",
+                Text::Scheme => "Schema",
+                Text::Hand => "Hand",
+                Text::LeftHand => "Links",
+                Text::RightHand => "Rechts",
+                Text::ZoneSize => "Zonengröße",
+                Text::Opacity => "Deckkraft",
+                Text::Done => "FERTIG",
+                Text::Muted => "STUMM",
+                Text::FinalScore => "Endstand",
+                Text::NewHighScore => "NEUER HIGHSCORE!",
+                Text::MadeLeaderboard => "IN DER BESTENLISTE - PLATZ",
+                Text::RunNotRecorded => "LAUF NICHT GEWERTET",
+                Text::ReasonReplay => "Wiederholung",
+                Text::ReasonBot => "Bot",
+                Text::ReasonCheats => "Cheats benutzt",
+                Text::ReasonPractice => "Übungsstart",
+                Text::ReturnToMenu => "zurück zum Menü",
+                Text::TapToReturn => "Tippen für das Menü",
+                Text::Shots => "Schüsse",
+                Text::Hits => "Treffer",
+                Text::Accuracy => "Trefferquote",
+                Text::Kills => "Abschüsse",
+                Text::EnemyStandard => "Std",
+                Text::EnemyFast => "Schnell",
+                Text::EnemyTank => "Panzer",
+                Text::EnemySwooper => "Sturz",
+                Text::Bees => "Bienen",
+                Text::BestCombo => "Beste Kombo",
+                Text::ClosestCall => "Knappste Stelle",
+                Text::Time => "Zeit",
+                Text::Waves => "Wellen",
+                Text::WaveShort => "W",
+                Text::ScoreOverTime => "PUNKTE IM VERLAUF",
+                Text::InitialsLetter => "Buchstabe",
+                Text::InitialsMove => "wechseln",
+                Text::InitialsDone => "fertig",
+                Text::InitialsTapHelp => "Oben/unten auf einen Buchstaben tippen",
+                Text::Ok => "OK",
+            },
+        }
+    }
+
+    /// The next language in [`Language::ALL`], wrapping around; `step` is
+    /// +1 or -1.
+    #[must_use]
+    pub fn cycle(self, step: isize) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|&language| language == self)
+            .unwrap_or(0);
+        let count = Self::ALL.len() as isize;
+        Self::ALL[(index as isize + step).rem_euclid(count) as usize]
+    }
+}
@@ -0,0 +1,763 @@
+//! Input layer.
+//!
+//! Input sources turn raw devices into one [`InputFrame`] of abstract
+//! [`Action`]s per frame:
+//! - [`KeyboardInput`], [`MouseInput`] and [`TouchInput`] read macroquad's
+//!   device state,
+//! - [`ReplayInput`] plays back a recorded run,
+//! - [`BotInput`] plays on its own.
+//!
+//! Several sources add to the same frame, and the game only looks at the
+//! merged result, so sources are interchangeable and the mapping from
+//! devices to actions can be tested without a window.
+
+use crate::bindings::{key_name, Bindings};
+use crate::constants::*;
+use crate::replay::Replay;
+use crate::touch::{TouchLayout, TouchScheme, TouchZone};
+use crate::world::{PlayInput, World};
+use macroquad::input::{
+    get_char_pressed, get_last_key_pressed, is_key_down, is_key_pressed, is_mouse_button_pressed,
+    mouse_position, touches, KeyCode, MouseButton, Touch, TouchPhase,
+};
+use macroquad::math::Vec2;
+use macroquad::time::get_frame_time;
+use serde::{Deserialize, Serialize};
+
+/// Something the player (or a stand-in) can ask the game to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    /// Pause or resume the run
+    Pause,
+    /// Accept: start the game, finish an entry, run a console command
+    Confirm,
+    /// Leave the current screen or close the console
+    Back,
+    /// Previous entry in lists, letters and the console history
+    Up,
+    /// Next entry in lists, letters and the console history
+    Down,
+    /// Delete the last typed character
+    Erase,
+    /// Return to the menu from the game-over screen
+    Restart,
+    /// Switch between local and online scores
+    ToggleScores,
+    /// Cycle the local leaderboard between all time, today and this week
+    CycleScoreWindow,
+    ToggleAchievements,
+    ToggleProfile,
+    ToggleTelemetry,
+    ExportScores,
+    ImportScores,
+    ToggleLog,
+    ToggleDebug,
+    ToggleConsole,
+    /// Open or close the controls screen
+    ToggleControls,
+    /// Open or close the options screen
+    Options,
+    /// Turn all sound off or on
+    ToggleMute,
+}
+
+impl Action {
+    /// Every action, in the order the controls screen lists them.
+    pub const ALL: [Self; 23] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Fire,
+        Self::Pause,
+        Self::Confirm,
+        Self::Back,
+        Self::Up,
+        Self::Down,
+        Self::Erase,
+        Self::Restart,
+        Self::ToggleScores,
+        Self::CycleScoreWindow,
+        Self::ToggleAchievements,
+        Self::ToggleProfile,
+        Self::ToggleTelemetry,
+        Self::ExportScores,
+        Self::ImportScores,
+        Self::ToggleLog,
+        Self::ToggleDebug,
+        Self::ToggleConsole,
+        Self::ToggleControls,
+        Self::Options,
+        Self::ToggleMute,
+    ];
+
+    /// Name shown on the controls screen.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move left",
+            Self::MoveRight => "Move right",
+            Self::Fire => "Fire",
+            Self::Pause => "Pause",
+            Self::Confirm => "Confirm",
+            Self::Back => "Back",
+            Self::Up => "Up",
+            Self::Down => "Down",
+            Self::Erase => "Delete character",
+            Self::Restart => "Back to menu",
+            Self::ToggleScores => "Local/online scores",
+            Self::CycleScoreWindow => "Score period",
+            Self::ToggleAchievements => "Achievements",
+            Self::ToggleProfile => "Profile",
+            Self::ToggleTelemetry => "Telemetry on/off",
+            Self::ExportScores => "Export scores",
+            Self::ImportScores => "Import scores",
+            Self::ToggleLog => "Log overlay",
+            Self::ToggleDebug => "Debug overlay",
+            Self::ToggleConsole => "Console",
+            Self::ToggleControls => "Controls screen",
+            Self::Options => "Options screen",
+            Self::ToggleMute => "Mute sound",
+        }
+    }
+
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Default keyboard layout.
+pub const KEY_BINDINGS: &[(KeyCode, Action)] = &[
+    (KeyCode::Left, Action::MoveLeft),
+    (KeyCode::Right, Action::MoveRight),
+    (KeyCode::Space, Action::Fire),
+    (KeyCode::P, Action::Pause),
+    (KeyCode::Enter, Action::Confirm),
+    (KeyCode::Escape, Action::Back),
+    (KeyCode::Up, Action::Up),
+    (KeyCode::Down, Action::Down),
+    (KeyCode::Backspace, Action::Erase),
+    (KeyCode::R, Action::Restart),
+    (KeyCode::Tab, Action::ToggleScores),
+    (KeyCode::F2, Action::CycleScoreWindow),
+    (KeyCode::F3, Action::ToggleAchievements),
+    (KeyCode::F4, Action::ToggleProfile),
+    (KeyCode::F5, Action::ExportScores),
+    (KeyCode::F7, Action::ToggleTelemetry),
+    (KeyCode::F9, Action::ImportScores),
+    (KeyCode::F10, Action::ToggleLog),
+    (KeyCode::F1, Action::ToggleDebug),
+    (KeyCode::GraveAccent, Action::ToggleConsole),
+    (KeyCode::F6, Action::ToggleControls),
+    (KeyCode::F8, Action::Options),
+    (KeyCode::M, Action::ToggleMute),
+];
+
+/// Everything the input sources reported for one frame.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::input::{Action, InputFrame};
+///
+/// let mut frame = InputFrame::default();
+/// frame.hold(Action::MoveLeft);
+/// frame.press(Action::Fire);
+///
+/// let input = frame.play_input();
+/// assert!(input.move_left && input.fire && !input.move_right);
+/// assert!(frame.is_held(Action::Fire));
+/// assert!(!frame.is_pressed(Action::MoveLeft));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputFrame {
+    held: u32,
+    pressed: u32,
+    /// Actions triggered by keys that type a letter or digit
+    text_keys: u32,
+    /// Characters typed this frame, in order
+    pub text: String,
+    /// Key pressed this frame, for rebinding
+    pub key: Option<KeyCode>,
+    /// Positions tapped or clicked this frame, in game coordinates
+    pub taps: Vec<Vec2>,
+    /// Player x position requested by touch controls
+    pub target_x: Option<f32>,
+    /// Share of the player's speed for analog movement
+    pub move_speed: Option<f32>,
+    /// Frame duration set by the source (replays) instead of the real one
+    pub frame_time: Option<f32>,
+    /// A finger is on the screen
+    pub touching: bool,
+}
+
+impl InputFrame {
+    /// Mark an action as held down.
+    pub fn hold(&mut self, action: Action) {
+        self.held |= action.bit();
+    }
+
+    /// Mark an action as triggered this frame (which also holds it).
+    pub fn press(&mut self, action: Action) {
+        self.held |= action.bit();
+        self.pressed |= action.bit();
+    }
+
+    /// Mark an action as triggered by `key`, remembering keys that also
+    /// type text (see [`ignore_text_keys`](Self::ignore_text_keys)).
+    pub fn press_key(&mut self, key: KeyCode, action: Action) {
+        self.press(action);
+        if is_text_key(key) {
+            self.text_keys |= action.bit();
+        }
+    }
+
+    /// Drop the actions of letter and digit keys, while their characters
+    /// go into a text field instead.
+    pub fn ignore_text_keys(&mut self) {
+        self.held &= !self.text_keys;
+        self.pressed &= !self.text_keys;
+    }
+
+    /// Whether the action is held down.
+    #[must_use]
+    pub const fn is_held(&self, action: Action) -> bool {
+        self.held & action.bit() != 0
+    }
+
+    /// Whether the action was triggered this frame.
+    #[must_use]
+    pub const fn is_pressed(&self, action: Action) -> bool {
+        self.pressed & action.bit() != 0
+    }
+
+    /// Add a tap or click; a touch that also moved the mouse is counted once.
+    pub fn tap(&mut self, position: Vec2) {
+        if !self.taps.contains(&position) {
+            self.taps.push(position);
+        }
+    }
+
+    /// The gameplay part of the frame, as applied by [`World::step`].
+    #[must_use]
+    pub fn play_input(&self) -> PlayInput {
+        PlayInput {
+            move_left: self.is_held(Action::MoveLeft),
+            move_right: self.is_held(Action::MoveRight),
+            fire: self.is_pressed(Action::Fire),
+            target_x: self.target_x,
+            speed: self.move_speed,
+        }
+    }
+
+    /// Replace the gameplay part of the frame with `input`.
+    pub fn set_play_input(&mut self, input: &PlayInput) {
+        let gameplay = Action::MoveLeft.bit() | Action::MoveRight.bit() | Action::Fire.bit();
+        self.held &= !gameplay;
+        self.pressed &= !gameplay;
+        if input.move_left {
+            self.hold(Action::MoveLeft);
+        }
+        if input.move_right {
+            self.hold(Action::MoveRight);
+        }
+        if input.fire {
+            self.press(Action::Fire);
+        }
+        self.target_x = input.target_x;
+        self.move_speed = input.speed;
+    }
+}
+
+/// Whether `key` types a character that names are made of.
+fn is_text_key(key: KeyCode) -> bool {
+    let name = key_name(key);
+    name.len() == 1 && name.chars().all(|ch| ch.is_ascii_alphanumeric())
+}
+
+/// Share of full speed for an analog position between -1 and 1: 0 inside
+/// the dead zone, rising to 1 at full deflection.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::input::analog_speed;
+///
+/// assert_eq!(analog_speed(0.1, 0.2), 0.0);
+/// assert_eq!(analog_speed(-1.0, 0.2), 1.0);
+/// assert!((analog_speed(0.6, 0.2) - 0.5).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn analog_speed(value: f32, deadzone: f32) -> f32 {
+    ((value.abs() - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0)
+}
+
+/// Produces input once per frame.
+pub trait InputSource {
+    /// Add this frame's input to `frame`.
+    fn poll(&mut self, frame: &mut InputFrame);
+}
+
+/// Bound keys and typed text.
+#[derive(Debug, Clone, Default)]
+pub struct KeyboardInput {
+    bindings: Bindings,
+}
+
+impl KeyboardInput {
+    /// Create a keyboard source with the given key bindings.
+    #[must_use]
+    pub fn new(bindings: Bindings) -> Self {
+        Self { bindings }
+    }
+
+    /// Keys currently mapped to actions.
+    #[must_use]
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Replace the key bindings.
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.bindings = bindings;
+    }
+}
+
+impl InputSource for KeyboardInput {
+    fn poll(&mut self, frame: &mut InputFrame) {
+        for (key, action) in self.bindings.iter() {
+            if is_key_pressed(key) {
+                frame.press_key(key, action);
+            } else if is_key_down(key) {
+                frame.hold(action);
+            }
+        }
+        frame.key = frame.key.or(get_last_key_pressed());
+
+        // macroquad hands out the newest character first
+        let mut typed = Vec::new();
+        while let Some(ch) = get_char_pressed() {
+            typed.push(ch);
+        }
+        frame.text.extend(typed.into_iter().rev());
+    }
+}
+
+/// Left clicks, reported as taps.
+#[derive(Debug, Clone, Copy)]
+pub struct MouseInput {
+    /// Converts window pixels to game coordinates
+    to_game: fn(Vec2) -> Vec2,
+}
+
+impl MouseInput {
+    /// Create a mouse source with a window-to-game coordinate conversion.
+    #[must_use]
+    pub fn new(to_game: fn(Vec2) -> Vec2) -> Self {
+        Self { to_game }
+    }
+}
+
+impl InputSource for MouseInput {
+    fn poll(&mut self, frame: &mut InputFrame) {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            frame.tap((self.to_game)(mouse_position().into()));
+        }
+    }
+}
+
+/// Touches: taps everywhere, plus the gameplay controls of the chosen
+/// [`TouchLayout`].
+///
+/// With [`controls`](Self::controls) on:
+/// - split screen: a finger in the movement zone moves the player to its x
+///   position, a new touch in the fire zone fires,
+/// - joystick: a finger that lands on the stick moves the player at a speed
+///   scaled by how far it is pushed, a new touch on the button fires,
+/// - drag: a finger anywhere moves the player to its x position and fires
+///   every [`TOUCH_AUTOFIRE_INTERVAL`] seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct TouchInput {
+    /// Map touches to movement and fire (during a run)
+    pub controls: bool,
+    pub layout: TouchLayout,
+    /// Converts window pixels to game coordinates
+    to_game: fn(Vec2) -> Vec2,
+    /// A finger was on the fire zone last frame
+    shooting: bool,
+    /// Touch id holding the joystick and the stick's offset from its center
+    stick: Option<(u64, Vec2)>,
+    /// Seconds until the next automatic shot
+    autofire: f32,
+}
+
+impl TouchInput {
+    /// Create a touch source with a window-to-game coordinate conversion.
+    #[must_use]
+    pub fn new(to_game: fn(Vec2) -> Vec2, layout: TouchLayout) -> Self {
+        Self {
+            controls: false,
+            layout,
+            to_game,
+            shooting: false,
+            stick: None,
+            autofire: 0.0,
+        }
+    }
+
+    /// Offset of the joystick knob from the stick's center, while held.
+    #[must_use]
+    pub fn stick_offset(&self) -> Option<Vec2> {
+        self.stick.map(|(_, offset)| offset)
+    }
+
+    /// Add the given touches, `dt` seconds after the last call, to `frame`.
+    pub fn apply(&mut self, touches: &[Touch], dt: f32, frame: &mut InputFrame) {
+        frame.touching |= !touches.is_empty();
+        let zones = self.layout.zones();
+        let mut shooting = false;
+        let mut dragging = false;
+        let mut stick = None;
+
+        for touch in touches {
+            let position = (self.to_game)(touch.position);
+            if touch.phase == TouchPhase::Started {
+                frame.tap(position);
+            }
+            let lifted = matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled);
+            if !self.controls || lifted {
+                continue;
+            }
+            let on_fire = zones.fire.is_some_and(|zone| zone.contains(position));
+
+            match self.layout.scheme {
+                TouchScheme::Split => {
+                    if zones.movement.contains(position) {
+                        frame.target_x = Some(position.x);
+                    } else if on_fire {
+                        shooting = true;
+                    }
+                }
+                TouchScheme::Joystick => {
+                    // The finger that grabbed the stick keeps it when it slides off
+                    let holding = self.stick.is_some_and(|(id, _)| id == touch.id);
+                    let grabbed =
+                        touch.phase == TouchPhase::Started && zones.movement.contains(position);
+                    if holding || grabbed {
+                        let TouchZone::Circle { center, radius } = zones.movement else {
+                            continue;
+                        };
+                        stick = Some((touch.id, (position - center).clamp_length_max(radius)));
+                    } else if on_fire {
+                        shooting = true;
+                    }
+                }
+                TouchScheme::Drag => {
+                    frame.target_x = Some(position.x);
+                    dragging = true;
+                }
+            }
+        }
+
+        // Shoot on touch start (not continuous)
+        if shooting && !self.shooting {
+            frame.press(Action::Fire);
+        }
+        self.shooting = shooting;
+
+        // Dragging fires right away, then at a steady rate
+        if dragging {
+            self.autofire -= dt;
+            if self.autofire <= 0.0 {
+                frame.press(Action::Fire);
+                self.autofire = TOUCH_AUTOFIRE_INTERVAL;
+            }
+        } else {
+            self.autofire = 0.0;
+        }
+
+        self.stick = stick;
+        if let (Some((_, offset)), TouchZone::Circle { radius, .. }) = (stick, zones.movement) {
+            let deflection = offset.x / radius;
+            let speed = analog_speed(deflection, TOUCH_STICK_DEADZONE);
+            if speed > 0.0 && !frame.is_held(Action::MoveLeft) && !frame.is_held(Action::MoveRight)
+            {
+                frame.hold(if deflection < 0.0 {
+                    Action::MoveLeft
+                } else {
+                    Action::MoveRight
+                });
+                frame.move_speed = Some(speed);
+            }
+        }
+    }
+}
+
+impl InputSource for TouchInput {
+    fn poll(&mut self, frame: &mut InputFrame) {
+        self.apply(&touches(), get_frame_time(), frame);
+    }
+}
+
+/// Plays back the inputs and frame times of a recording.
+#[derive(Debug, Clone)]
+pub struct ReplayInput {
+    replay: Replay,
+    next: usize,
+}
+
+impl ReplayInput {
+    /// Play `replay` from its first frame.
+    #[must_use]
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, next: 0 }
+    }
+
+    /// The recording being played.
+    #[must_use]
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Whether every recorded frame was played.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.frames.len()
+    }
+}
+
+impl InputSource for ReplayInput {
+    fn poll(&mut self, frame: &mut InputFrame) {
+        if let Some(recorded) = self.replay.frames.get(self.next) {
+            frame.set_play_input(&recorded.input);
+            frame.frame_time = Some(recorded.dt);
+            self.next += 1;
+        }
+    }
+}
+
+/// A simple computer player: lines up under the lowest enemy and fires.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BotInput {
+    /// Horizontal distance to the target; negative means it is to the left
+    offset: Option<f32>,
+    /// Whether another shot may be fired
+    can_fire: bool,
+}
+
+impl BotInput {
+    /// Pick the target from the current state of the run. Call before
+    /// [`poll`](InputSource::poll).
+    pub fn observe(&mut self, world: &World) {
+        // The lowest enemy is the closest to breaching
+        let target = world.enemies.iter().max_by(|a, b| a.y.total_cmp(&b.y));
+        self.offset = target.map(|enemy| enemy.x - world.player.x);
+        self.can_fire = world.bullets.len() < BOT_MAX_BULLETS;
+    }
+}
+
+impl InputSource for BotInput {
+    fn poll(&mut self, frame: &mut InputFrame) {
+        let Some(offset) = self.offset else {
+            return;
+        };
+        if offset < -BOT_AIM_TOLERANCE {
+            frame.hold(Action::MoveLeft);
+        } else if offset > BOT_AIM_TOLERANCE {
+            frame.hold(Action::MoveRight);
+        } else if self.can_fire {
+            frame.press(Action::Fire);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Difficulty;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn touch(phase: TouchPhase, x: f32) -> Touch {
+        Touch {
+            id: 0,
+            phase,
+            position: Vec2::new(x, 300.0),
+        }
+    }
+
+    #[test]
+    fn test_ignore_text_keys() {
+        // AZERTY layout: Q and D move, Space fires
+        let mut frame = InputFrame::default();
+        frame.press_key(KeyCode::Q, Action::MoveLeft);
+        frame.press_key(KeyCode::Space, Action::Fire);
+        frame.press_key(KeyCode::Key1, Action::Options);
+        frame.press_key(KeyCode::Right, Action::MoveRight);
+        frame.ignore_text_keys();
+
+        assert!(!frame.is_pressed(Action::MoveLeft));
+        assert!(!frame.is_held(Action::MoveLeft));
+        assert!(!frame.is_pressed(Action::Options));
+        assert!(frame.is_pressed(Action::Fire));
+        assert!(frame.is_pressed(Action::MoveRight));
+    }
+
+    #[test]
+    fn test_set_play_input_replaces_gameplay_actions() {
+        let mut frame = InputFrame::default();
+        frame.hold(Action::MoveLeft);
+        frame.press(Action::Pause);
+        let input = PlayInput {
+            move_right: true,
+            fire: true,
+            target_x: Some(100.0),
+            speed: Some(0.5),
+            ..PlayInput::default()
+        };
+
+        frame.set_play_input(&input);
+        assert_eq!(frame.play_input(), input);
+        assert!(frame.is_pressed(Action::Pause));
+    }
+
+    #[test]
+    fn test_touch_controls() {
+        let mut source = TouchInput::new(|position| position, TouchLayout::default());
+        let mut frame = InputFrame::default();
+        source.apply(&[touch(TouchPhase::Started, 700.0)], DT, &mut frame);
+        // Taps only while the controls are off
+        assert_eq!(frame.taps, [Vec2::new(700.0, 300.0)]);
+        assert!(!frame.is_pressed(Action::Fire));
+
+        source.controls = true;
+        let mut frame = InputFrame::default();
+        source.apply(
+            &[
+                touch(TouchPhase::Moved, 100.0),
+                touch(TouchPhase::Started, 700.0),
+            ],
+            DT,
+            &mut frame,
+        );
+        assert_eq!(frame.target_x, Some(100.0));
+        assert!(frame.is_pressed(Action::Fire));
+        assert!(frame.touching);
+
+        // Holding the fire half doesn't fire again
+        let mut frame = InputFrame::default();
+        source.apply(&[touch(TouchPhase::Stationary, 700.0)], DT, &mut frame);
+        assert!(!frame.is_pressed(Action::Fire));
+    }
+
+    #[test]
+    fn test_joystick_scales_speed() {
+        let layout = TouchLayout {
+            scheme: TouchScheme::Joystick,
+            ..TouchLayout::default()
+        };
+        let TouchZone::Circle { center, radius } = layout.zones().movement else {
+            panic!("joystick moves with a circle");
+        };
+        let mut source = TouchInput::new(|position| position, layout);
+        source.controls = true;
+
+        let at = |phase, x| Touch {
+            id: 3,
+            phase,
+            position: Vec2::new(x, center.y),
+        };
+        let mut frame = InputFrame::default();
+        source.apply(&[at(TouchPhase::Started, center.x)], DT, &mut frame);
+        assert!(!frame.is_held(Action::MoveLeft) && !frame.is_held(Action::MoveRight));
+
+        // Pushed all the way right, and past the edge: full speed
+        let mut frame = InputFrame::default();
+        source.apply(
+            &[at(TouchPhase::Moved, center.x + radius * 2.0)],
+            DT,
+            &mut frame,
+        );
+        assert!(frame.is_held(Action::MoveRight));
+        assert_eq!(frame.move_speed, Some(1.0));
+        assert_eq!(source.stick_offset(), Some(Vec2::new(radius, 0.0)));
+
+        // Halfway left: slower
+        let mut frame = InputFrame::default();
+        source.apply(
+            &[at(TouchPhase::Moved, center.x - radius / 2.0)],
+            DT,
+            &mut frame,
+        );
+        assert!(frame.is_held(Action::MoveLeft));
+        assert!(frame.move_speed.unwrap() < 0.6);
+
+        source.apply(&[at(TouchPhase::Ended, center.x)], DT, &mut frame);
+        assert_eq!(source.stick_offset(), None);
+    }
+
+    #[test]
+    fn test_drag_fires_automatically() {
+        let layout = TouchLayout {
+            scheme: TouchScheme::Drag,
+            ..TouchLayout::default()
+        };
+        let mut source = TouchInput::new(|position| position, layout);
+        source.controls = true;
+
+        let mut shots = 0;
+        for _ in 0..60 {
+            let mut frame = InputFrame::default();
+            source.apply(&[touch(TouchPhase::Moved, 300.0)], 1.0 / 60.0, &mut frame);
+            assert_eq!(frame.target_x, Some(300.0));
+            shots += u32::from(frame.is_pressed(Action::Fire));
+        }
+        // One second: the first shot right away, then one per interval
+        assert_eq!(shots, (1.0 / TOUCH_AUTOFIRE_INTERVAL) as u32);
+    }
+
+    #[test]
+    fn test_replay_input_plays_recorded_frames() {
+        let mut replay = Replay::new(1, Difficulty::Normal, 1);
+        replay.record(
+            0.02,
+            PlayInput {
+                fire: true,
+                ..PlayInput::default()
+            },
+        );
+        let mut source = ReplayInput::new(replay);
+
+        let mut frame = InputFrame::default();
+        source.poll(&mut frame);
+        assert!(frame.is_pressed(Action::Fire));
+        assert_eq!(frame.frame_time, Some(0.02));
+        assert!(source.is_finished());
+
+        let mut frame = InputFrame::default();
+        source.poll(&mut frame);
+        assert_eq!(frame, InputFrame::default());
+    }
+
+    #[test]
+    fn test_bot_aims_at_lowest_enemy() {
+        let mut world = World::new(Difficulty::Normal, 1, 1);
+        world.enemies.truncate(2);
+        world.enemies[0].x = 100.0;
+        world.enemies[1].x = 900.0;
+        world.enemies[1].y += 50.0;
+
+        let mut bot = BotInput::default();
+        bot.observe(&world);
+        let mut frame = InputFrame::default();
+        bot.poll(&mut frame);
+        assert!(frame.is_held(Action::MoveRight));
+
+        world.player.x = 900.0;
+        bot.observe(&world);
+        let mut frame = InputFrame::default();
+        bot.poll(&mut frame);
+        assert!(frame.is_pressed(Action::Fire));
+    }
+}
@@ -0,0 +1,402 @@
+//! Online leaderboard client.
+//!
+//! Talks to a leaderboard server (see `src/bin/leaderboard_server.rs`) over a
+//! small JSON-over-HTTP protocol:
+//!
+//! - `POST /scores` with a [`ScoreRecord`] body submits a run and answers
+//!   with a [`SubmitResponse`] carrying the rank the score achieved.
+//! - `GET /scores?limit=N` returns the global top N as a JSON array of
+//!   [`ScoreRecord`]s, highest score first.
+//!
+//! Only plain HTTP/1.1 is spoken, which keeps the client dependency-free and
+//! is enough for a server on the local machine or LAN. On WASM raw sockets are
+//! unavailable, so every request fails with an I/O error and submissions
+//! simply stay queued.
+//!
+//! Requests block, so the game talks to the server through a [`SyncJob`],
+//! which runs them on a background thread and is polled every frame.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::mpsc;
+
+/// Environment variable holding the leaderboard server address (`host:port`).
+pub const LEADERBOARD_ENV_VAR: &str = "BUMBLEBEES_LEADERBOARD";
+
+/// Default port used by the reference leaderboard server.
+pub const DEFAULT_LEADERBOARD_PORT: u16 = 8787;
+
+/// Maximum size of a response body the client will read (1MB).
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RESPONSE_SIZE: u64 = 1024 * 1024;
+
+/// A single score as exchanged with the leaderboard server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreRecord {
+    /// Player name (max 20 characters)
+    pub name: String,
+    /// Final score of the run
+    pub score: u32,
+    /// When the run finished (Unix seconds), if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+}
+
+impl ScoreRecord {
+    /// Create a new score record.
+    #[must_use]
+    pub fn new(name: &str, score: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            score,
+            timestamp: None,
+        }
+    }
+}
+
+/// Server reply to a score submission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmitResponse {
+    /// 1-based rank of the submitted score on the global leaderboard
+    pub rank: usize,
+}
+
+/// Errors returned by [`LeaderboardClient`].
+#[derive(Debug)]
+pub enum LeaderboardError {
+    /// Connecting, sending or receiving failed (server offline, timeout, ...)
+    Io(std::io::Error),
+    /// The server answered with a non-2xx status code
+    Status(u16),
+    /// The response could not be parsed
+    Protocol(String),
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "network error: {}", err),
+            Self::Status(code) => write!(f, "server returned HTTP {}", code),
+            Self::Protocol(msg) => write!(f, "malformed response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LeaderboardError {}
+
+impl LeaderboardError {
+    /// Whether the request may succeed later: the server couldn't be
+    /// reached or had a problem of its own (5xx). Anything else, such as a
+    /// 4xx rejection, will fail the same way every time.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Io(_) | Self::Status(500..))
+    }
+}
+
+impl From<std::io::Error> for LeaderboardError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Blocking client for the leaderboard server.
+///
+/// Requests use short timeouts so an unreachable server costs at most a
+/// couple of seconds; callers treat any error as "offline" and retry later.
+#[derive(Debug, Clone)]
+pub struct LeaderboardClient {
+    /// Server address as `host:port`
+    addr: String,
+}
+
+impl LeaderboardClient {
+    /// Create a client for the server at `addr`.
+    ///
+    /// Accepts `host:port` as well as `http://host:port[/]`. A missing port
+    /// defaults to [`DEFAULT_LEADERBOARD_PORT`].
+    #[must_use]
+    pub fn new(addr: &str) -> Self {
+        let addr = addr.trim();
+        let addr = addr.strip_prefix("http://").unwrap_or(addr);
+        let addr = addr.trim_end_matches('/');
+        let addr = if addr.contains(':') {
+            addr.to_string()
+        } else {
+            format!("{}:{}", addr, DEFAULT_LEADERBOARD_PORT)
+        };
+
+        Self { addr }
+    }
+
+    /// Create a client from the [`LEADERBOARD_ENV_VAR`] environment variable.
+    ///
+    /// Returns `None` when the variable is unset or empty, which keeps all
+    /// scores local to the device.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        std::env::var(LEADERBOARD_ENV_VAR)
+            .ok()
+            .filter(|addr| !addr.trim().is_empty())
+            .map(|addr| Self::new(&addr))
+    }
+
+    /// Server address as `host:port`.
+    #[must_use]
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Submit a run to the global leaderboard.
+    ///
+    /// # Returns
+    ///
+    /// The 1-based rank the score achieved on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server is unreachable, rejects the submission,
+    /// or sends a malformed reply.
+    pub fn submit(&self, record: &ScoreRecord) -> Result<usize, LeaderboardError> {
+        let body = serde_json::to_string(record)
+            .map_err(|err| LeaderboardError::Protocol(err.to_string()))?;
+        let response = self.request("POST", "/scores", Some(&body))?;
+        let reply: SubmitResponse = serde_json::from_str(&response)
+            .map_err(|err| LeaderboardError::Protocol(err.to_string()))?;
+        Ok(reply.rank)
+    }
+
+    /// Fetch the global top `n` scores, highest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server is unreachable or sends a malformed reply.
+    pub fn fetch_top(&self, n: usize) -> Result<Vec<ScoreRecord>, LeaderboardError> {
+        let response = self.request("GET", &format!("/scores?limit={}", n), None)?;
+        let mut scores: Vec<ScoreRecord> = serde_json::from_str(&response)
+            .map_err(|err| LeaderboardError::Protocol(err.to_string()))?;
+        scores.truncate(n);
+        Ok(scores)
+    }
+
+    /// Submit `records` in order, stopping at the first one that may
+    /// succeed on a later try (see [`LeaderboardError::is_retryable`]).
+    /// Records the server rejects for good are dropped.
+    ///
+    /// # Returns
+    ///
+    /// How many records from the start of `records` are settled, i.e.
+    /// uploaded or dropped.
+    pub fn submit_all(&self, records: &[ScoreRecord]) -> usize {
+        for (settled, record) in records.iter().enumerate() {
+            match self.submit(record) {
+                Ok(rank) => log::info!(
+                    "Submitted {} - {} to online leaderboard (rank {})",
+                    record.name,
+                    record.score,
+                    rank
+                ),
+                Err(err) if err.is_retryable() => {
+                    log::warn!("Online leaderboard unavailable, will retry: {}", err);
+                    return settled;
+                }
+                Err(err) => log::warn!(
+                    "Online leaderboard rejected {} - {}, dropping it: {}",
+                    record.name,
+                    record.score,
+                    err
+                ),
+            }
+        }
+        records.len()
+    }
+
+    /// Send a single HTTP/1.1 request and return the response body.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+    ) -> Result<String, LeaderboardError> {
+        use std::io::{Read, Write};
+        use std::net::{TcpStream, ToSocketAddrs};
+        use std::time::Duration;
+
+        let socket_addr = self.addr.to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("cannot resolve {}", self.addr),
+            )
+        })?;
+
+        let mut stream = TcpStream::connect_timeout(&socket_addr, Duration::from_millis(500))?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        stream.set_write_timeout(Some(Duration::from_secs(2)))?;
+
+        let body = body.unwrap_or("");
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            self.addr,
+            body.len(),
+            body
+        )?;
+        stream.flush()?;
+
+        let mut raw = String::new();
+        stream.take(MAX_RESPONSE_SIZE).read_to_string(&mut raw)?;
+        parse_response(&raw)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn request(
+        &self,
+        _method: &str,
+        _path: &str,
+        _body: Option<&str>,
+    ) -> Result<String, LeaderboardError> {
+        Err(LeaderboardError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "sockets are not available in the browser",
+        )))
+    }
+}
+
+/// Result of a [`SyncJob`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncOutcome {
+    /// Queued runs that were uploaded or rejected for good
+    pub settled: Vec<ScoreRecord>,
+    /// Global top scores, or `None` if they couldn't be fetched
+    pub global_scores: Option<Vec<ScoreRecord>>,
+}
+
+/// Uploads queued runs and fetches the global top scores without blocking
+/// the caller.
+///
+/// On desktop the requests run on a background thread; poll the job each
+/// frame until it returns its [`SyncOutcome`]. In the browser requests fail
+/// right away, so the job finishes on the spot.
+#[derive(Debug)]
+pub struct SyncJob {
+    receiver: mpsc::Receiver<SyncOutcome>,
+}
+
+impl SyncJob {
+    /// Start uploading `records` with `client`, then fetch the top `n`.
+    #[must_use]
+    pub fn start(client: LeaderboardClient, records: Vec<ScoreRecord>, n: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let run = move || {
+            let settled = client.submit_all(&records);
+            let global_scores = client
+                .fetch_top(n)
+                .map_err(|err| log::warn!("Failed to fetch online leaderboard: {}", err))
+                .ok();
+            // The game may have quit in the meantime
+            let _ = sender.send(SyncOutcome {
+                settled: records[..settled].to_vec(),
+                global_scores,
+            });
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(run);
+        #[cfg(target_arch = "wasm32")]
+        run();
+
+        Self { receiver }
+    }
+
+    /// The outcome, once the job is done.
+    ///
+    /// A job whose thread died is reported as done with nothing settled.
+    #[must_use]
+    pub fn poll(&self) -> Option<SyncOutcome> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(SyncOutcome {
+                settled: Vec::new(),
+                global_scores: None,
+            }),
+        }
+    }
+}
+
+/// Split a raw HTTP response into status and body, returning the body for
+/// 2xx responses.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn parse_response(raw: &str) -> Result<String, LeaderboardError> {
+    let (head, body) = raw
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| LeaderboardError::Protocol("missing header terminator".to_string()))?;
+
+    let status_line = head.lines().next().unwrap_or("");
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| LeaderboardError::Protocol(format!("bad status line: {}", status_line)))?;
+
+    if (200..300).contains(&status) {
+        Ok(body.to_string())
+    } else {
+        Err(LeaderboardError::Status(status))
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_address_normalization() {
+        assert_eq!(
+            LeaderboardClient::new("127.0.0.1:9000").addr(),
+            "127.0.0.1:9000"
+        );
+        assert_eq!(
+            LeaderboardClient::new("http://localhost:9000/").addr(),
+            "localhost:9000"
+        );
+        assert_eq!(LeaderboardClient::new("localhost").addr(), "localhost:8787");
+    }
+
+    #[test]
+    fn test_parse_response_success() {
+        let raw = "HTTP/1.1 201 Created\r\nContent-Length: 10\r\n\r\n{\"rank\":3}";
+        assert_eq!(parse_response(raw).unwrap(), "{\"rank\":3}");
+    }
+
+    #[test]
+    fn test_parse_response_error_status() {
+        let raw = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
+        assert!(matches!(
+            parse_response(raw),
+            Err(LeaderboardError::Status(400))
+        ));
+    }
+
+    #[test]
+    fn test_parse_response_malformed() {
+        assert!(matches!(
+            parse_response("garbage"),
+            Err(LeaderboardError::Protocol(_))
+        ));
+    }
+
+    #[test]
+    fn test_unreachable_server_is_io_error() {
+        // Port 1 is reserved and nothing listens there in test environments
+        let client = LeaderboardClient::new("127.0.0.1:1");
+        assert!(matches!(
+            client.submit(&ScoreRecord::new("Alice", 100)),
+            Err(LeaderboardError::Io(_))
+        ));
+    }
+}
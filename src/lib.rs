@@ -4,15 +4,52 @@
 //! Features include:
 //! - Progressive difficulty with wave-based gameplay
 //! - Parallax scrolling background
-//! - Highscore tracking with persistent storage
+//! - Highscore tracking with persistent storage and an optional online leaderboard
+//! - Player profiles with lifetime stats and achievements
 //! - Sound effects and background music
 
+pub mod achievements;
+pub mod audio;
+pub mod bindings;
+pub mod cli;
+pub mod console;
 pub mod constants;
+pub mod debug;
+pub mod difficulty;
 pub mod entities;
+pub mod events;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gamepad;
 pub mod highscore;
+pub mod i18n;
+pub mod input;
+pub mod leaderboard;
+pub mod logger;
+pub mod march;
+pub mod music;
+pub mod postfx;
+pub mod profile;
+pub mod replay;
+pub mod settings;
+pub mod stats;
+pub mod storage;
+pub mod synth;
 pub mod systems;
+pub mod telemetry;
+pub mod touch;
+pub mod wav;
+pub mod world;
 
+pub use achievements::{Achievement, AchievementManager, ACHIEVEMENTS};
 pub use constants::*;
+pub use difficulty::Difficulty;
 pub use entities::*;
-pub use highscore::{HighscoreEntry, HighscoreManager};
+pub use events::{EventQueue, GameEvent};
+pub use highscore::{
+    HighscoreEntry, HighscoreExport, HighscoreManager, ImportMode, ImportSummary,
+    LeaderboardPolicy, Retention, TimeWindow,
+};
+pub use leaderboard::{LeaderboardClient, ScoreRecord};
+pub use profile::{PlayerProfile, ProfileManager};
+pub use stats::{KillCounts, RunStats};
 pub use systems::*;
@@ -0,0 +1,290 @@
+//! Logging backend.
+//!
+//! [`GameLogger`] receives the `log::debug!`/`log::info!`/... calls made
+//! throughout the game. Every record that passes the level filter is
+//! - printed to stderr (desktop) or the browser console (WASM),
+//! - appended to an optional log file (desktop),
+//! - kept in a short in-memory history for the in-game log overlay.
+//!
+//! The level comes from the `--log-level` flag or the [`LOG_ENV_VAR`]
+//! environment variable and defaults to [`DEFAULT_LEVEL`].
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::{self, Write};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// Environment variable holding the log level on desktop
+/// (`off`, `error`, `warn`, `info`, `debug` or `trace`).
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const LOG_ENV_VAR: &str = "BUMBLEBEES_LOG";
+
+/// Level used when neither the flag nor the environment variable is set.
+pub const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Formatted lines kept for the log overlay.
+pub const HISTORY_LINES: usize = 200;
+
+/// The installed logger, reachable from the overlay.
+static LOGGER: OnceLock<GameLogger> = OnceLock::new();
+
+/// `log` backend writing to the console, an optional file and a history
+/// ring buffer.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::logger::GameLogger;
+/// use log::{Level, LevelFilter, Log, Record};
+///
+/// let logger = GameLogger::new(LevelFilter::Warn);
+/// let record = Record::builder()
+///     .level(Level::Warn)
+///     .target("bumblebees::highscore")
+///     .args(format_args!("Failed to save"))
+///     .build();
+/// logger.log(&record);
+///
+/// assert_eq!(logger.recent_lines(10), ["WARN  highscore: Failed to save"]);
+/// ```
+pub struct GameLogger {
+    level: LevelFilter,
+    #[cfg(not(target_arch = "wasm32"))]
+    file: Option<Mutex<File>>,
+    history: Mutex<VecDeque<String>>,
+}
+
+impl GameLogger {
+    /// Create a logger that lets through records up to `level`.
+    #[must_use]
+    pub fn new(level: LevelFilter) -> Self {
+        Self {
+            level,
+            #[cfg(not(target_arch = "wasm32"))]
+            file: None,
+            history: Mutex::new(VecDeque::with_capacity(HISTORY_LINES)),
+        }
+    }
+
+    /// Also append every line to the file at `path`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error if the file cannot be opened for appending.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_file(mut self, path: &Path) -> io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        self.file = Some(Mutex::new(file));
+        Ok(self)
+    }
+
+    /// The last `count` lines, oldest first.
+    #[must_use]
+    pub fn recent_lines(&self, count: usize) -> Vec<String> {
+        let Ok(history) = self.history.lock() else {
+            return Vec::new();
+        };
+        let skip = history.len().saturating_sub(count);
+        history.iter().skip(skip).cloned().collect()
+    }
+}
+
+impl Log for GameLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // Only the last path segment: "bumblebees::systems::collision" -> "collision"
+        let module = record.target().rsplit("::").next().unwrap_or_default();
+        let line = format!("{:<5} {}: {}", record.level(), module, record.args());
+
+        write_console(record.level(), &line);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                // A failing log file must not take the game down with it
+                let _ = writeln!(file, "{} {}", crate::highscore::unix_time_now(), line);
+            }
+        }
+
+        if let Ok(mut history) = self.history.lock() {
+            if history.len() == HISTORY_LINES {
+                history.pop_front();
+            }
+            history.push_back(line);
+        }
+    }
+
+    fn flush(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// Install `logger` as the global `log` backend.
+///
+/// # Errors
+///
+/// Returns an error if a logger was already installed.
+pub fn init(logger: GameLogger) -> Result<(), SetLoggerError> {
+    // On a second call the new logger is dropped and `set_logger` fails
+    let _ = LOGGER.set(logger);
+    let logger = LOGGER.get().expect("logger was just set");
+    log::set_logger(logger)?;
+    log::set_max_level(logger.level);
+    Ok(())
+}
+
+/// The last `count` lines of the installed logger, oldest first (empty if
+/// none is installed).
+#[must_use]
+pub fn recent_lines(count: usize) -> Vec<String> {
+    LOGGER
+        .get()
+        .map(|logger| logger.recent_lines(count))
+        .unwrap_or_default()
+}
+
+/// Parse a level name such as `debug` or `WARN`.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+#[must_use]
+pub fn parse_level(name: &str) -> Option<LevelFilter> {
+    name.trim().parse().ok()
+}
+
+/// Level from [`LOG_ENV_VAR`]; unset or unparsable values give `None`.
+#[cfg(not(target_arch = "wasm32"))]
+#[must_use]
+pub fn level_from_env() -> Option<LevelFilter> {
+    let value = std::env::var(LOG_ENV_VAR).ok()?;
+    let level = parse_level(&value);
+    if level.is_none() {
+        eprintln!("Ignoring unknown {} level '{}'", LOG_ENV_VAR, value);
+    }
+    level
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_console(_level: Level, line: &str) {
+    eprintln!("{}", line);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_console(level: Level, line: &str) {
+    use std::ffi::CString;
+    use std::os::raw::c_char;
+
+    // Provided by miniquad's gl.js
+    extern "C" {
+        fn console_debug(msg: *const c_char);
+        fn console_info(msg: *const c_char);
+        fn console_warn(msg: *const c_char);
+        fn console_error(msg: *const c_char);
+    }
+
+    let Ok(message) = CString::new(line) else {
+        return;
+    };
+    unsafe {
+        match level {
+            Level::Error => console_error(message.as_ptr()),
+            Level::Warn => console_warn(message.as_ptr()),
+            Level::Info => console_info(message.as_ptr()),
+            Level::Debug | Level::Trace => console_debug(message.as_ptr()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+
+    fn log_line(logger: &GameLogger, level: Level, message: &str) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .target("bumblebees::systems::collision")
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug"), Some(LevelFilter::Debug));
+        assert_eq!(parse_level(" WARN "), Some(LevelFilter::Warn));
+        assert_eq!(parse_level("off"), Some(LevelFilter::Off));
+        assert_eq!(parse_level("loud"), None);
+    }
+
+    #[test]
+    fn test_level_filter_applies_to_history() {
+        let logger = GameLogger::new(LevelFilter::Info);
+        log_line(&logger, Level::Debug, "hidden");
+        log_line(&logger, Level::Info, "shown");
+        log_line(&logger, Level::Error, "also shown");
+
+        assert_eq!(
+            logger.recent_lines(10),
+            ["INFO  collision: shown", "ERROR collision: also shown"]
+        );
+    }
+
+    #[test]
+    fn test_history_keeps_last_lines() {
+        let logger = GameLogger::new(LevelFilter::Trace);
+        for i in 0..HISTORY_LINES + 5 {
+            log_line(&logger, Level::Trace, &i.to_string());
+        }
+
+        let lines = logger.recent_lines(usize::MAX);
+        assert_eq!(lines.len(), HISTORY_LINES);
+        assert_eq!(lines[0], "TRACE collision: 5");
+        assert_eq!(
+            logger.recent_lines(2),
+            [
+                format!("TRACE collision: {}", HISTORY_LINES + 3),
+                format!("TRACE collision: {}", HISTORY_LINES + 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_output() {
+        let test_file = "test_logger_output.log";
+        let _ = std::fs::remove_file(test_file);
+
+        let logger = GameLogger::new(LevelFilter::Warn)
+            .with_file(Path::new(test_file))
+            .unwrap();
+        log_line(&logger, Level::Warn, "written");
+        log_line(&logger, Level::Info, "filtered");
+        logger.flush();
+
+        let contents = std::fs::read_to_string(test_file).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.trim_end().ends_with("WARN  collision: written"));
+
+        let _ = std::fs::remove_file(test_file);
+    }
+}
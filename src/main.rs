@@ -1,9 +1,7 @@
 //! BumbleBees - Space Invaders-style arcade shooter
 //! Macroquad edition with WASM support
 
-use macroquad::audio::{
-    load_sound, play_sound, play_sound_once, stop_sound, PlaySoundParams, Sound,
-};
+use macroquad::audio::{load_sound, load_sound_from_bytes, Sound};
 use macroquad::prelude::*;
 
 #[cfg(target_arch = "wasm32")]
@@ -29,15 +27,69 @@ use image::GenericImageView;
 #[cfg(not(target_arch = "wasm32"))]
 use macroquad::miniquad::conf::Icon;
 
+mod achievements;
+mod audio;
+mod bindings;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod console;
 mod constants;
+mod debug;
+mod difficulty;
 mod entities;
+mod events;
+#[cfg(not(target_arch = "wasm32"))]
+mod gamepad;
 mod highscore;
+mod i18n;
+mod input;
+mod leaderboard;
+mod logger;
+mod march;
+mod music;
+mod postfx;
+mod profile;
+mod replay;
+mod settings;
+mod stats;
+mod storage;
+mod synth;
 mod systems;
-
+mod telemetry;
+mod touch;
+mod wav;
+mod world;
+
+use achievements::{Achievement, AchievementManager, ACHIEVEMENTS};
+use audio::{AudioManager, Bus, Clip, PannedSound, SoundPack};
+use bindings::{key_name, ControlsManager, ControlsScreen};
+use console::{Console, ConsoleCommand};
 use constants::*;
-use entities::{Bullet, Enemy, EnemyType, Explosion, Player};
-use highscore::HighscoreManager;
-use systems::{generate_wave, process_collisions};
+use debug::{formation_bounds, FrameTimes};
+use difficulty::Difficulty;
+use entities::{Enemy, EnemyType, Explosion};
+use events::GameEvent;
+#[cfg(not(target_arch = "wasm32"))]
+use gamepad::{GamepadChange, GamepadInput};
+use highscore::{HighscoreManager, ImportMode, LeaderboardPolicy, Retention, TimeWindow};
+use i18n::{Language, Text};
+use input::{
+    Action, BotInput, InputFrame, InputSource, KeyboardInput, MouseInput, ReplayInput, TouchInput,
+};
+use leaderboard::{LeaderboardClient, ScoreRecord, SyncJob};
+use logger::GameLogger;
+use march::March;
+use music::{Manifest, Playlists, Scene};
+use postfx::{Pass, PassSet, PostFx};
+use profile::ProfileManager;
+use replay::Replay;
+use settings::{OptionRow, Settings, SettingsStore};
+use stats::RunStats;
+use systems::initials::INITIALS_LEN;
+use systems::InitialsEntry;
+use telemetry::{RunSummary, Telemetry};
+use touch::{TouchLayout, TouchScheme, TouchZone};
+use world::{PlayInput, World};
 
 /// Generate a list of candidate file paths for asset loading across different bundle structures.
 ///
@@ -216,6 +268,27 @@ async fn load_sound_fallback(path: &str) -> Result<Sound, macroquad::Error> {
     }
 }
 
+/// Load a file with fallback paths for bundle compatibility
+async fn load_bytes_fallback(path: &str) -> Option<Vec<u8>> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        load_file(path).await.ok()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        candidate_asset_paths(path)
+            .into_iter()
+            .find_map(|candidate| std::fs::read(candidate).ok())
+    }
+}
+
+/// Load a sound effect that can be panned, with fallback paths
+async fn load_panned_sound_fallback(path: &str) -> Option<PannedSound> {
+    let bytes = load_bytes_fallback(path).await?;
+    PannedSound::load(&bytes).await
+}
+
 /// Load TTF font with fallback paths for bundle compatibility
 async fn load_font_fallback(path: &str) -> Option<Font> {
     #[cfg(target_arch = "wasm32")]
@@ -249,6 +322,7 @@ enum GameState {
     Menu,
     Playing,
     GameOver,
+    Options, // Settings screen, opened from the menu
 }
 
 /// Represents a single parallax background layer with infinite scrolling.
@@ -367,26 +441,58 @@ impl BackgroundLayer {
 }
 
 struct Game {
-    player: Player,
-    bullets: Vec<Bullet>,
-    enemies: Vec<Enemy>,
+    world: World, // Player, enemies, bullets, bee, wave and score of the run
+    keyboard: KeyboardInput,
+    mouse: MouseInput,
+    touch: TouchInput,
+    #[cfg(not(target_arch = "wasm32"))]
+    gamepad: GamepadInput,
+    bot: Option<BotInput>,         // Computer player (--bot)
+    playback: Option<ReplayInput>, // Recording played back instead of live input (--replay)
+    input: PlayInput,              // Gameplay input gathered by handle_input for the next step
+    input_frame_time: Option<f32>, // Frame time dictated by the input (replays)
+    recording: Replay,             // Inputs of the current run, saved when it ends
+    paused: bool,                  // Run paused (P/Esc)
     explosions: Vec<Explosion>,
-    bullet_spawn_buffer: Vec<Bullet>,
-    collision_results: Vec<(f32, f32, u32)>,
-    enemy_speed: f32,
-    bullet_speed: f32,
-    player_speed: f32,
-    descent_speed: f32,
-    descent_distance: f32, // how much enemies need to descend
-    wave_number: u32,
+    seed: u64,               // Random seed of the current run
+    fixed_seed: Option<u64>, // Seed for every run instead of the clock (--seed)
+    start_wave: u32,         // Wave new runs start at (--wave)
+    telemetry: Telemetry,    // Opt-in local gameplay log
     state: GameState,
-    score: u32,
+    run_stats: RunStats, // Statistics of the current (or just finished) run
 
     // Player and highscore
     player_name: String,
-    highscore_manager: HighscoreManager,
-    just_reset: bool,    // Flag to prevent 'R' key from entering name after reset
-    intro_playing: bool, // Flag to track if intro music is currently playing
+    difficulty: Difficulty,          // Chosen in the menu, scales enemy speed
+    settings: SettingsStore,         // Options screen settings (volumes, window, language...)
+    options_selected: usize,         // Highlighted row of the options screen
+    profile_manager: ProfileManager, // Lifetime stats per player name
+    controls: ControlsManager,       // Key bindings per player name
+    bindings_profile: String,        // Player whose bindings the keyboard uses
+    controls_screen: Option<ControlsScreen>, // Menu shows the controls screen (F6)
+    show_profile: bool,              // Menu shows the player's profile instead of scores
+    achievements: AchievementManager, // Unlock state and career counters
+    achievement_toasts: Vec<(&'static Achievement, f32)>, // Unlock notifications and seconds left
+    show_achievements: bool,         // Menu shows the achievement gallery
+    show_log: bool,                  // Log overlay with the latest log lines (F10)
+    debug_tools: bool,               // Debug overlay hotkey enabled (debug builds or --debug)
+    show_debug: bool,                // Debug overlay with hitboxes and tunables (F1)
+    frame_times: FrameTimes,         // Recent frame times for the debug overlay graph
+    console: Console,                // Developer console (`), enabled with the debug tools
+    cheats_used: bool,               // A console command changed this run; keeps it off the boards
+    time_scale: f32,                 // Gameplay speed multiplier set from the console
+    highscore_manager: HighscoreManager, // All-time board (exported, uploaded)
+    weekly_highscores: HighscoreManager, // Best run per player this week
+    daily_highscores: HighscoreManager, // Best run per player today
+    highscore_window: TimeWindow,    // Board shown in the menu
+    new_record_rank: Option<usize>,  // All-time rank of the finished run, if it made the board
+    initials_entry: Option<InitialsEntry>, // Arcade initials entry on game over (no name typed)
+    global_scores: Option<Vec<ScoreRecord>>, // Online top scores (None if offline/disabled)
+    leaderboard_sync: Option<SyncJob>, // Upload and fetch running in the background
+    show_global_scores: bool,        // Menu shows online instead of local scores
+    status_message: Option<String>,  // Feedback shown in the menu (export/import)
+    status_timer: f32,               // Seconds until the status message disappears
+    just_reset: bool,                // Flag to prevent 'R' key from entering name after reset
 
     // UI elements
     // scroll_text_x: Arc<Mutex<f32>>, // Commented out - removed wobbling BumbleBee text
@@ -395,14 +501,10 @@ struct Game {
     highscore_scroll_offset: f32, // For scrolling highscore list animation
     background_layers: Vec<BackgroundLayer>,
 
-    // Flying bee animation
-    bee_x: f32,                // Current X position of flying bee
-    bee_y: f32,                // Y position of flying bee
-    bee_active: bool,          // Whether bee is currently flying
-    bee_next_spawn_timer: f32, // Time until next bee spawn
-
     // Mobile touch input
-    touch_shooting: bool,     // Whether player is touching shoot zone
+    touching: bool,   // A finger is on the screen (touch hints instead of keys)
+    touch_used: bool, // The touch screen was used this session (shows the touch zones)
+    show_touch_settings: bool, // Menu shows the touch controls settings
     name_input_focused: bool, // Whether name input is focused (for mobile keyboard)
 
     // Resources
@@ -425,11 +527,12 @@ struct Game {
     retro_font: Option<Font>,
 
     // Audio
-    intro_sound: Option<Sound>,
-    shoot_sound: Option<Sound>,
-    hit_sound: Option<Sound>,
-    background_music: Option<Sound>,
-    bee_sound: Option<Sound>,
+    audio: AudioManager,  // All sounds and music, mixed on buses
+    march: March,         // Beat under the gameplay, faster as the formation closes in
+    playlists: Playlists, // Picks the music for the menu, the waves, the bee and game over
+
+    // Post-processing shader passes over the drawn scene
+    postfx: PostFx,
 
     // Wobble text effect
     time: f32,
@@ -437,9 +540,6 @@ struct Game {
 
 impl Game {
     async fn new() -> Self {
-        #[cfg(target_arch = "wasm32")]
-        println!("Game::new() - Starting resource load");
-
         log::info!("Loading game resources");
 
         #[cfg(target_arch = "wasm32")]
@@ -488,21 +588,15 @@ impl Game {
                 Texture2D::from_rgba8(1024, 575, &[34, 139, 34, 255])
             }); // Forest green fallback
 
-        #[cfg(target_arch = "wasm32")]
-        println!("Loading bg_main.png...");
+        log::debug!("Loading bg_main.png");
 
         let near_field = load_texture_fallback("resources/bg_main.png")
             .await
             .unwrap_or_else(|_| {
                 log::warn!("Failed to load near_field texture, using fallback");
-                #[cfg(target_arch = "wasm32")]
-                println!("WARNING: Failed to load bg_main.png");
                 Texture2D::from_rgba8(1024, 575, &[0, 100, 0, 255])
             }); // Dark green fallback
 
-        #[cfg(target_arch = "wasm32")]
-        println!("Loaded bg_main.png successfully");
-
         let layer_4 = load_texture_fallback("resources/bg_layer_04.png")
             .await
             .unwrap_or_else(|_| {
@@ -592,25 +686,84 @@ impl Game {
         #[cfg(target_arch = "wasm32")]
         update_progress("Loading audio files...");
 
-        #[cfg(target_arch = "wasm32")]
-        println!("Loading audio files...");
+        log::debug!("Loading audio files");
+
+        // Music tracks and their playlists, plus the player's own music on
+        // desktop
+        let manifest = match load_bytes_fallback(MUSIC_MANIFEST_FILE)
+            .await
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+        {
+            Some(json) => Manifest::parse(&json).unwrap_or_else(|err| {
+                log::warn!(
+                    "Invalid {}, using the bundled music: {}",
+                    MUSIC_MANIFEST_FILE,
+                    err
+                );
+                Manifest::default()
+            }),
+            None => Manifest::default(),
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let manifest = {
+            let mut manifest = manifest;
+            manifest.add_user_tracks(&music::user_music_files(&storage::key(USER_MUSIC_DIR)));
+            manifest
+        };
+        let mut music_tracks = Vec::new();
+        for track in &manifest.tracks {
+            let sound = load_sound_fallback(&track.file).await;
+            if sound.is_err() {
+                log::warn!("Failed to load music {}", track.file);
+            }
+            music_tracks.push((track.volume, sound.ok()));
+        }
+        let playlists = Playlists::new(
+            &manifest,
+            (macroquad::miniquad::date::now() * 1000.0) as u32,
+        );
 
-        let intro_sound = load_sound_fallback("resources/intro.ogg").await.ok();
+        let shoot_sound = load_panned_sound_fallback("resources/sfx_shoot.wav").await;
 
-        let shoot_sound = load_sound_fallback("resources/sfx_shoot.wav").await.ok();
+        let hit_sound = load_panned_sound_fallback("resources/sfx_hit.wav").await;
 
-        let hit_sound = load_sound_fallback("resources/sfx_hit.wav").await.ok();
+        // The bee sample also serves as its explosion
+        let bee_sound = load_panned_sound_fallback("resources/sfx_bumblebee.wav").await;
+        // Separate copy for the looping buzz of the flying bee
+        let bee_buzz = load_panned_sound_fallback("resources/sfx_bumblebee.wav").await;
 
-        let background_music = load_sound_fallback("resources/music_background.ogg")
+        // Synthesized effects: the retro pack and the fallback for the
+        // effects without a sample file
+        let synth_data = load_bytes_fallback("resources/sfx_retro.json")
             .await
-            .ok();
+            .and_then(|bytes| String::from_utf8(bytes).ok());
+        let synth_params = synth::clip_params(synth_data.as_deref()).unwrap_or_else(|err| {
+            log::warn!("Invalid sfx_retro.json, using built-in sounds: {}", err);
+            synth::clip_params(None).unwrap_or_default()
+        });
+        let mut retro_sounds = Vec::new();
+        // The bee only buzzes, which gets a copy of its own below
+        for clip in Clip::ALL.into_iter().filter(|&clip| clip != Clip::Bee) {
+            let Some(params) = synth_params.get(&clip) else {
+                continue;
+            };
+            let wav = synth::synthesize(params).to_wav();
+            retro_sounds.push((clip, PannedSound::load(&wav).await));
+        }
+        let retro_buzz = match synth_params.get(&Clip::Bee) {
+            Some(params) => PannedSound::load(&synth::synthesize(params).to_wav()).await,
+            None => None,
+        };
 
-        #[cfg(target_arch = "wasm32")]
-        println!("All audio loaded");
+        // The march notes are synthesized rather than loaded
+        let mut march_notes = Vec::new();
+        for frequency in MARCH_NOTES {
+            if let Ok(sound) = load_sound_from_bytes(&march::note(frequency).to_wav()).await {
+                march_notes.push(sound);
+            }
+        }
 
-        let bee_sound = load_sound_fallback("resources/sfx_bumblebee.wav")
-            .await
-            .ok();
+        log::debug!("Audio loaded");
 
         #[cfg(target_arch = "wasm32")]
         update_progress("Initializing game systems...");
@@ -632,40 +785,108 @@ impl Game {
         #[cfg(target_arch = "wasm32")]
         update_progress("Creating game state...");
 
-        log::info!("Game state created successfully");
+        // Scores stay local unless an online leaderboard server is configured
+        let mut highscore_manager = main_highscore_manager();
+        if let Some(client) = LeaderboardClient::from_env() {
+            log::info!("Using online leaderboard at {}", client.addr());
+            highscore_manager = highscore_manager.with_leaderboard(client);
+        }
+        // Retry runs that couldn't be uploaded last session
+        let leaderboard_sync = highscore_manager.start_sync(5);
+
+        // Pre-fill the name of whoever played last
+        let profile_manager = ProfileManager::new(&storage::key(PROFILES_FILE));
+        let player_name = profile_manager
+            .last_used_name()
+            .unwrap_or_default()
+            .to_string();
+        let controls = ControlsManager::new(&storage::key(CONTROLS_FILE));
+        let keyboard = KeyboardInput::new(controls.bindings(&player_name));
+        let bindings_profile = player_name.clone();
+        let settings = SettingsStore::new(&storage::key(SETTINGS_FILE));
+        let mut audio = AudioManager::new(settings.get())
+            .with_clip(SoundPack::Samples, Clip::Shoot, shoot_sound)
+            .with_clip(SoundPack::Samples, Clip::Hit, hit_sound)
+            .with_clip(SoundPack::Samples, Clip::Explosion, bee_sound)
+            .with_buzz(SoundPack::Samples, bee_buzz)
+            .with_buzz(SoundPack::Retro, retro_buzz)
+            .with_march(march_notes);
+        for (clip, sound) in retro_sounds {
+            audio = audio.with_clip(SoundPack::Retro, clip, sound);
+        }
+        for (volume, sound) in music_tracks {
+            audio = audio.with_track(volume, sound);
+        }
 
-        #[cfg(target_arch = "wasm32")]
-        println!("Game::new() - All resources loaded, creating game state");
+        log::info!("Game state created successfully");
 
         Self {
-            player: Player::new(),
-            bullets: Vec::new(),
-            enemies: generate_wave(1),
+            world: World::new(Difficulty::default(), 1, 0),
+            keyboard,
+            mouse: MouseInput::new(Self::game_position),
+            touch: TouchInput::new(Self::game_position, settings.get().touch),
+            #[cfg(not(target_arch = "wasm32"))]
+            gamepad: GamepadInput::new(),
+            bot: None,
+            playback: None,
+            input: PlayInput::default(),
+            input_frame_time: None,
+            recording: Replay::new(0, Difficulty::default(), 1),
+            paused: false,
             explosions: Vec::new(),
-            bullet_spawn_buffer: Vec::with_capacity(3),
-            collision_results: Vec::new(),
-            enemy_speed: INITIAL_ENEMY_SPEED,
-            bullet_speed: crate::constants::BULLET_SPEED,
-            player_speed: crate::constants::PLAYER_SPEED,
-            descent_speed: 100.0,  // pixels per second for controlled descent
-            descent_distance: 0.0, // current descent progress
-            wave_number: 1,
+            seed: 0,
+            fixed_seed: None,
+            start_wave: 1,
+            telemetry: Telemetry::new(&storage::key(TELEMETRY_FILE)),
             state: GameState::Menu,
-            score: 0,
-            player_name: String::new(),
-            highscore_manager: HighscoreManager::new("highscores.txt"),
+            run_stats: RunStats::new(),
+            player_name,
+            difficulty: settings.get().difficulty,
+            settings,
+            options_selected: 0,
+            profile_manager,
+            controls,
+            bindings_profile,
+            controls_screen: None,
+            show_profile: false,
+            achievements: AchievementManager::new(&storage::key(ACHIEVEMENTS_FILE)),
+            achievement_toasts: Vec::new(),
+            show_achievements: false,
+            show_log: false,
+            debug_tools: cfg!(debug_assertions),
+            show_debug: false,
+            frame_times: FrameTimes::new(),
+            console: Console::new(),
+            cheats_used: false,
+            time_scale: 1.0,
+            highscore_manager,
+            weekly_highscores: HighscoreManager::new(&storage::key("highscores_weekly.txt"))
+                .with_policy(LeaderboardPolicy {
+                    retention: Retention::BestPerPlayer,
+                    window: TimeWindow::ThisWeek,
+                }),
+            daily_highscores: HighscoreManager::new(&storage::key("highscores_daily.txt"))
+                .with_policy(LeaderboardPolicy {
+                    retention: Retention::BestPerPlayer,
+                    window: TimeWindow::Today,
+                }),
+            highscore_window: TimeWindow::AllTime,
+            new_record_rank: None,
+            initials_entry: None,
+            global_scores: None,
+            leaderboard_sync,
+            show_global_scores: false,
+            status_message: None,
+            status_timer: 0.0,
             just_reset: false,
-            intro_playing: false,
             // scroll_text_x: Arc::new(Mutex::new(SCREEN_WIDTH)), // Commented out - removed wobbling BumbleBee text
             // scroll_direction: Arc::new(Mutex::new(-1.0)), // Commented out - removed wobbling BumbleBee text
             // scroll_text_time: 0.0, // Commented out - removed wobbling BumbleBee text
             highscore_scroll_offset: 0.0,
             background_layers,
-            bee_x: SCREEN_WIDTH + 100.0, // Start off-screen to the right
-            bee_y: SCREEN_HEIGHT / 3.0,  // Start at 1/3 screen height
-            bee_active: false,
-            bee_next_spawn_timer: rand::gen_range(BEE_SPAWN_MIN_TIME, BEE_SPAWN_MAX_TIME),
-            touch_shooting: false,
+            touching: false,
+            touch_used: false,
+            show_touch_settings: false,
             name_input_focused: false,
             sky,
             clouds,
@@ -682,41 +903,21 @@ impl Game {
             explosion_frame2,
             explosion_frame3,
             retro_font,
-            intro_sound,
-            shoot_sound,
-            hit_sound,
-            background_music,
-            bee_sound,
+            audio,
+            march: March::new(),
+            playlists,
+            postfx: PostFx::new(),
             time: 0.0,
         }
     }
 
     fn reset(&mut self) {
         log::info!("Resetting game to menu");
-        // Stop background music
-        if let Some(ref sound) = self.background_music {
-            stop_sound(sound);
-        }
-        // Stop intro music
-        if let Some(ref sound) = self.intro_sound {
-            stop_sound(sound);
-            self.intro_playing = false;
-        }
-        self.player.reset();
-        self.bullets.clear();
-        self.bullet_spawn_buffer.clear();
-        self.collision_results.clear();
-        self.enemies = generate_wave(1);
-        self.enemy_speed = INITIAL_ENEMY_SPEED;
-        self.bullet_speed = crate::constants::BULLET_SPEED;
-        self.player_speed = crate::constants::PLAYER_SPEED;
-        self.descent_speed = 100.0;
-        self.descent_distance = 0.0;
-        self.wave_number = 1;
-        self.score = 0;
+        self.world = World::new(self.difficulty, 1, 0);
+        self.playback = None;
+        self.paused = false;
         self.state = GameState::Menu;
         self.just_reset = true; // Skip character input on next frame
-        self.touch_shooting = false;
         self.name_input_focused = false;
         for layer in &mut self.background_layers {
             let texture_width = match layer.layer_type {
@@ -732,7 +933,15 @@ impl Game {
             };
             layer.reset(texture_width);
         }
-        self.player_name.clear();
+        self.player_name = self
+            .profile_manager
+            .last_used_name()
+            .unwrap_or_default()
+            .to_string();
+        self.load_player_bindings();
+
+        // Retry queued uploads and refresh the online leaderboard for the menu
+        self.start_leaderboard_sync();
 
         // let mut text_x = self.scroll_text_x.lock().unwrap(); // Commented out - removed wobbling BumbleBee text
         // *text_x = SCREEN_WIDTH; // Commented out - removed wobbling BumbleBee text
@@ -740,34 +949,89 @@ impl Game {
     }
 
     fn start_game(&mut self) {
-        if !self.player_name.is_empty() {
+        self.load_player_bindings();
+        if self.player_name.is_empty() {
+            log::info!("Starting game without a name, initials are entered on game over");
+        } else {
             log::info!("Starting game for player: {}", self.player_name);
-            // Stop intro music
-            if let Some(ref sound) = self.intro_sound {
-                stop_sound(sound);
-                self.intro_playing = false;
-            }
-            self.state = GameState::Playing;
-            self.score = 0;
-            self.wave_number = 1;
-            self.enemies = generate_wave(1);
-            self.bullets.clear();
-            self.player.reset();
-            self.enemy_speed = INITIAL_ENEMY_SPEED;
-            self.descent_speed = 100.0;
-            self.descent_distance = 0.0;
-            // Start background music
-            if let Some(ref sound) = self.background_music {
-                play_sound(
-                    sound,
-                    PlaySoundParams {
-                        looped: true,
-                        volume: 0.5,
-                    },
-                );
-            }
+        }
+        self.state = GameState::Playing;
+        self.cheats_used = false;
+        self.time_scale = 1.0;
+        // Seed the run so it can be identified (and reproduced) later
+        if let Some(ref mut playback) = self.playback {
+            let replay = playback.replay();
+            log::info!("Playing back a recorded run of {:.0}s", replay.duration());
+            self.recording = Replay::new(replay.seed, replay.difficulty, replay.start_wave);
+            *playback = ReplayInput::new(replay.clone());
+        } else {
+            let seed = self
+                .fixed_seed
+                .unwrap_or_else(|| (macroquad::miniquad::date::now() * 1000.0) as u64);
+            self.recording = Replay::new(seed, self.difficulty, self.start_wave);
+        }
+        self.seed = self.recording.seed;
+        self.difficulty = self.recording.difficulty;
+        // Scattered formations on the web draw from the global generator
+        rand::srand(self.seed);
+        self.world = self.recording.start();
+        self.input = PlayInput::default();
+        self.input_frame_time = None;
+        self.paused = false;
+        self.telemetry
+            .start_run(self.seed, self.recording.start_wave);
+
+        // Reset per-run consumers before the first frame is played
+        self.world.events.emit(GameEvent::RunStarted);
+        self.process_events();
+        self.march.reset();
+    }
+
+    /// Apply command-line settings to a freshly created game. A name or a
+    /// recording to play back starts the run right away.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply_options(&mut self, options: &cli::PlayOptions, playback: Option<Replay>) {
+        self.debug_tools |= options.debug;
+        self.fixed_seed = options.seed;
+        self.start_wave = options.wave.unwrap_or(1);
+        self.difficulty = options.difficulty.unwrap_or(self.difficulty);
+        self.audio.mixer_mut().set_muted(options.mute);
+        if let Some(ref name) = options.name {
+            self.player_name.clone_from(name);
+        }
+
+        if options.bot {
+            self.bot = Some(BotInput::default());
+        }
+
+        if playback.is_some() {
+            self.playback = playback.map(ReplayInput::new);
+            self.start_game();
+        } else if options.name.is_some() {
+            self.start_game();
+        }
+    }
+
+    /// Why the current run stays off the leaderboards, if it does
+    fn unranked_reason(&self) -> Option<Text> {
+        if self.playback.is_some() {
+            Some(Text::ReasonReplay)
+        } else if self.bot.is_some() {
+            Some(Text::ReasonBot)
+        } else if self.cheats_used {
+            Some(Text::ReasonCheats)
+        } else if self.recording.start_wave > 1 {
+            Some(Text::ReasonPractice)
         } else {
-            log::warn!("Cannot start game without player name");
+            None
+        }
+    }
+
+    /// Keep the recording of the finished run for `--replay`
+    fn save_replay(&self) {
+        match self.recording.to_json() {
+            Ok(json) => storage::save(&storage::key(REPLAY_FILE), &json),
+            Err(err) => log::warn!("Failed to encode replay: {}", err),
         }
     }
 
@@ -800,224 +1064,157 @@ impl Game {
         }
     }
 
-    fn shoot(&mut self) {
-        if matches!(self.state, GameState::Playing) {
-            self.bullet_spawn_buffer.clear();
-            self.player.shoot(&mut self.bullet_spawn_buffer);
-            if !self.bullet_spawn_buffer.is_empty() {
-                if let Some(ref sound) = self.shoot_sound {
-                    play_sound_once(sound);
-                }
-                self.bullets.append(&mut self.bullet_spawn_buffer);
-            }
-        }
-    }
+    /// Switch to the game-over screen and record the run.
+    ///
+    /// With a name typed in the menu the score is saved immediately. Without
+    /// one, the player enters arcade initials first and the score is saved
+    /// once they confirm.
+    fn end_run(&mut self) {
+        self.state = GameState::GameOver;
+        // Catch the breaching enemy's final position before closing the stats
+        self.run_stats
+            .update(0.0, self.world.score, &self.world.enemies);
+        self.run_stats.finish(self.world.score);
+        // Career counters are only saved once per run
+        self.achievements.save();
+        self.telemetry.record_run(RunSummary {
+            score: self.world.score,
+            difficulty: self.difficulty,
+            kills: self.run_stats.kills.total(),
+            accuracy: self.run_stats.accuracy(),
+            duration: self.run_stats.duration,
+            start_wave: self.recording.start_wave,
+            unranked: self
+                .unranked_reason()
+                .map(|reason| Language::English.text(reason).to_string()),
+        });
+        self.new_record_rank = None;
+        self.initials_entry = None;
 
-    fn update_bullets(&mut self, dt: f32) {
-        for bullet in &mut self.bullets {
-            bullet.update(dt, self.bullet_speed);
+        // Console commands can't be replayed, and replays aren't recorded again
+        if !self.cheats_used && self.playback.is_none() {
+            self.save_replay();
         }
-        self.bullets.retain(|bullet| !bullet.is_out_of_bounds());
-    }
-
-    fn update_enemies(&mut self, dt: f32) {
-        // Handle gradual descent if active
-        if self.descent_distance > 0.0 {
-            let descent_this_frame = self.descent_speed * dt;
-            if descent_this_frame >= self.descent_distance {
-                // Complete the descent
-                for enemy in &mut self.enemies {
-                    enemy.y += self.descent_distance;
-                }
-                self.descent_distance = 0.0;
-            } else {
-                // Continue descending
-                for enemy in &mut self.enemies {
-                    enemy.y += descent_this_frame;
-                }
-                self.descent_distance -= descent_this_frame;
-            }
-        } else {
-            // Normal horizontal movement when not descending
-            for enemy in &mut self.enemies {
-                enemy.update(self.enemy_speed, dt);
-            }
-
-            // Check if any enemy has reached the edge it's moving toward
-            let mut edge_reached = false;
-            for enemy in &self.enemies {
-                let moving_right = enemy.direction > 0.0;
-                let moving_left = enemy.direction < 0.0;
-
-                if (moving_right && enemy.x >= SCREEN_WIDTH - 20.0)
-                    || (moving_left && enemy.x <= 20.0)
-                {
-                    edge_reached = true;
-                    break;
-                }
-            }
-
-            if edge_reached {
-                log::info!("Enemy reached edge - reversing direction and starting descent");
 
-                // Reverse ALL directions
-                for enemy in &mut self.enemies {
-                    enemy.direction *= -1.0;
-                    // Move back into bounds
-                    if enemy.x < 20.0 {
-                        enemy.x = 20.0;
-                    } else if enemy.x > SCREEN_WIDTH - 20.0 {
-                        enemy.x = SCREEN_WIDTH - 20.0;
-                    }
-                }
-
-                // Start controlled descent for the entire wave
-                self.descent_distance = 40.0;
-            }
+        if let Some(reason) = self.unranked_reason() {
+            log::info!("Run is not recorded: {}", Language::English.text(reason));
+            return;
         }
 
-        // Check if any enemy has breached the defender line
-        for enemy in &self.enemies {
-            if enemy.has_breached_defender_line() {
-                log::warn!("Enemy breached defender line at y={}, game over!", enemy.y);
-                self.state = GameState::GameOver;
-                // Save highscore immediately when game over
-                if !self.player_name.is_empty() && self.score > 0 {
-                    log::info!("Game over! Final score: {}", self.score);
-                    self.highscore_manager
-                        .save_highscore(&self.player_name, self.score);
-                }
-                return;
-            }
+        if !self.player_name.is_empty() {
+            self.record_profile();
         }
-    }
 
-    fn update_explosions(&mut self, dt: f32) {
-        // Update all explosions
-        for explosion in &mut self.explosions {
-            explosion.update(dt);
+        if self.world.score == 0 {
+            return;
         }
 
-        // Remove finished explosions
-        self.explosions.retain(|explosion| !explosion.is_finished());
+        if self.player_name.is_empty() {
+            // Preview the rank so the celebration shows during entry
+            self.new_record_rank = self.highscore_manager.qualifying_rank("", self.world.score);
+            self.initials_entry = Some(InitialsEntry::new());
+        } else {
+            self.save_run();
+        }
     }
 
-    fn update_bee(&mut self, dt: f32) {
-        // Update spawn timer
-        if !self.bee_active {
-            self.bee_next_spawn_timer -= dt;
+    /// Save the finished run under `player_name` on every local leaderboard
+    fn save_run(&mut self) {
+        log::info!("Game over! Final score: {}", self.world.score);
+        self.new_record_rank = self.highscore_manager.save_highscore_with_stats(
+            &self.player_name,
+            self.world.score,
+            Some(&self.run_stats),
+        );
+        self.weekly_highscores
+            .save_highscore(&self.player_name, self.world.score);
+        self.daily_highscores
+            .save_highscore(&self.player_name, self.world.score);
 
-            // Spawn new bee when timer expires
-            if self.bee_next_spawn_timer <= 0.0 {
-                self.bee_active = true;
-                self.bee_x = SCREEN_WIDTH + 100.0; // Start off-screen to the right
-                self.bee_y = rand::gen_range(SCREEN_HEIGHT * 0.2, SCREEN_HEIGHT * 0.5); // Random height in upper portion
-                self.bee_next_spawn_timer = rand::gen_range(BEE_SPAWN_MIN_TIME, BEE_SPAWN_MAX_TIME);
-            }
-        } else {
-            // Move bee from right to left
-            self.bee_x -= BEE_FLY_SPEED * dt;
+        if let Some(rank) = self.new_record_rank {
+            log::info!("New high score for {}: rank #{}", self.player_name, rank);
+        }
+        self.start_leaderboard_sync();
+    }
 
-            // Deactivate bee when it goes off-screen to the left
-            if self.bee_x < -100.0 {
-                self.bee_active = false;
-            }
+    /// Upload queued runs and fetch the global top scores in the
+    /// background, unless that is already going on
+    fn start_leaderboard_sync(&mut self) {
+        if self.leaderboard_sync.is_none() {
+            self.leaderboard_sync = self.highscore_manager.start_sync(5);
         }
     }
 
-    fn update_bee_collisions(&mut self) {
-        if !self.bee_active {
+    /// Pick up the result of the background upload once it is done
+    fn update_leaderboard_sync(&mut self) {
+        let Some(outcome) = self.leaderboard_sync.as_ref().and_then(SyncJob::poll) else {
             return;
+        };
+        self.leaderboard_sync = None;
+        let pending = self.highscore_manager.finish_sync(&outcome);
+        if pending > 0 {
+            log::info!("{} runs wait for the online leaderboard", pending);
         }
+        self.global_scores = outcome.global_scores;
+    }
 
-        // Check collision between bee and each bullet
-        let bee_radius = 50.0; // Half of 100x100 bee size
-        let combined_radius = bee_radius + COLLISION_RADIUS;
-        let combined_radius_sq = combined_radius * combined_radius;
-        let mut bee_hit = false;
-        let bee_pos = (self.bee_x + 50.0, self.bee_y + 50.0); // Center of bee
-
-        // Remove bullets that hit the bee
-        self.bullets.retain(|bullet| {
-            let dx = bullet.x - bee_pos.0;
-            let dy = bullet.y - bee_pos.1;
-            let distance_sq = dx * dx + dy * dy;
-
-            if distance_sq < combined_radius_sq {
-                bee_hit = true;
-                false // Remove this bullet
-            } else {
-                true // Keep this bullet
-            }
-        });
-
-        // If bee was hit, award points, create explosion, and deactivate bee
-        if bee_hit {
-            self.score += BEE_POINTS;
-            log::info!(
-                "Bee hit! Awarded {} points. Total score: {}",
-                BEE_POINTS,
-                self.score
-            );
-
-            // Create large explosion for the bee
-            self.explosions.push(Explosion::new_with_size(
-                self.bee_x + 50.0, // Center X
-                self.bee_y + 50.0, // Center Y
-                100.0,             // Width
-                100.0,             // Height
-            ));
-
-            // Play bee-specific sound
-            if let Some(ref sound) = self.bee_sound {
-                play_sound_once(sound);
-            }
-
-            // Deactivate bee
-            self.bee_active = false;
+    /// Finish arcade initials entry and save the run under the initials
+    fn confirm_initials(&mut self) {
+        if let Some(entry) = self.initials_entry.take() {
+            self.player_name = entry.initials();
+            self.record_profile();
+            self.save_run();
         }
     }
 
-    fn update_collisions(&mut self) {
-        self.collision_results.clear();
-        process_collisions(
-            &mut self.enemies,
-            &mut self.bullets,
-            &mut self.collision_results,
+    /// Add the finished run to the player's lifetime stats
+    fn record_profile(&mut self) {
+        self.profile_manager.record_run(
+            &self.player_name,
+            &self.run_stats,
+            self.world.wave_number,
+            self.world.score,
+            self.difficulty,
         );
+    }
 
-        if !self.collision_results.is_empty() {
-            // Play hit sound
-            if let Some(ref sound) = self.hit_sound {
-                play_sound_once(sound);
-            }
+    /// Switch the menu's profile screen to the next known player
+    fn cycle_profile(&mut self, step: isize) {
+        let names: Vec<String> = self
+            .profile_manager
+            .profiles()
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect();
+        if names.is_empty() {
+            return;
+        }
 
-            // Create explosions and update score for each destroyed enemy
-            for (x, y, points) in self.collision_results.drain(..) {
-                self.explosions.push(Explosion::new(x, y));
-                self.score += points; // Add enemy-specific points
-                log::debug!("Created explosion at ({}, {}) - {} points", x, y, points);
-            }
+        let next = match names.iter().position(|name| *name == self.player_name) {
+            Some(index) => (index as isize + step).rem_euclid(names.len() as isize) as usize,
+            None => 0,
+        };
+        self.player_name = names[next].clone();
+        self.load_player_bindings();
+    }
+
+    /// Local leaderboard currently shown in the menu
+    fn shown_highscores(&self) -> &HighscoreManager {
+        match self.highscore_window {
+            TimeWindow::Today => &self.daily_highscores,
+            TimeWindow::ThisWeek => &self.weekly_highscores,
+            TimeWindow::AllTime => &self.highscore_manager,
         }
     }
 
-    fn check_wave_complete(&mut self) {
-        if self.enemies.is_empty() {
-            self.wave_number += 1;
-            self.enemy_speed += SPEED_INCREASE_PER_WAVE;
-            self.bullet_speed += BULLET_SPEED_INCREASE_PER_WAVE;
-            self.player_speed += PLAYER_SPEED_INCREASE_PER_WAVE;
-            self.player.upgrade();
-            self.enemies = generate_wave(self.wave_number);
-            log::info!(
-                "Wave {} complete! Starting wave {} with enemy speed {}, bullet speed {}, and player speed {}",
-                self.wave_number - 1,
-                self.wave_number,
-                self.enemy_speed,
-                self.bullet_speed,
-                self.player_speed
-            );
+    fn update_explosions(&mut self, dt: f32) {
+        // Update all explosions
+        for explosion in &mut self.explosions {
+            explosion.update(dt);
         }
+
+        // Remove finished explosions
+        self.explosions.retain(|explosion| !explosion.is_finished());
     }
 
     /*
@@ -1067,63 +1264,325 @@ impl Game {
         }
     }
 
+    /// Dispatch the frame's gameplay events to their consumers: explosions,
+    /// audio, run statistics and achievements. A defender breach
+    /// ends the run once all events of the frame are counted.
+    fn process_events(&mut self) {
+        if self.world.events.is_empty() {
+            return;
+        }
+        let mut events = std::mem::take(&mut self.world.events);
+        // Positions of the enemies destroyed this frame, for the hit sound
+        let (mut destroyed_x, mut destroyed) = (0.0, 0);
+        let mut breached = false;
+
+        for event in events.drain() {
+            log::trace!("{:?}", event);
+            match event {
+                GameEvent::ShotFired { x, .. } => self.audio.play_at(Clip::Shoot, Bus::Sfx, x),
+                GameEvent::EnemyDestroyed { x, y, .. } => {
+                    self.explosions.push(Explosion::new(x, y));
+                    destroyed_x += x;
+                    destroyed += 1;
+                }
+                GameEvent::BeeDestroyed { x, y, .. } => {
+                    // Large explosion for the bee
+                    self.explosions
+                        .push(Explosion::new_with_size(x, y, 100.0, 100.0));
+                    self.audio.play_at(Clip::Explosion, Bus::Sfx, x);
+                    self.audio.mixer_mut().duck();
+                }
+                // Every cleared wave upgrades the gun
+                GameEvent::WaveCleared { .. } => self.audio.play(Clip::PowerUp, Bus::Sfx),
+                GameEvent::DefenderBreached { .. } => breached = true,
+                _ => {}
+            }
+            self.run_stats.record_event(&event);
+            self.telemetry.record_event(&event, self.run_stats.duration);
+            self.achievement_event(&event);
+        }
+
+        // One hit sound per frame, however many enemies died, panned to
+        // where they were
+        if destroyed > 0 {
+            self.audio
+                .play_at(Clip::Hit, Bus::Sfx, destroyed_x / destroyed as f32);
+        }
+
+        // Hand the queue back to keep its capacity
+        self.world.events = events;
+
+        if breached {
+            self.end_run();
+        }
+    }
+
+    /// Crossfade to the track the playlists pick for what is going on
+    fn update_music(&mut self) {
+        let scene = match self.state {
+            GameState::Menu | GameState::Options => Scene::Menu,
+            // The march plays on its own
+            GameState::Playing if MARCH_REPLACES_MUSIC => {
+                self.audio.play_music(None, true);
+                return;
+            }
+            GameState::Playing if self.world.bee_active => Scene::Boss,
+            GameState::Playing => Scene::Playing,
+            GameState::GameOver => Scene::GameOver,
+        };
+        match self.playlists.select(scene, self.world.wave_number) {
+            Some((track, looped)) => self.audio.play_music(Some(track), looped),
+            None => self.audio.play_music(None, true),
+        }
+    }
+
+    /// Store changed settings and apply them to the running game
+    fn change_settings(&mut self, settings: Settings) {
+        let old = *self.settings.get();
+        self.settings.set(settings);
+        let settings = *self.settings.get();
+
+        self.audio.mixer_mut().set_volumes(&settings);
+        self.audio.set_pack(settings.sound_pack);
+        if settings.fullscreen != old.fullscreen || settings.window_scale != old.window_scale {
+            set_fullscreen(settings.fullscreen);
+            // The browser decides the size of the web version
+            #[cfg(not(target_arch = "wasm32"))]
+            if !settings.fullscreen {
+                request_new_screen_size(
+                    SCREEN_WIDTH * settings.window_scale,
+                    SCREEN_HEIGHT * settings.window_scale,
+                );
+            }
+        }
+        // Keep a difficulty from the command line until the player picks one
+        if settings.difficulty != old.difficulty {
+            self.difficulty = settings.difficulty;
+        }
+        self.touch.layout = settings.touch;
+    }
+
+    /// Feed a gameplay event to the achievements and announce new unlocks
+    fn achievement_event(&mut self, event: &GameEvent) {
+        if self.unranked_reason().is_some() {
+            return;
+        }
+        for achievement in self.achievements.handle(event) {
+            log::info!("Achievement unlocked: {}", achievement.name);
+            self.audio.play(Clip::PowerUp, Bus::Ui);
+            self.achievement_toasts
+                .push((achievement, ACHIEVEMENT_TOAST_DURATION));
+        }
+    }
+
+    fn update_achievement_toasts(&mut self, dt: f32) {
+        // Only the oldest toast counts down; the others wait their turn
+        if let Some((_, timer)) = self.achievement_toasts.first_mut() {
+            *timer -= dt;
+            if *timer <= 0.0 {
+                self.achievement_toasts.remove(0);
+            }
+        }
+    }
+
+    /// Show a short feedback message in the menu
+    fn set_status_message(&mut self, message: String) {
+        log::info!("{}", message);
+        self.status_message = Some(message);
+        self.status_timer = STATUS_MESSAGE_DURATION;
+    }
+
+    fn update_status_message(&mut self, dt: f32) {
+        if self.status_message.is_some() {
+            self.status_timer -= dt;
+            if self.status_timer <= 0.0 {
+                self.status_message = None;
+            }
+        }
+
+        // Pick up a file chosen in the browser's import dialog
+        #[cfg(target_arch = "wasm32")]
+        if let Some(json) = self.highscore_manager.take_browser_import() {
+            self.finish_import(self.highscore_manager.import_json(&json, ImportMode::Merge));
+        }
+    }
+
+    /// Export the local leaderboard (menu action).
+    ///
+    /// Desktop writes `HIGHSCORE_EXPORT_FILE` next to the game, the web
+    /// version offers the same file as a browser download.
+    fn export_highscores(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = std::path::Path::new(HIGHSCORE_EXPORT_FILE);
+            match self.highscore_manager.export_to_file(path) {
+                Ok(count) => self.set_status_message(format!(
+                    "Exported {} scores to {}",
+                    count,
+                    path.display()
+                )),
+                Err(err) => self.set_status_message(format!("Export failed: {}", err)),
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.highscore_manager
+                .export_to_download(HIGHSCORE_EXPORT_FILE);
+            self.set_status_message("Scores exported".to_string());
+        }
+    }
+
+    /// Merge an exported leaderboard into the local one (menu action).
+    ///
+    /// Desktop reads `HIGHSCORE_IMPORT_FILE`, the web version opens a file
+    /// picker and finishes the import once the browser delivers the file.
+    fn import_highscores(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let result = self.highscore_manager.import_from_file(
+                std::path::Path::new(HIGHSCORE_IMPORT_FILE),
+                ImportMode::Merge,
+            );
+            self.finish_import(result);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        self.highscore_manager.request_browser_import();
+    }
+
+    fn finish_import(&mut self, result: Result<highscore::ImportSummary, String>) {
+        match result {
+            Ok(summary) => self.set_status_message(format!(
+                "Imported {} scores ({} duplicates skipped)",
+                summary.added, summary.duplicates
+            )),
+            Err(err) => self.set_status_message(format!("Import failed: {}", err)),
+        }
+    }
+
     fn update(&mut self, dt: f32) {
+        self.frame_times.push(dt);
+        // Fades and ducking run on real time, also while paused
+        self.audio.update(dt);
+        self.update_music();
+        self.update_leaderboard_sync();
+        // Time of the title and shader animations
+        self.time += dt;
+        // The flying bee buzzes from wherever it is
+        let buzzing = self.state == GameState::Playing
+            && !self.paused
+            && !self.console.is_open()
+            && self.world.bee_active;
+        self.audio
+            .buzz_at(buzzing.then_some(self.world.bee_x + BEE_RADIUS));
+
         match self.state {
-            GameState::Menu => {
+            GameState::Menu | GameState::Options => {
                 self.update_background_scroll(dt);
                 self.update_highscore_scroll(dt);
-                self.time += dt; // Update time for rainbow animation
-                                 // Play intro music if not already playing
-                if !self.intro_playing {
-                    if let Some(ref sound) = self.intro_sound {
-                        play_sound(
-                            sound,
-                            PlaySoundParams {
-                                looped: true,
-                                volume: 0.7,
-                            },
-                        );
-                        self.intro_playing = true;
-                    }
-                }
+                self.update_status_message(dt);
             }
             GameState::Playing => {
+                // The open console pauses the game
+                if self.console.is_open() {
+                    return;
+                }
+                // So does pausing
+                if self.paused {
+                    return;
+                }
+                let dt = self.input_frame_time.take().unwrap_or(dt * self.time_scale);
+                let input = std::mem::take(&mut self.input);
+                self.recording.record(dt, input);
+
                 // Update scrolling background
                 self.update_background_scroll(dt);
 
-                // Update flying bee
-                self.update_bee(dt);
-
                 // Update scrolling text
                 // self.update_scroll_text(dt); // Commented out - removed wobbling BumbleBee text
 
-                // Update bullets
-                self.update_bullets(dt);
-
-                // Check bee collisions (before enemy collisions to remove bullets that hit the bee)
-                self.update_bee_collisions();
+                // Move the player, bee, bullets and enemies and resolve hits
+                self.world.step(dt, &input);
+
+                // March beat, faster as the formation thins out and comes down
+                let lowest_y = self
+                    .world
+                    .enemies
+                    .iter()
+                    .map(|enemy| enemy.y)
+                    .reduce(f32::max);
+                if let Some(note) = self.march.update(dt, self.world.enemies.len(), lowest_y) {
+                    self.audio.play_march(note);
+                }
 
-                // Update enemies
-                self.update_enemies(dt);
+                // Track run statistics
+                self.run_stats
+                    .update(dt, self.world.score, &self.world.enemies);
 
                 // Update explosions
                 self.update_explosions(dt);
 
-                // Process collisions
-                self.update_collisions();
+                // Hand this frame's events to audio, stats and achievements
+                self.process_events();
 
-                // Check if wave is complete
-                self.check_wave_complete();
+                self.update_achievement_toasts(dt);
             }
             GameState::GameOver => {
                 self.update_background_scroll(dt);
-                self.time += dt;
+                self.update_achievement_toasts(dt);
             }
         }
     }
 
+    /// Camera mapping the 1024x575 game area onto the window, letterboxed
+    /// when the window has another size or aspect ratio
+    fn screen_camera() -> Camera2D {
+        let mut camera =
+            Camera2D::from_display_rect(Rect::new(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT));
+        // from_display_rect is meant for render targets, which are upside down
+        camera.zoom.y = -camera.zoom.y;
+        camera.viewport = Some(letterbox(screen_width(), screen_height()));
+        camera
+    }
+
+    /// Game-area position of a mouse or touch position in window pixels
+    fn game_position(position: Vec2) -> Vec2 {
+        Self::screen_camera().screen_to_world(position)
+    }
+
+    /// Post-processing passes for this frame. The C64 wave only has titles
+    /// to draw on the menu and game over screens.
+    fn postfx_passes(&self) -> PassSet {
+        let settings = self.settings.get();
+        let mut passes = self.postfx.usable(settings.postfx, settings.effects);
+        if !matches!(self.state, GameState::Menu | GameState::GameOver) {
+            passes.c64_wave = false;
+        }
+        passes
+    }
+
+    /// Camera the scene is drawn with: the offscreen scene while
+    /// post-processing, the window otherwise
+    fn scene_camera(&self) -> Camera2D {
+        if self.postfx_passes().is_empty() {
+            Self::screen_camera()
+        } else {
+            self.postfx.scene_camera()
+        }
+    }
+
     fn draw(&self) {
         clear_background(BLACK);
+        let passes = self.postfx_passes();
+        if passes.contains(Pass::C64Wave) {
+            set_camera(&self.postfx.titles_camera());
+            clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+        }
+        set_camera(&self.scene_camera());
+        if !passes.is_empty() {
+            clear_background(BLACK);
+        }
 
         match self.state {
             GameState::Menu => {
@@ -1140,11 +1599,45 @@ impl Game {
                 self.draw_touch_indicators(); // Show touch zones when touching
                 self.draw_wave_level();
                 self.draw_score();
+                self.draw_achievement_toasts();
+                if self.paused {
+                    self.draw_paused();
+                }
             }
             GameState::GameOver => {
                 self.draw_background();
                 self.draw_game_over();
+                self.draw_achievement_toasts();
             }
+            GameState::Options => {
+                self.draw_background();
+                self.draw_options();
+            }
+        }
+
+        // Indicators and overlays stay sharp on top of the effects
+        if !passes.is_empty() {
+            self.postfx.present(
+                passes,
+                self.time,
+                self.settings.get().effects,
+                &Self::screen_camera(),
+            );
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.draw_gamepad_indicator();
+        if self.audio.mixer().is_muted() {
+            self.draw_muted_indicator();
+        }
+        if self.show_debug {
+            self.draw_debug_overlay();
+        }
+        if self.show_log {
+            self.draw_log_overlay();
+        }
+        if self.console.is_open() {
+            self.draw_console();
         }
     }
 
@@ -1215,27 +1708,27 @@ impl Game {
     */
 
     fn draw_player(&self) {
-        let player_x = self.player.x - self.player.base_width / 2.0;
-        let player_y = self.player.y();
+        let player_x = self.world.player.x - self.world.player.base_width / 2.0;
+        let player_y = self.world.player.y();
         let player_color = Color::from_rgba(0, 128, 0, 255);
 
         draw_rectangle(
             player_x,
             player_y,
-            self.player.base_width,
-            self.player.height(),
+            self.world.player.base_width,
+            self.world.player.height(),
             player_color,
         );
     }
 
     fn draw_bullets(&self) {
-        for bullet in &self.bullets {
+        for bullet in &self.world.bullets {
             draw_rectangle(bullet.x - 5.0, bullet.y - 10.0, 10.0, 20.0, WHITE);
         }
     }
 
     fn draw_enemies(&self) {
-        for enemy in &self.enemies {
+        for enemy in &self.world.enemies {
             // Different colors for different enemy types
             let color = match enemy.enemy_type {
                 EnemyType::Standard => WHITE, // Standard: White
@@ -1305,58 +1798,43 @@ impl Game {
         }
     }
 
-    fn draw_menu(&self) {
-        // Draw parallax backgrounds
-        self.draw_background();
+    /// Centered title with the C64 rainbow wobble: drawn plainly onto the
+    /// layer of the C64 wave shader when that pass runs, per letter on the
+    /// CPU otherwise. `wobble` is the amplitude (scaled by the screen
+    /// effects setting), spatial frequency and speed of the CPU wobble;
+    /// `colors` the speed of the color cycle and its step per letter.
+    fn draw_c64_title(
+        &self,
+        text: &str,
+        y: f32,
+        font_size: f32,
+        wobble: (f32, f32, f32),
+        colors: (f32, f32),
+    ) {
+        let dims = self.measure_text_retro(text, font_size as u16);
+        let start_x = SCREEN_WIDTH / 2.0 - dims.width / 2.0;
 
-        // Center the main menu content vertically and horizontally
-        let center_x = SCREEN_WIDTH / 2.0;
-        let center_y = SCREEN_HEIGHT / 2.0;
+        if self.postfx_passes().contains(Pass::C64Wave) {
+            set_camera(&self.postfx.titles_camera());
+            self.draw_text_retro(text, start_x, y, font_size, WHITE);
+            set_camera(&self.scene_camera());
+            return;
+        }
 
-        // Debug: print screen dimensions
-        #[cfg(target_arch = "wasm32")]
-        {
-            println!(
-                "WASM Screen dimensions: {}x{} (expected: {}x{})",
-                screen_width(),
-                screen_height(),
-                SCREEN_WIDTH,
-                SCREEN_HEIGHT
-            );
-            println!("WASM Center coordinates: {}x{}", center_x, center_y);
-        }
-
-        // Draw hummel icon on the left side, centered vertically
-        let icon_x = 50.0; // Left margin
-        let icon_y = center_y - self.intro_icon.height() / 2.0; // Center vertically
-        draw_texture(&self.intro_icon, icon_x, icon_y, WHITE);
-
-        // ========================================================================
-        // Title with C64-style rainbow wobble effect (CPU-based)
-        // ========================================================================
-        let title_text = "BUMBLEBEES";
-        let title_font_size = 60.0;
-        let title_dims = self.measure_text_retro(title_text, title_font_size as u16);
-        let title_start_x = center_x - title_dims.width / 2.0;
-        let title_y = 100.0;
+        let (wobble_amplitude, wobble_frequency, wobble_speed) = wobble;
+        let wobble_amplitude = wobble_amplitude * self.settings.get().effects;
+        let (color_speed, color_step) = colors;
         let mut x_offset = 0.0;
-
-        // Wobble parameters
-        let wobble_amplitude = 8.0;
-        let wobble_frequency = 0.12;
-        let wobble_speed = 4.0;
-
-        // Draw each letter with rainbow color cycling and wobble
-        for (i, character) in title_text.chars().enumerate() {
+        for (i, character) in text.chars().enumerate() {
             let char_str = character.to_string();
-            let char_dims = self.measure_text_retro(&char_str, title_font_size as u16);
+            let char_dims = self.measure_text_retro(&char_str, font_size as u16);
 
             // Calculate wobble effect
             let y_offset =
                 (x_offset * wobble_frequency + self.time * wobble_speed).sin() * wobble_amplitude;
 
             // C64-style color cycling
-            let color_offset = self.time * 0.8 + i as f32 * 0.25;
+            let color_offset = self.time * color_speed + i as f32 * color_step;
             let r = ((color_offset * 3.0).sin() * 0.5 + 0.5) * 255.0;
             let g = ((color_offset * 3.0 + 2.094).sin() * 0.5 + 0.5) * 255.0;
             let b = ((color_offset * 3.0 + 4.189).sin() * 0.5 + 0.5) * 255.0;
@@ -1364,26 +1842,60 @@ impl Game {
 
             self.draw_text_retro(
                 &char_str,
-                title_start_x + x_offset,
-                title_y + y_offset,
-                title_font_size,
+                start_x + x_offset,
+                y + y_offset,
+                font_size,
                 rainbow_color,
             );
 
             x_offset += char_dims.width;
         }
+    }
+
+    fn draw_menu(&self) {
+        // Draw parallax backgrounds
+        self.draw_background();
+
+        // Center the main menu content vertically and horizontally
+        let center_x = SCREEN_WIDTH / 2.0;
+        let center_y = SCREEN_HEIGHT / 2.0;
+
+        // Debug: log screen dimensions (every frame, hence trace)
+        #[cfg(target_arch = "wasm32")]
+        {
+            log::trace!(
+                "WASM Screen dimensions: {}x{} (expected: {}x{})",
+                screen_width(),
+                screen_height(),
+                SCREEN_WIDTH,
+                SCREEN_HEIGHT
+            );
+            log::trace!("WASM Center coordinates: {}x{}", center_x, center_y);
+        }
+
+        // Draw hummel icon on the left side, centered vertically
+        let icon_x = 50.0; // Left margin
+        let icon_y = center_y - self.intro_icon.height() / 2.0; // Center vertically
+        draw_texture(&self.intro_icon, icon_x, icon_y, WHITE);
+
+        // Title with C64-style rainbow wobble effect
+        self.draw_c64_title("BUMBLEBEES", 100.0, 60.0, (8.0, 0.12, 4.0), (0.8, 0.25));
+
         // Main menu panel - centered horizontally on screen
         let panel_width = 320.0;
         let panel_height = 200.0;
         let panel_x = center_x - panel_width / 2.0;
         let panel_y = center_y - panel_height / 2.0;
 
-        // Debug: print panel position
+        // Debug: log panel position
         #[cfg(target_arch = "wasm32")]
         {
-            println!(
+            log::trace!(
                 "WASM Panel position: x={}, y={}, w={}, h={}",
-                panel_x, panel_y, panel_width, panel_height
+                panel_x,
+                panel_y,
+                panel_width,
+                panel_height
             );
         }
 
@@ -1398,7 +1910,8 @@ impl Game {
         draw_rectangle_lines(panel_x, panel_y, panel_width, panel_height, 2.0, BLACK);
 
         // Name input section inside panel
-        let label_text = "Enter Your Name:";
+        let language = self.settings.get().language;
+        let label_text = language.text(Text::EnterName);
         let label_dims = self.measure_text_retro(label_text, 20);
         let label_x = panel_x + (panel_width - label_dims.width) / 2.0; // Center within panel
         self.draw_text_retro(label_text, label_x, panel_y + 25.0, 20.0, BLACK);
@@ -1430,11 +1943,11 @@ impl Game {
             self.draw_text_retro(&self.player_name, name_x, name_y, font_size, BLACK);
         } else {
             // Center placeholder text both horizontally and vertically in the box
-            let placeholder = if touches().is_empty() {
-                "Type your name..."
+            let placeholder = language.text(if !self.touching {
+                Text::TypeName
             } else {
-                "Tap to enter name..."
-            };
+                Text::TapName
+            });
             let placeholder_font_size = 22.0;
             let placeholder_dims =
                 self.measure_text_retro(placeholder, placeholder_font_size as u16);
@@ -1472,7 +1985,7 @@ impl Game {
         draw_rectangle_lines(button_x, button_y, button_width, button_height, 2.0, BLACK);
 
         // Button text - properly centered
-        let button_text = "START GAME";
+        let button_text = language.text(Text::StartGame);
         let button_font_size = 24.0;
         let button_text_dims = self.measure_text_retro(button_text, button_font_size as u16);
         let button_text_x = button_x + (button_width - button_text_dims.width) / 2.0;
@@ -1486,217 +1999,1707 @@ impl Game {
             WHITE,
         );
 
+        // Difficulty selector below the button, changed with left/right
+        let difficulty_text = format!("< {} >", self.difficulty.name());
+        let difficulty_dims = self.measure_text_retro(&difficulty_text, 18);
+        self.draw_text_retro(
+            &difficulty_text,
+            panel_x + (panel_width - difficulty_dims.width) / 2.0,
+            button_y + button_height + 22.0,
+            18.0,
+            BLACK,
+        );
+
+        // Options and touch controls settings buttons below the panel
+        for (button, text) in [
+            (Self::options_button_rect(), Text::Options),
+            (Self::touch_settings_button_rect(), Text::TouchControls),
+        ] {
+            draw_rectangle(
+                button.x,
+                button.y,
+                button.w,
+                button.h,
+                Color::from_rgba(255, 255, 255, 200),
+            );
+            draw_rectangle_lines(button.x, button.y, button.w, button.h, 2.0, BLACK);
+            let text = language.text(text);
+            let dims = self.measure_text_retro(text, 16);
+            self.draw_text_retro(
+                text,
+                button.center().x - dims.width / 2.0,
+                button.y + 22.0,
+                16.0,
+                BLACK,
+            );
+        }
+
         // Highscores section - aligned with name entry panel
         let highscore_x = SCREEN_WIDTH - 300.0;
         let highscore_y = panel_y; // Align with the name entry panel
 
-        // Highscores header
+        if self.show_profile {
+            self.draw_profile(highscore_x, highscore_y);
+        } else if self.show_global_scores {
+            self.draw_global_scores(highscore_x, highscore_y);
+        } else {
+            // Highscores header
+            let header = match self.highscore_window {
+                TimeWindow::Today => "TODAY'S BEST",
+                TimeWindow::ThisWeek => "WEEKLY BEST",
+                TimeWindow::AllTime => "HIGH SCORES",
+            };
+            self.draw_text_retro(header, highscore_x + 10.0, highscore_y + 10.0, 24.0, BLACK);
+
+            // Display highscores
+            let top_scores = self.shown_highscores().get_top_scores(5); // Show only top 5
+            for (i, entry) in top_scores.iter().enumerate() {
+                let y_pos = highscore_y + 35.0 + i as f32 * 25.0;
+
+                // Scores that failed signature verification are greyed out and marked
+                if entry.verified {
+                    let score_text = format!("{}. {} - {}", i + 1, entry.name, entry.score);
+                    self.draw_text_retro(&score_text, highscore_x + 10.0, y_pos, 18.0, BLACK);
+                } else {
+                    let score_text = format!("{}. {} - {} *", i + 1, entry.name, entry.score);
+                    self.draw_text_retro(
+                        &score_text,
+                        highscore_x + 10.0,
+                        y_pos,
+                        18.0,
+                        Color::from_rgba(120, 120, 120, 255),
+                    );
+                }
+            }
+
+            if top_scores.iter().any(|entry| !entry.verified) {
+                let y_pos = highscore_y + 35.0 + top_scores.len() as f32 * 25.0;
+                self.draw_text_retro(
+                    "* unverified score",
+                    highscore_x + 10.0,
+                    y_pos,
+                    14.0,
+                    Color::from_rgba(120, 120, 120, 255),
+                );
+            }
+        }
+
+        self.draw_text_retro(
+            &format!(
+                "{}: today/week/all time",
+                self.key_label(Action::CycleScoreWindow)
+            ),
+            highscore_x + 10.0,
+            highscore_y + 230.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+
+        self.draw_text_retro(
+            &format!(
+                "{}: export  {}: import scores",
+                self.key_label(Action::ExportScores),
+                self.key_label(Action::ImportScores)
+            ),
+            highscore_x + 10.0,
+            highscore_y + 210.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+
         self.draw_text_retro(
-            "HIGH SCORES",
+            &format!(
+                "{}: achievements  {}: profile",
+                self.key_label(Action::ToggleAchievements),
+                self.key_label(Action::ToggleProfile)
+            ),
             highscore_x + 10.0,
-            highscore_y + 10.0,
+            highscore_y + 250.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+
+        let telemetry_hint = format!(
+            "{}: telemetry ({})",
+            self.key_label(Action::ToggleTelemetry),
+            if self.telemetry.is_enabled() {
+                "on"
+            } else {
+                "off"
+            }
+        );
+        self.draw_text_retro(
+            &telemetry_hint,
+            highscore_x + 10.0,
+            highscore_y + 270.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+
+        self.draw_text_retro(
+            &format!(
+                "{}: controls - {}: options",
+                self.key_label(Action::ToggleControls),
+                self.key_label(Action::Options)
+            ),
+            highscore_x + 10.0,
+            highscore_y + 290.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+
+        if let Some(ref message) = self.status_message {
+            let dims = self.measure_text_retro(message, 18);
+            self.draw_text_retro(
+                message,
+                center_x - dims.width / 2.0,
+                SCREEN_HEIGHT - 40.0,
+                18.0,
+                BLACK,
+            );
+        }
+
+        if self.highscore_manager.has_leaderboard() {
+            let toggle_text = format!(
+                "{}: {} scores",
+                self.key_label(Action::ToggleScores),
+                if self.show_global_scores {
+                    "local"
+                } else {
+                    "global"
+                }
+            );
+            self.draw_text_retro(
+                &toggle_text,
+                highscore_x + 10.0,
+                highscore_y + 190.0,
+                14.0,
+                Color::from_rgba(60, 60, 60, 255),
+            );
+        }
+
+        if self.show_achievements {
+            self.draw_achievement_gallery();
+        }
+        if let Some(ref screen) = self.controls_screen {
+            self.draw_controls_screen(screen);
+        }
+        if self.show_touch_settings {
+            self.draw_touch_settings();
+        }
+    }
+
+    /// First key bound to `action`, for on-screen hints
+    fn key_label(&self, action: Action) -> String {
+        self.keyboard
+            .bindings()
+            .keys(action)
+            .first()
+            .map_or_else(|| "-".to_string(), |&key| key_name(key))
+    }
+
+    /// Every action with its keys over the menu, the selected one highlighted
+    fn draw_controls_screen(&self, screen: &ControlsScreen) {
+        let width = 560.0;
+        let row_height = 19.0;
+        let height = 95.0 + Action::ALL.len() as f32 * row_height;
+        let x = SCREEN_WIDTH / 2.0 - width / 2.0;
+        let y = SCREEN_HEIGHT / 2.0 - height / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::from_rgba(255, 255, 255, 235));
+        draw_rectangle_lines(x, y, width, height, 2.0, BLACK);
+
+        let header = if self.player_name.is_empty() {
+            "CONTROLS".to_string()
+        } else {
+            format!("CONTROLS - {}", self.player_name)
+        };
+        self.draw_text_retro(&header, x + 20.0, y + 32.0, 24.0, BLACK);
+
+        let bindings = self.keyboard.bindings();
+        for (i, &action) in Action::ALL.iter().enumerate() {
+            let row_y = y + 60.0 + i as f32 * row_height;
+            if i == screen.selected {
+                draw_rectangle(
+                    x + 10.0,
+                    row_y - 14.0,
+                    width - 20.0,
+                    row_height,
+                    Color::from_rgba(255, 220, 0, 160),
+                );
+            }
+            self.draw_text_retro(action.label(), x + 20.0, row_y, 16.0, BLACK);
+
+            let keys = if i == screen.selected && screen.capturing {
+                "press a key...".to_string()
+            } else {
+                bindings
+                    .keys(action)
+                    .into_iter()
+                    .map(key_name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            self.draw_text_retro(&keys, x + 260.0, row_y, 16.0, BLACK);
+        }
+
+        let footer = match screen.message {
+            Some(ref message) => message.clone(),
+            None => format!(
+                "{}: add key  {}: remove key  {}: defaults  {}: close",
+                self.key_label(Action::Confirm),
+                self.key_label(Action::Erase),
+                self.key_label(Action::Restart),
+                self.key_label(Action::Back)
+            ),
+        };
+        self.draw_text_retro(
+            &footer,
+            x + 20.0,
+            y + height - 14.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+    }
+
+    /// Draw all achievements over the menu, locked ones greyed out
+    fn draw_achievement_gallery(&self) {
+        let width = 640.0;
+        let height = 80.0 + ACHIEVEMENTS.len() as f32 * 60.0;
+        let x = SCREEN_WIDTH / 2.0 - width / 2.0;
+        let y = SCREEN_HEIGHT / 2.0 - height / 2.0;
+
+        draw_rectangle(x, y, width, height, Color::from_rgba(255, 255, 255, 235));
+        draw_rectangle_lines(x, y, width, height, 2.0, BLACK);
+
+        let header = format!(
+            "ACHIEVEMENTS {}/{}",
+            self.achievements.unlocked_count(),
+            ACHIEVEMENTS.len()
+        );
+        self.draw_text_retro(&header, x + 20.0, y + 35.0, 24.0, BLACK);
+
+        let grey = Color::from_rgba(150, 150, 150, 255);
+        for (i, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            let row_y = y + 75.0 + i as f32 * 60.0;
+            let unlocked = self.achievements.is_unlocked(achievement.id);
+            let (name_color, text_color) = if unlocked {
+                (GOLD, BLACK)
+            } else {
+                (grey, grey)
+            };
+
+            self.draw_text_retro(achievement.name, x + 20.0, row_y, 20.0, name_color);
+            let description = match self.achievements.progress(achievement) {
+                Some((current, target)) if !unlocked => {
+                    format!("{} ({}/{})", achievement.description, current, target)
+                }
+                _ => achievement.description.to_string(),
+            };
+            self.draw_text_retro(&description, x + 20.0, row_y + 22.0, 16.0, text_color);
+        }
+
+        self.draw_text_retro(
+            "F3: close",
+            x + width - 100.0,
+            y + 35.0,
+            14.0,
+            Color::from_rgba(60, 60, 60, 255),
+        );
+    }
+
+    /// Draw the lifetime stats of the player named in the menu in place of
+    /// the highscore list
+    fn draw_profile(&self, x: f32, y: f32) {
+        self.draw_text_retro("PLAYER PROFILE", x + 10.0, y + 10.0, 24.0, BLACK);
+
+        let grey = Color::from_rgba(120, 120, 120, 255);
+        let Some(profile) = self.profile_manager.profile(&self.player_name) else {
+            let text = if self.player_name.is_empty() {
+                "No name entered"
+            } else {
+                "No games played yet"
+            };
+            self.draw_text_retro(text, x + 10.0, y + 35.0, 18.0, grey);
+            return;
+        };
+
+        let minutes = (profile.play_time / 60.0) as u32;
+        let favorite = profile.favorite_difficulty().map_or("-", Difficulty::name);
+        let lines = [
+            profile.name.clone(),
+            format!("Games: {}", profile.games_played),
+            format!("Kills: {}", profile.total_kills),
+            format!("Best wave: {}", profile.best_wave),
+            format!("Best score: {}", profile.best_score),
+            format!("Play time: {}h {:02}m", minutes / 60, minutes % 60),
+            format!("Favorite: {}", favorite),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_text_retro(line, x + 10.0, y + 35.0 + i as f32 * 20.0, 16.0, BLACK);
+        }
+
+        if self.profile_manager.profiles().len() > 1 {
+            self.draw_text_retro("UP/DOWN: other players", x + 10.0, y + 175.0, 14.0, grey);
+        }
+    }
+
+    /// Draw the online top scores in place of the local highscore list
+    fn draw_global_scores(&self, x: f32, y: f32) {
+        self.draw_text_retro("GLOBAL SCORES", x + 10.0, y + 10.0, 24.0, BLACK);
+
+        match self.global_scores {
+            Some(ref scores) if !scores.is_empty() => {
+                for (i, record) in scores.iter().enumerate() {
+                    let score_text = format!("{}. {} - {}", i + 1, record.name, record.score);
+                    let y_pos = y + 35.0 + i as f32 * 25.0;
+                    self.draw_text_retro(&score_text, x + 10.0, y_pos, 18.0, BLACK);
+                }
+            }
+            Some(_) => {
+                self.draw_text_retro("No scores yet", x + 10.0, y + 35.0, 18.0, BLACK);
+            }
+            None => {
+                self.draw_text_retro(
+                    "Leaderboard offline",
+                    x + 10.0,
+                    y + 35.0,
+                    18.0,
+                    Color::from_rgba(120, 120, 120, 255),
+                );
+            }
+        }
+    }
+
+    fn draw_game_over(&self) {
+        let language = self.settings.get().language;
+        let game_over_text = language.text(Text::GameOver);
+        self.draw_c64_title(game_over_text, 200.0, 80.0, (10.0, 0.1, 5.0), (1.0, 0.3));
+
+        // ========================================================================
+        // Additional UI elements (score, instructions)
+        // ========================================================================
+        if let Some(rank) = self.new_record_rank {
+            self.draw_new_record(rank);
+        }
+
+        if let Some(reason) = self.unranked_reason() {
+            let cheat_text = format!(
+                "{} - {}",
+                language.text(reason).to_uppercase(),
+                language.text(Text::RunNotRecorded)
+            );
+            let cheat_dims = self.measure_text_retro(&cheat_text, 24);
+            self.draw_text_retro(
+                &cheat_text,
+                SCREEN_WIDTH / 2.0 - cheat_dims.width / 2.0,
+                120.0,
+                24.0,
+                RED,
+            );
+        }
+
+        let score_text = format!("{}: {}", language.text(Text::FinalScore), self.world.score);
+        let score_dims = self.measure_text_retro(&score_text, 50);
+        self.draw_text_retro(
+            &score_text,
+            SCREEN_WIDTH / 2.0 - score_dims.width / 2.0,
+            300.0,
+            50.0,
+            BLACK,
+        );
+
+        if let Some(ref entry) = self.initials_entry {
+            self.draw_initials_entry(entry);
+            return;
+        }
+
+        self.draw_run_stats(40.0, 325.0);
+        self.draw_score_graph(560.0, 325.0, 424.0, 190.0);
+
+        let return_text = if !self.touching {
+            format!(
+                "{}: {}",
+                self.key_label(Action::Restart),
+                language.text(Text::ReturnToMenu)
+            )
+        } else {
+            language.text(Text::TapToReturn).to_string()
+        };
+        let return_dims = self.measure_text_retro(&return_text, 24);
+        self.draw_text_retro(
+            &return_text,
+            SCREEN_WIDTH / 2.0 - return_dims.width / 2.0,
+            555.0,
             24.0,
+            Color::from_rgba(0, 0, 0, 255),
+        );
+    }
+
+    /// Summary of the finished run's statistics
+    fn draw_run_stats(&self, x: f32, y: f32) {
+        let stats = &self.run_stats;
+        let language = self.settings.get().language;
+        let text = |key| language.text(key);
+        draw_rectangle(x, y, 500.0, 190.0, Color::from_rgba(255, 255, 255, 200));
+        draw_rectangle_lines(x, y, 500.0, 190.0, 2.0, BLACK);
+
+        let minutes = (stats.duration / 60.0) as u32;
+        let seconds = stats.duration as u32 % 60;
+        let closest = stats
+            .closest_approach
+            .map_or_else(|| "-".to_string(), |distance| format!("{:.0}px", distance));
+        // Only the most recent waves fit on one line
+        let recent_waves = stats
+            .wave_times
+            .iter()
+            .enumerate()
+            .rev()
+            .take(4)
+            .rev()
+            .map(|(i, time)| format!("{}{} {:.0}s", text(Text::WaveShort), i + 1, time))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        let lines = [
+            format!(
+                "{}: {}  {}: {}  {}: {:.0}%",
+                text(Text::Shots),
+                stats.shots_fired,
+                text(Text::Hits),
+                stats.hits,
+                text(Text::Accuracy),
+                stats.accuracy() * 100.0
+            ),
+            format!(
+                "{}: {}  ({} {}  {} {}  {} {}  {} {})",
+                text(Text::Kills),
+                stats.kills.total(),
+                text(Text::EnemyStandard),
+                stats.kills.get(EnemyType::Standard),
+                text(Text::EnemyFast),
+                stats.kills.get(EnemyType::Fast),
+                text(Text::EnemyTank),
+                stats.kills.get(EnemyType::Tank),
+                text(Text::EnemySwooper),
+                stats.kills.get(EnemyType::Swooper)
+            ),
+            format!(
+                "{}: {}  {}: {}",
+                text(Text::Bees),
+                stats.bees_destroyed,
+                text(Text::BestCombo),
+                stats.highest_combo
+            ),
+            format!("{}: {}", text(Text::ClosestCall), closest),
+            format!(
+                "{}: {}:{:02}  {}: {}",
+                text(Text::Time),
+                minutes,
+                seconds,
+                text(Text::Waves),
+                recent_waves
+            ),
+        ];
+
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_text_retro(line, x + 12.0, y + 32.0 + i as f32 * 34.0, 18.0, BLACK);
+        }
+    }
+
+    /// Line graph of the score sampled over the run
+    fn draw_score_graph(&self, x: f32, y: f32, width: f32, height: f32) {
+        draw_rectangle(x, y, width, height, Color::from_rgba(255, 255, 255, 200));
+        draw_rectangle_lines(x, y, width, height, 2.0, BLACK);
+        let title = self.settings.get().language.text(Text::ScoreOverTime);
+        self.draw_text_retro(title, x + 10.0, y + 22.0, 16.0, BLACK);
+
+        // Plot area inside the frame, below the title
+        let left = x + 10.0;
+        let right = x + width - 10.0;
+        let top = y + 35.0;
+        let bottom = y + height - 10.0;
+
+        let samples = &self.run_stats.score_samples;
+        let max_score = samples.iter().copied().max().unwrap_or(0).max(1) as f32;
+        let step = (right - left) / samples.len().max(1) as f32;
+
+        // Start at zero score on the left edge, one point per sample
+        let mut previous = Vec2::new(left, bottom);
+        for (i, &score) in samples.iter().enumerate() {
+            let point = Vec2::new(
+                left + step * (i + 1) as f32,
+                bottom - (bottom - top) * score as f32 / max_score,
+            );
+            draw_line(
+                previous.x,
+                previous.y,
+                point.x,
+                point.y,
+                2.0,
+                Color::from_rgba(0, 150, 0, 255),
+            );
+            previous = point;
+        }
+
+        draw_line(left, bottom, right, bottom, 1.0, BLACK);
+        draw_line(left, top, left, bottom, 1.0, BLACK);
+    }
+
+    /// Banner between title and score: a flashing "NEW HIGH SCORE" for
+    /// the top spot, a plain line with the rank for the rest of the board
+    fn draw_new_record(&self, rank: usize) {
+        let language = self.settings.get().language;
+        if rank > 1 {
+            let text = format!("{} #{}", language.text(Text::MadeLeaderboard), rank);
+            let dims = self.measure_text_retro(&text, 28);
+            self.draw_text_retro(
+                &text,
+                SCREEN_WIDTH / 2.0 - dims.width / 2.0,
+                250.0,
+                28.0,
+                Color::from_rgba(0, 120, 0, 255),
+            );
+            return;
+        }
+
+        let text = language.text(Text::NewHighScore);
+        let font_size = 32.0;
+        let dims = self.measure_text_retro(text, font_size as u16);
+
+        // Flash between gold and white, with a slight pulse in size
+        let flash = (self.time * 8.0).sin() * 0.5 + 0.5;
+        let color = Color::new(1.0, 0.8 + 0.2 * flash, flash, 1.0);
+        let pulse = 1.0 + (self.time * 4.0).sin() * 0.05;
+
+        self.draw_text_retro(
+            text,
+            SCREEN_WIDTH / 2.0 - dims.width * pulse / 2.0,
+            250.0,
+            font_size * pulse,
+            color,
+        );
+    }
+
+    /// Screen rectangle of an initials slot (shared by drawing and touch input)
+    fn initials_slot_rect(slot: usize) -> Rect {
+        let width = 50.0;
+        let spacing = 70.0;
+        let first_x = SCREEN_WIDTH / 2.0 - spacing * (INITIALS_LEN as f32 - 1.0) / 2.0;
+        Rect::new(
+            first_x + spacing * slot as f32 - width / 2.0,
+            340.0,
+            width,
+            60.0,
+        )
+    }
+
+    /// Screen rectangle of the initials OK button
+    fn initials_ok_rect() -> Rect {
+        Rect::new(SCREEN_WIDTH / 2.0 - 70.0, 470.0, 140.0, 40.0)
+    }
+
+    /// Three letter slots with up/down markers, instructions and OK button
+    fn draw_initials_entry(&self, entry: &InitialsEntry) {
+        let language = self.settings.get().language;
+        let initials = entry.initials();
+
+        for (slot, letter) in initials.chars().enumerate() {
+            let rect = Self::initials_slot_rect(slot);
+            let selected = slot == entry.cursor();
+            let (fill, border) = if selected {
+                (Color::from_rgba(255, 220, 0, 230), BLACK)
+            } else {
+                (
+                    Color::from_rgba(255, 255, 255, 200),
+                    Color::from_rgba(80, 80, 80, 255),
+                )
+            };
+
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, border);
+
+            let letter = letter.to_string();
+            let dims = self.measure_text_retro(&letter, 40);
+            self.draw_text_retro(
+                &letter,
+                rect.x + (rect.w - dims.width) / 2.0,
+                rect.y + rect.h / 2.0 + 15.0,
+                40.0,
+                BLACK,
+            );
+
+            if selected {
+                let center_x = rect.x + rect.w / 2.0;
+                draw_triangle(
+                    Vec2::new(center_x, rect.y - 14.0),
+                    Vec2::new(center_x - 10.0, rect.y - 4.0),
+                    Vec2::new(center_x + 10.0, rect.y - 4.0),
+                    BLACK,
+                );
+                draw_triangle(
+                    Vec2::new(center_x, rect.y + rect.h + 14.0),
+                    Vec2::new(center_x - 10.0, rect.y + rect.h + 4.0),
+                    Vec2::new(center_x + 10.0, rect.y + rect.h + 4.0),
+                    BLACK,
+                );
+            }
+        }
+
+        let help_text = if !self.touching {
+            format!(
+                "{}/{}: {}  {}/{}: {}  {}: {}",
+                self.key_label(Action::Up),
+                self.key_label(Action::Down),
+                language.text(Text::InitialsLetter),
+                self.key_label(Action::MoveLeft),
+                self.key_label(Action::MoveRight),
+                language.text(Text::InitialsMove),
+                self.key_label(Action::Confirm),
+                language.text(Text::InitialsDone)
+            )
+        } else {
+            language.text(Text::InitialsTapHelp).to_string()
+        };
+        let help_dims = self.measure_text_retro(&help_text, 18);
+        self.draw_text_retro(
+            &help_text,
+            SCREEN_WIDTH / 2.0 - help_dims.width / 2.0,
+            450.0,
+            18.0,
             BLACK,
         );
 
-        // Display highscores
-        let top_scores = self.highscore_manager.get_top_scores(5); // Show only top 5
-        for (i, entry) in top_scores.iter().enumerate() {
-            let score_text = format!("{}. {} - {}", i + 1, entry.name, entry.score);
-            let y_pos = highscore_y + 35.0 + i as f32 * 25.0;
+        let ok = Self::initials_ok_rect();
+        draw_rectangle(ok.x, ok.y, ok.w, ok.h, Color::from_rgba(0, 150, 0, 255));
+        draw_rectangle_lines(ok.x, ok.y, ok.w, ok.h, 2.0, BLACK);
+        let ok_text = language.text(Text::Ok);
+        let ok_dims = self.measure_text_retro(ok_text, 24);
+        self.draw_text_retro(
+            ok_text,
+            ok.x + (ok.w - ok_dims.width) / 2.0,
+            ok.y + ok.h / 2.0 + 8.0,
+            24.0,
+            WHITE,
+        );
+    }
+
+    fn draw_score(&self) {
+        let label = self.settings.get().language.text(Text::Score);
+        let score_text = format!("{}: {}", label, self.world.score);
+
+        // Use fixed position based on maximum expected score width to prevent jumping
+        // Reserve space for "Score: 99999" to keep position stable
+        let max_score_text = format!("{}: 99999", label);
+        let max_text_dims = self.measure_text_retro(&max_score_text, 32);
+        let padding = 20.0;
+        let x_pos = SCREEN_WIDTH - max_text_dims.width - padding;
+
+        // Draw shadow for bold effect
+        self.draw_text_retro(
+            &score_text,
+            x_pos + 2.0,
+            42.0,
+            32.0,
+            Color::from_rgba(0, 0, 0, 128),
+        );
+
+        // Draw main text in red with larger font for bold effect
+        self.draw_text_retro(&score_text, x_pos, 40.0, 32.0, RED);
+    }
+
+    /// Dim the frozen run and explain how to continue
+    fn draw_paused(&self) {
+        draw_rectangle(
+            0.0,
+            0.0,
+            SCREEN_WIDTH,
+            SCREEN_HEIGHT,
+            Color::from_rgba(0, 0, 0, 140),
+        );
+
+        let language = self.settings.get().language;
+        let title = language.text(Text::Paused);
+        let title_dims = self.measure_text_retro(title, 60);
+        self.draw_text_retro(
+            title,
+            SCREEN_WIDTH / 2.0 - title_dims.width / 2.0,
+            SCREEN_HEIGHT / 2.0,
+            60.0,
+            YELLOW,
+        );
+
+        let hint = format!(
+            "{}: {} - {}: {}",
+            self.key_label(Action::Pause),
+            language.text(Text::Continue),
+            self.key_label(Action::Back),
+            language.text(Text::QuitToMenu)
+        );
+        let hint_dims = self.measure_text_retro(&hint, 24);
+        self.draw_text_retro(
+            &hint,
+            SCREEN_WIDTH / 2.0 - hint_dims.width / 2.0,
+            SCREEN_HEIGHT / 2.0 + 50.0,
+            24.0,
+            WHITE,
+        );
+    }
+
+    fn draw_wave_level(&self) {
+        let wave_text = format!(
+            "{}: {}",
+            self.settings.get().language.text(Text::EnemyRound),
+            self.world.wave_number
+        );
+        let padding = 20.0;
+
+        // Draw shadow for bold effect
+        self.draw_text_retro(
+            &wave_text,
+            padding + 2.0,
+            42.0,
+            32.0,
+            Color::from_rgba(0, 0, 0, 128),
+        );
+
+        // Draw main text in blue
+        self.draw_text_retro(&wave_text, padding, 40.0, 32.0, BLUE);
+    }
+
+    /// Slide-in notification for the oldest unannounced achievement
+    fn draw_achievement_toasts(&self) {
+        let Some((achievement, timer)) = self.achievement_toasts.first() else {
+            return;
+        };
+
+        let width = 420.0;
+        let height = 60.0;
+        // Slide down during the first and up during the last 0.3 seconds
+        let shown = ACHIEVEMENT_TOAST_DURATION - timer;
+        let slide = (shown.min(*timer) / 0.3).min(1.0);
+        let x = SCREEN_WIDTH / 2.0 - width / 2.0;
+        let y = -height + slide * (height + 60.0);
+
+        draw_rectangle(x, y, width, height, Color::from_rgba(255, 255, 255, 220));
+        draw_rectangle_lines(x, y, width, height, 2.0, GOLD);
+        self.draw_text_retro("ACHIEVEMENT UNLOCKED", x + 12.0, y + 22.0, 16.0, GOLD);
+        self.draw_text_retro(achievement.name, x + 12.0, y + 48.0, 24.0, BLACK);
+    }
 
-            self.draw_text_retro(&score_text, highscore_x + 10.0, y_pos, 18.0, BLACK);
+    /// Hitboxes and formation geometry during play, plus a panel with
+    /// counters, frame times and the current tunables
+    fn draw_debug_overlay(&self) {
+        if self.state == GameState::Playing {
+            self.draw_debug_geometry();
         }
+        self.draw_debug_panel(SCREEN_WIDTH - 310.0, 60.0);
     }
 
-    fn draw_game_over(&self) {
-        let game_over_text = "GAME OVER";
-        let font_size = 80.0;
-        let text_dims = self.measure_text_retro(game_over_text, font_size as u16);
-        let mut x_offset = 0.0;
+    fn draw_debug_geometry(&self) {
+        let hitbox = Color::from_rgba(0, 255, 0, 200);
 
-        // Center the text
-        let start_x = SCREEN_WIDTH / 2.0 - text_dims.width / 2.0;
-        let start_y = 200.0;
+        // Edges at which the formation turns around and steps down
+        for x in [FORMATION_EDGE_MARGIN, SCREEN_WIDTH - FORMATION_EDGE_MARGIN] {
+            draw_line(x, 0.0, x, SCREEN_HEIGHT, 1.0, YELLOW);
+        }
 
-        // Wobble parameters
-        let wobble_amplitude = 10.0;
-        let wobble_frequency = 0.1;
-        let wobble_speed = 5.0;
+        // Enemies crossing this line end the run
+        let defender_y = SCREEN_HEIGHT - DEFENDER_LINE;
+        draw_line(0.0, defender_y, SCREEN_WIDTH, defender_y, 2.0, RED);
+        draw_text("DEFENDER_LINE", 28.0, defender_y - 4.0, 16.0, RED);
+
+        // Formation bounds including the enemies' hit circles
+        if let Some(bounds) = formation_bounds(&self.world.enemies) {
+            draw_rectangle_lines(
+                bounds.x - COLLISION_RADIUS,
+                bounds.y - COLLISION_RADIUS,
+                bounds.w + 2.0 * COLLISION_RADIUS,
+                bounds.h + 2.0 * COLLISION_RADIUS,
+                1.0,
+                SKYBLUE,
+            );
+        }
 
-        // Draw each letter with rainbow color cycling and wobble (CPU-based)
-        for (i, character) in game_over_text.chars().enumerate() {
-            let char_str = character.to_string();
-            let char_dims = self.measure_text_retro(&char_str, font_size as u16);
+        // A bullet hits when its center is inside an enemy's circle
+        for enemy in &self.world.enemies {
+            draw_circle_lines(enemy.x, enemy.y, COLLISION_RADIUS, 1.0, hitbox);
+        }
+        for bullet in &self.world.bullets {
+            draw_circle(bullet.x, bullet.y, 2.0, hitbox);
+        }
 
-            // Calculate wobble effect
-            let y_offset =
-                (x_offset * wobble_frequency + self.time * wobble_speed).sin() * wobble_amplitude;
+        // The bee's own circle and the combined radius bullet centers are tested against
+        if self.world.bee_active {
+            let x = self.world.bee_x + BEE_RADIUS;
+            let y = self.world.bee_y + BEE_RADIUS;
+            draw_circle_lines(x, y, BEE_RADIUS, 1.0, hitbox);
+            draw_circle_lines(x, y, BEE_RADIUS + COLLISION_RADIUS, 1.0, ORANGE);
+        }
+    }
 
-            // C64-style color cycling
-            let color_offset = self.time + i as f32 * 0.3;
-            let r = ((color_offset * 3.0).sin() * 0.5 + 0.5) * 255.0;
-            let g = ((color_offset * 3.0 + 2.094).sin() * 0.5 + 0.5) * 255.0;
-            let b = ((color_offset * 3.0 + 4.189).sin() * 0.5 + 0.5) * 255.0;
-            let rainbow_color = Color::from_rgba(r as u8, g as u8, b as u8, 255);
+    fn draw_debug_panel(&self, x: f32, y: f32) {
+        let width = 300.0;
+        let line_height = 16.0;
+        let graph_height = 40.0;
+
+        let descent = if self.world.enemies.is_empty() {
+            "no formation".to_string()
+        } else if self.world.descent_distance > 0.0 {
+            format!("descending, {:.0}px left", self.world.descent_distance)
+        } else if self.world.enemies[0].direction > 0.0 {
+            "marching right".to_string()
+        } else {
+            "marching left".to_string()
+        };
+
+        let lines = [
+            format!(
+                "FPS {:.0}  avg {:.1} ms  max {:.1} ms",
+                self.frame_times.fps(),
+                self.frame_times.average() * 1000.0,
+                self.frame_times.max() * 1000.0
+            ),
+            format!(
+                "enemies {}  bullets {}  explosions {}  bee {}",
+                self.world.enemies.len(),
+                self.world.bullets.len(),
+                self.explosions.len(),
+                if self.world.bee_active { "on" } else { "off" }
+            ),
+            format!("formation: {}", descent),
+            format!(
+                "wave {}  difficulty {}",
+                self.world.wave_number,
+                self.difficulty.name()
+            ),
+            format!(
+                "enemy speed {:.0}  descent speed {:.0}",
+                self.world.enemy_speed, self.world.descent_speed
+            ),
+            format!(
+                "bullet speed {:.0}  player speed {:.0}",
+                self.world.bullet_speed, self.world.player_speed
+            ),
+            format!(
+                "COLLISION_RADIUS {}  BEE_RADIUS {}",
+                COLLISION_RADIUS, BEE_RADIUS
+            ),
+            format!(
+                "edge margin {}  descent step {}",
+                FORMATION_EDGE_MARGIN, FORMATION_DESCENT_STEP
+            ),
+            format!("DEFENDER_LINE {}", DEFENDER_LINE),
+            format!(
+                "march every {:.2}s ({}-{}s, count weight {})",
+                self.march.interval(),
+                MARCH_FASTEST_INTERVAL,
+                MARCH_SLOWEST_INTERVAL,
+                MARCH_COUNT_WEIGHT
+            ),
+        ];
+
+        let height = graph_height + lines.len() as f32 * line_height + 20.0;
+        draw_rectangle(x, y, width, height, Color::from_rgba(0, 0, 0, 180));
+        draw_rectangle_lines(x, y, width, height, 1.0, GREEN);
+
+        // Frame time graph, newest on the right; the line marks 60 FPS
+        let graph_x = x + 10.0;
+        let graph_y = y + 10.0;
+        let graph_width = width - 20.0;
+        let bar_width = graph_width / DEBUG_GRAPH_SAMPLES as f32;
+        let ms_scale = graph_height / 50.0; // 50 ms fills the graph
+        let samples = self.frame_times.samples().count();
+        for (i, dt) in self.frame_times.samples().enumerate() {
+            let ms = dt * 1000.0;
+            let bar_height = (ms * ms_scale).min(graph_height);
+            let color = if ms > 1000.0 / 30.0 {
+                RED
+            } else if ms > 1000.0 / 60.0 + 1.0 {
+                YELLOW
+            } else {
+                GREEN
+            };
+            let bar_x = graph_x + (DEBUG_GRAPH_SAMPLES - samples + i) as f32 * bar_width;
+            draw_rectangle(
+                bar_x,
+                graph_y + graph_height - bar_height,
+                bar_width,
+                bar_height,
+                color,
+            );
+        }
+        let target_y = graph_y + graph_height - 1000.0 / 60.0 * ms_scale;
+        draw_line(
+            graph_x,
+            target_y,
+            graph_x + graph_width,
+            target_y,
+            1.0,
+            WHITE,
+        );
+
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                line,
+                x + 10.0,
+                graph_y + graph_height + 16.0 + i as f32 * line_height,
+                16.0,
+                WHITE,
+            );
+        }
+    }
+
+    /// Drop-down developer console over the top of the screen
+    fn draw_console(&self) {
+        let line_height = 18.0;
+        let lines = 12;
+        let height = (lines + 1) as f32 * line_height + 16.0;
+
+        draw_rectangle(
+            0.0,
+            0.0,
+            SCREEN_WIDTH,
+            height,
+            Color::from_rgba(0, 0, 0, 220),
+        );
+        draw_line(0.0, height, SCREEN_WIDTH, height, 2.0, GREEN);
+
+        for (i, line) in self.console.output(lines).enumerate() {
+            let color = if line.starts_with('>') { GRAY } else { WHITE };
+            draw_text(line, 10.0, 20.0 + i as f32 * line_height, 18.0, color);
+        }
+
+        // Blinking cursor after the input line
+        let cursor = if ((get_time() * 2.0) as u64).is_multiple_of(2) {
+            "_"
+        } else {
+            ""
+        };
+        draw_text(
+            format!("> {}{}", self.console.input(), cursor),
+            10.0,
+            height - 10.0,
+            18.0,
+            GREEN,
+        );
+    }
+
+    /// Name of the controller in use, in the bottom left corner
+    #[cfg(not(target_arch = "wasm32"))]
+    fn draw_gamepad_indicator(&self) {
+        let Some(name) = self.gamepad.active_name() else {
+            return;
+        };
+        let text = format!("PAD: {}", name);
+        let dims = self.measure_text_retro(&text, 16);
+        draw_rectangle(
+            6.0,
+            SCREEN_HEIGHT - dims.height - 18.0,
+            dims.width + 12.0,
+            dims.height + 12.0,
+            Color::from_rgba(0, 0, 0, 160),
+        );
+        self.draw_text_retro(&text, 12.0, SCREEN_HEIGHT - 12.0, 16.0, GREEN);
+    }
+
+    /// "MUTED" in the bottom right corner while the sound is off
+    fn draw_muted_indicator(&self) {
+        let text = self.settings.get().language.text(Text::Muted);
+        let dims = self.measure_text_retro(text, 16);
+        let x = SCREEN_WIDTH - dims.width - 12.0;
+        draw_rectangle(
+            x - 6.0,
+            SCREEN_HEIGHT - dims.height - 18.0,
+            dims.width + 12.0,
+            dims.height + 12.0,
+            Color::from_rgba(0, 0, 0, 160),
+        );
+        self.draw_text_retro(text, x, SCREEN_HEIGHT - 12.0, 16.0, ORANGE);
+    }
+
+    /// Latest log lines on a translucent strip along the bottom of the screen
+    fn draw_log_overlay(&self) {
+        let lines = logger::recent_lines(LOG_OVERLAY_LINES);
+        let line_height = 16.0;
+        let height = LOG_OVERLAY_LINES as f32 * line_height + 12.0;
+        let y = SCREEN_HEIGHT - height;
+
+        draw_rectangle(0.0, y, SCREEN_WIDTH, height, Color::from_rgba(0, 0, 0, 180));
+        for (i, line) in lines.iter().enumerate() {
+            let color = match line.split_whitespace().next() {
+                Some("ERROR") => RED,
+                Some("WARN") => ORANGE,
+                Some("INFO") => WHITE,
+                _ => GRAY,
+            };
+            draw_text(line, 8.0, y + 18.0 + i as f32 * line_height, 16.0, color);
+        }
+    }
+
+    fn draw_bee(&self) {
+        // Only draw if bee is active
+        if self.world.bee_active {
+            let logo_width = 100.0; // Scale the logo to reasonable size
+            let logo_height = 100.0;
 
+            draw_texture_ex(
+                &self.intro_icon,
+                self.world.bee_x,
+                self.world.bee_y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(logo_width, logo_height)),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    /// Draw subtle touch zone indicators for mobile gameplay
+    fn draw_touch_indicators(&self) {
+        // Only show indicators on touch devices
+        if !self.touch_used {
+            return;
+        }
+        self.draw_touch_zones(&self.touch.layout, self.touch.stick_offset());
+    }
+
+    /// Movement and fire zones of a touch layout, tinted blue and red
+    fn draw_touch_zones(&self, layout: &TouchLayout, stick: Option<Vec2>) {
+        // Alpha values at full opacity; the default opacity gives faint zones
+        let alpha = |max: f32| (max * layout.opacity).min(255.0) as u8;
+        let line = Color::from_rgba(255, 255, 255, alpha(250.0));
+        let label = Color::from_rgba(255, 255, 255, alpha(450.0));
+        let zones = layout.zones();
+
+        let (move_label, knob) = match layout.scheme {
+            TouchScheme::Split => ("MOVE", None),
+            TouchScheme::Joystick => ("MOVE", Some(stick.unwrap_or_default())),
+            TouchScheme::Drag => ("DRAG TO MOVE - AUTO FIRE", None),
+        };
+        self.draw_touch_zone(
+            &zones.movement,
+            Color::from_rgba(100, 150, 255, alpha(75.0)),
+            line,
+            label,
+            move_label,
+            knob,
+        );
+        if let Some(ref fire) = zones.fire {
+            self.draw_touch_zone(
+                fire,
+                Color::from_rgba(255, 100, 100, alpha(75.0)),
+                line,
+                label,
+                "SHOOT",
+                None,
+            );
+        }
+    }
+
+    /// One touch zone with its label; `knob` is the joystick knob offset
+    fn draw_touch_zone(
+        &self,
+        zone: &TouchZone,
+        fill: Color,
+        line: Color,
+        label_color: Color,
+        label: &str,
+        knob: Option<Vec2>,
+    ) {
+        let dims = self.measure_text_retro(label, 24);
+        match *zone {
+            TouchZone::Area(rect) => {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, line);
+                self.draw_text_retro(
+                    label,
+                    rect.center().x - dims.width / 2.0,
+                    rect.bottom() - 30.0,
+                    24.0,
+                    label_color,
+                );
+            }
+            TouchZone::Circle { center, radius } => {
+                draw_circle(center.x, center.y, radius, fill);
+                draw_circle_lines(center.x, center.y, radius, 2.0, line);
+                if let Some(offset) = knob {
+                    let knob = center + offset;
+                    draw_circle(knob.x, knob.y, radius * 0.4, line);
+                }
+                self.draw_text_retro(
+                    label,
+                    center.x - dims.width / 2.0,
+                    center.y - radius - 10.0,
+                    24.0,
+                    label_color,
+                );
+            }
+        }
+    }
+
+    /// Menu button that opens the touch controls settings
+    fn touch_settings_button_rect() -> Rect {
+        Rect::new(SCREEN_WIDTH / 2.0 + 5.0, 400.0, 180.0, 34.0)
+    }
+
+    /// Menu button that opens the options screen
+    fn options_button_rect() -> Rect {
+        Rect::new(SCREEN_WIDTH / 2.0 - 185.0, 400.0, 180.0, 34.0)
+    }
+
+    /// Panel of the touch controls settings
+    fn touch_settings_rect() -> Rect {
+        Rect::new(SCREEN_WIDTH / 2.0 - 230.0, 110.0, 460.0, 340.0)
+    }
+
+    /// The "<" and ">" buttons of a touch settings row
+    fn touch_setting_arrow_rects(row: usize) -> (Rect, Rect) {
+        let panel = Self::touch_settings_rect();
+        let y = panel.y + 70.0 + row as f32 * 55.0;
+        (
+            Rect::new(panel.x + 210.0, y, 40.0, 40.0),
+            Rect::new(panel.right() - 60.0, y, 40.0, 40.0),
+        )
+    }
+
+    /// Button that closes the touch controls settings
+    fn touch_settings_done_rect() -> Rect {
+        let panel = Self::touch_settings_rect();
+        Rect::new(panel.center().x - 70.0, panel.bottom() - 50.0, 140.0, 40.0)
+    }
+
+    /// Label and current value of each touch settings row
+    fn touch_setting_rows(layout: &TouchLayout, language: Language) -> [(&'static str, String); 4] {
+        let hand = if layout.left_handed {
+            Text::LeftHand
+        } else {
+            Text::RightHand
+        };
+        [
+            (
+                language.text(Text::Scheme),
+                layout.scheme.name().to_string(),
+            ),
+            (language.text(Text::Hand), language.text(hand).to_string()),
+            (
+                language.text(Text::ZoneSize),
+                format!("{:.0}%", layout.zone_scale * 100.0),
+            ),
+            (
+                language.text(Text::Opacity),
+                format!("{:.0}%", layout.opacity * 100.0),
+            ),
+        ]
+    }
+
+    /// Taps on the touch settings: arrows change a row, Done (or Back) closes
+    fn handle_touch_settings_input(&mut self, frame: &InputFrame) {
+        if frame.is_pressed(Action::Back) {
+            self.show_touch_settings = false;
+            return;
+        }
+
+        let mut layout = self.touch.layout;
+        for &tap in &frame.taps {
+            if Self::touch_settings_done_rect().contains(tap) {
+                self.show_touch_settings = false;
+                return;
+            }
+            for row in 0..4 {
+                let (less, more) = Self::touch_setting_arrow_rects(row);
+                let step = if less.contains(tap) {
+                    -1.0
+                } else if more.contains(tap) {
+                    1.0
+                } else {
+                    continue;
+                };
+                // Round to whole percent so repeated steps don't drift
+                let adjust = |value: f32, by: f32| ((value + step * by) * 100.0).round() / 100.0;
+                match row {
+                    0 => layout.scheme = layout.scheme.cycle(step as isize),
+                    1 => layout.left_handed = !layout.left_handed,
+                    2 => layout.zone_scale = adjust(layout.zone_scale, TOUCH_ZONE_SCALE_STEP),
+                    _ => layout.opacity = adjust(layout.opacity, TOUCH_OPACITY_STEP),
+                }
+            }
+        }
+
+        self.change_settings(Settings {
+            touch: layout,
+            ..*self.settings.get()
+        });
+    }
+
+    /// Touch controls settings over the menu, with a preview of the zones
+    fn draw_touch_settings(&self) {
+        let layout = &self.touch.layout;
+        let language = self.settings.get().language;
+        self.draw_touch_zones(layout, None);
+
+        let panel = Self::touch_settings_rect();
+        draw_rectangle(
+            panel.x,
+            panel.y,
+            panel.w,
+            panel.h,
+            Color::from_rgba(255, 255, 255, 235),
+        );
+        draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 2.0, BLACK);
+        self.draw_text_retro(
+            language.text(Text::TouchControls),
+            panel.x + 20.0,
+            panel.y + 40.0,
+            24.0,
+            BLACK,
+        );
+
+        for (row, (label, value)) in Self::touch_setting_rows(layout, language)
+            .iter()
+            .enumerate()
+        {
+            let (less, more) = Self::touch_setting_arrow_rects(row);
+            let text_y = less.y + 27.0;
+            self.draw_text_retro(label, panel.x + 20.0, text_y, 20.0, BLACK);
+
+            for (rect, arrow) in [(less, "<"), (more, ">")] {
+                draw_rectangle(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    Color::from_rgba(240, 240, 240, 255),
+                );
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, BLACK);
+                let dims = self.measure_text_retro(arrow, 20);
+                self.draw_text_retro(
+                    arrow,
+                    rect.center().x - dims.width / 2.0,
+                    text_y,
+                    20.0,
+                    BLACK,
+                );
+            }
+
+            let dims = self.measure_text_retro(value, 18);
             self.draw_text_retro(
-                &char_str,
-                start_x + x_offset,
-                start_y + y_offset,
-                font_size,
-                rainbow_color,
+                value,
+                (less.right() + more.x) / 2.0 - dims.width / 2.0,
+                text_y,
+                18.0,
+                BLACK,
             );
+        }
 
-            x_offset += char_dims.width;
+        let done = Self::touch_settings_done_rect();
+        draw_rectangle(
+            done.x,
+            done.y,
+            done.w,
+            done.h,
+            Color::from_rgba(0, 150, 0, 255),
+        );
+        draw_rectangle_lines(done.x, done.y, done.w, done.h, 2.0, BLACK);
+        let done_text = language.text(Text::Done);
+        let dims = self.measure_text_retro(done_text, 22);
+        self.draw_text_retro(
+            done_text,
+            done.center().x - dims.width / 2.0,
+            done.y + 28.0,
+            22.0,
+            WHITE,
+        );
+    }
+
+    /// Show the options screen
+    fn open_options(&mut self) {
+        self.state = GameState::Options;
+        self.options_selected = 0;
+    }
+
+    /// Panel of the options screen
+    fn options_rect() -> Rect {
+        Rect::new(SCREEN_WIDTH / 2.0 - 260.0, 15.0, 520.0, 550.0)
+    }
+
+    /// The "<" and ">" buttons of an options row
+    fn option_arrow_rects(row: usize) -> (Rect, Rect) {
+        let panel = Self::options_rect();
+        let y = panel.y + 60.0 + row as f32 * 36.0;
+        (
+            Rect::new(panel.x + 270.0, y, 30.0, 30.0),
+            Rect::new(panel.right() - 50.0, y, 30.0, 30.0),
+        )
+    }
+
+    /// Button that leaves the options screen
+    fn options_back_rect() -> Rect {
+        let panel = Self::options_rect();
+        Rect::new(panel.center().x - 70.0, panel.bottom() - 50.0, 140.0, 40.0)
+    }
+
+    /// Options screen: up/down pick a row, left/right (or the arrows)
+    /// change it, Back returns to the menu
+    fn handle_options_input(&mut self, frame: &InputFrame) {
+        let rows = OptionRow::rows();
+        // One entry past the rows is the back button
+        let back = rows.len();
+        if frame.is_pressed(Action::Back) || frame.is_pressed(Action::Options) {
+            self.state = GameState::Menu;
+            return;
+        }
+        if frame.is_pressed(Action::Up) {
+            self.options_selected = (self.options_selected + back) % (back + 1);
+        }
+        if frame.is_pressed(Action::Down) {
+            self.options_selected = (self.options_selected + 1) % (back + 1);
+        }
+        if frame.is_pressed(Action::Confirm) && self.options_selected == back {
+            self.state = GameState::Menu;
+            return;
+        }
+
+        let mut settings = *self.settings.get();
+        if let Some(&row) = rows.get(self.options_selected) {
+            if frame.is_pressed(Action::MoveLeft) {
+                settings.adjust(row, -1);
+            }
+            if frame.is_pressed(Action::MoveRight) || frame.is_pressed(Action::Confirm) {
+                settings.adjust(row, 1);
+            }
+        }
+        for &tap in &frame.taps {
+            if Self::options_back_rect().contains(tap) {
+                self.state = GameState::Menu;
+                return;
+            }
+            for (index, &row) in rows.iter().enumerate() {
+                let (less, more) = Self::option_arrow_rects(index);
+                if less.contains(tap) {
+                    settings.adjust(row, -1);
+                } else if more.contains(tap) {
+                    settings.adjust(row, 1);
+                } else {
+                    continue;
+                }
+                self.options_selected = index;
+            }
+        }
+        if settings != *self.settings.get() {
+            self.change_settings(settings);
+            // Click at the new volume so the player hears the change
+            self.audio.play(Clip::Ui, Bus::Ui);
         }
+    }
 
-        // ========================================================================
-        // Additional UI elements (score, instructions)
-        // ========================================================================
-        let score_text = format!("Final Score: {}", self.score);
-        let score_dims = self.measure_text_retro(&score_text, 50);
+    /// Options screen with the current value of every setting
+    fn draw_options(&self) {
+        let settings = self.settings.get();
+        let language = settings.language;
+        let panel = Self::options_rect();
+        draw_rectangle(
+            panel.x,
+            panel.y,
+            panel.w,
+            panel.h,
+            Color::from_rgba(255, 255, 255, 235),
+        );
+        draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 2.0, BLACK);
         self.draw_text_retro(
-            &score_text,
-            SCREEN_WIDTH / 2.0 - score_dims.width / 2.0,
-            300.0,
-            50.0,
+            language.text(Text::Options),
+            panel.x + 20.0,
+            panel.y + 40.0,
+            24.0,
             BLACK,
         );
 
-        let return_text = if touches().is_empty() {
-            "Press R to Return to Menu"
-        } else {
-            "Tap to Return to Menu"
-        };
-        let return_dims = self.measure_text_retro(return_text, 30);
-        self.draw_text_retro(
-            return_text,
-            SCREEN_WIDTH / 2.0 - return_dims.width / 2.0,
-            400.0,
-            30.0,
-            Color::from_rgba(0, 0, 0, 255),
-        );
-    }
+        for (index, row) in OptionRow::rows().into_iter().enumerate() {
+            let (less, more) = Self::option_arrow_rects(index);
+            if index == self.options_selected {
+                draw_rectangle(
+                    panel.x + 10.0,
+                    less.y - 2.0,
+                    panel.w - 20.0,
+                    less.h + 4.0,
+                    Color::from_rgba(255, 230, 120, 255),
+                );
+            }
+            let text_y = less.y + 22.0;
+            self.draw_text_retro(
+                language.text(row.label()),
+                panel.x + 20.0,
+                text_y,
+                18.0,
+                BLACK,
+            );
 
-    fn draw_score(&self) {
-        let score_text = format!("Score: {}", self.score);
+            for (rect, arrow) in [(less, "<"), (more, ">")] {
+                draw_rectangle(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    Color::from_rgba(240, 240, 240, 255),
+                );
+                draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, BLACK);
+                let dims = self.measure_text_retro(arrow, 18);
+                self.draw_text_retro(
+                    arrow,
+                    rect.center().x - dims.width / 2.0,
+                    text_y,
+                    18.0,
+                    BLACK,
+                );
+            }
 
-        // Use fixed position based on maximum expected score width to prevent jumping
-        // Reserve space for "Score: 99999" to keep position stable
-        let max_score_text = "Score: 99999";
-        let max_text_dims = self.measure_text_retro(max_score_text, 32);
-        let padding = 20.0;
-        let x_pos = SCREEN_WIDTH - max_text_dims.width - padding;
+            let value = row.value(settings);
+            let dims = self.measure_text_retro(&value, 16);
+            self.draw_text_retro(
+                &value,
+                (less.right() + more.x) / 2.0 - dims.width / 2.0,
+                text_y,
+                16.0,
+                BLACK,
+            );
+        }
 
-        // Draw shadow for bold effect
+        let back = Self::options_back_rect();
+        let back_color = if self.options_selected == OptionRow::rows().len() {
+            Color::from_rgba(0, 190, 0, 255)
+        } else {
+            Color::from_rgba(0, 150, 0, 255)
+        };
+        draw_rectangle(back.x, back.y, back.w, back.h, back_color);
+        draw_rectangle_lines(back.x, back.y, back.w, back.h, 2.0, BLACK);
+        let back_text = language.text(Text::Back);
+        let dims = self.measure_text_retro(back_text, 22);
         self.draw_text_retro(
-            &score_text,
-            x_pos + 2.0,
-            42.0,
-            32.0,
-            Color::from_rgba(0, 0, 0, 128),
+            back_text,
+            back.center().x - dims.width / 2.0,
+            back.y + 28.0,
+            22.0,
+            WHITE,
         );
-
-        // Draw main text in red with larger font for bold effect
-        self.draw_text_retro(&score_text, x_pos, 40.0, 32.0, RED);
     }
 
-    fn draw_wave_level(&self) {
-        let wave_text = format!("Enemy round: {}", self.wave_number);
-        let padding = 20.0;
-
-        // Draw shadow for bold effect
-        self.draw_text_retro(
-            &wave_text,
-            padding + 2.0,
-            42.0,
-            32.0,
-            Color::from_rgba(0, 0, 0, 128),
-        );
-
-        // Draw main text in blue
-        self.draw_text_retro(&wave_text, padding, 40.0, 32.0, BLUE);
+    /// Typing, history and command execution while the console is open
+    fn handle_console_input(&mut self, frame: &InputFrame) {
+        for ch in frame.text.chars() {
+            // The toggle key itself is not part of a command
+            if ch != '`' && ch != '~' {
+                self.console.push_char(ch);
+            }
+        }
+        if frame.is_pressed(Action::Erase) {
+            self.console.backspace();
+        }
+        if frame.is_pressed(Action::Up) {
+            self.console.browse_history(-1);
+        }
+        if frame.is_pressed(Action::Down) {
+            self.console.browse_history(1);
+        }
+        if frame.is_pressed(Action::Back) {
+            self.console.toggle();
+        }
+        if frame.is_pressed(Action::Confirm) {
+            if let Some(line) = self.console.submit() {
+                match ConsoleCommand::parse(&line) {
+                    Ok(command) => self.run_console_command(command),
+                    Err(err) => self.console.print(err),
+                }
+            }
+        }
     }
 
-    fn draw_bee(&self) {
-        // Only draw if bee is active
-        if self.bee_active {
-            let logo_width = 100.0; // Scale the logo to reasonable size
-            let logo_height = 100.0;
-
-            draw_texture_ex(
-                &self.intro_icon,
-                self.bee_x,
-                self.bee_y,
-                WHITE,
-                DrawTextureParams {
-                    dest_size: Some(vec2(logo_width, logo_height)),
-                    ..Default::default()
-                },
-            );
+    /// Apply a console command to the running game.
+    fn run_console_command(&mut self, command: ConsoleCommand) {
+        if self.state != GameState::Playing && command.is_cheat() {
+            self.console.print("start a game first");
+            return;
         }
+        if command.is_cheat() && !self.cheats_used {
+            self.cheats_used = true;
+            log::info!("Console command used, this run will not be recorded");
+        }
+
+        let message = match command {
+            ConsoleCommand::Help => {
+                for line in console::HELP {
+                    self.console.print(*line);
+                }
+                return;
+            }
+            ConsoleCommand::Wave(wave) => {
+                self.world.start_wave(wave);
+                format!("wave {}", wave)
+            }
+            ConsoleCommand::Spawn { enemy_type, x, y } => {
+                // Join the formation's current direction
+                let direction = self
+                    .world
+                    .enemies
+                    .first()
+                    .map_or(1.0, |enemy| enemy.direction);
+                self.world
+                    .enemies
+                    .push(Enemy::new(x, y, direction, enemy_type));
+                format!("spawned {:?} at {}, {}", enemy_type, x, y)
+            }
+            ConsoleCommand::God => {
+                self.world.god_mode = !self.world.god_mode;
+                format!(
+                    "god mode {}",
+                    if self.world.god_mode { "on" } else { "off" }
+                )
+            }
+            ConsoleCommand::Bee => {
+                self.world.spawn_bee();
+                "bee launched".to_string()
+            }
+            ConsoleCommand::Speed(multiplier) => {
+                self.time_scale = multiplier;
+                format!("game speed x{}", multiplier)
+            }
+            ConsoleCommand::Score(score) => {
+                self.world.score = score;
+                format!("score {}", score)
+            }
+            ConsoleCommand::Upgrade => {
+                self.world.player.upgrade();
+                format!("{} shots", self.world.player.available_shots)
+            }
+            ConsoleCommand::Clear => {
+                // The next frame advances to the next wave as if it was shot down
+                let count = self.world.enemies.len();
+                self.world.enemies.clear();
+                format!("removed {} enemies", count)
+            }
+        };
+        log::info!("Console: {}", message);
+        self.console.print(message);
     }
 
-    /// Draw subtle touch zone indicators for mobile gameplay
-    fn draw_touch_indicators(&self) {
-        // Only show indicators if there are active touches (mobile device)
-        if touches().is_empty() {
+    /// Arcade initials entry: arrows or taps on the letter slots, Enter or OK to finish
+    fn handle_initials_input(&mut self, frame: &InputFrame) {
+        let Some(ref mut entry) = self.initials_entry else {
             return;
+        };
+
+        if frame.is_pressed(Action::Up) {
+            entry.cycle_up();
+        }
+        if frame.is_pressed(Action::Down) {
+            entry.cycle_down();
+        }
+        if frame.is_pressed(Action::MoveLeft) {
+            entry.previous_slot();
+        }
+        if frame.is_pressed(Action::MoveRight) {
+            entry.next_slot();
         }
 
-        // Draw semi-transparent overlay split in half
-        // Left side: Movement zone (blue tint)
-        draw_rectangle(
-            0.0,
-            0.0,
-            SCREEN_WIDTH / 2.0,
-            SCREEN_HEIGHT,
-            Color::from_rgba(100, 150, 255, 30),
-        );
+        // Taps and clicks: upper half of a slot cycles up, lower half down
+        let mut confirmed = frame.is_pressed(Action::Confirm);
+        for &tap in &frame.taps {
+            if Self::initials_ok_rect().contains(tap) {
+                confirmed = true;
+            }
+            for slot in 0..INITIALS_LEN {
+                let rect = Self::initials_slot_rect(slot);
+                if rect.contains(tap) {
+                    entry.select_slot(slot);
+                    if tap.y < rect.y + rect.h / 2.0 {
+                        entry.cycle_up();
+                    } else {
+                        entry.cycle_down();
+                    }
+                }
+            }
+        }
 
-        // Right side: Shooting zone (red tint)
-        draw_rectangle(
-            SCREEN_WIDTH / 2.0,
-            0.0,
-            SCREEN_WIDTH / 2.0,
-            SCREEN_HEIGHT,
-            Color::from_rgba(255, 100, 100, 30),
-        );
+        if confirmed {
+            self.confirm_initials();
+        }
+    }
 
-        // Draw center divider line
-        draw_line(
-            SCREEN_WIDTH / 2.0,
-            0.0,
-            SCREEN_WIDTH / 2.0,
-            SCREEN_HEIGHT,
-            2.0,
-            Color::from_rgba(255, 255, 255, 100),
-        );
+    /// Switch to the controls of the player named in the menu. Called once
+    /// the name is settled, not while it is being typed.
+    fn load_player_bindings(&mut self) {
+        if self.bindings_profile != self.player_name {
+            self.keyboard
+                .set_bindings(self.controls.bindings(&self.player_name));
+            self.bindings_profile = self.player_name.clone();
+        }
+    }
 
-        // Draw labels
-        let move_text = "MOVE";
-        let move_dims = self.measure_text_retro(move_text, 24);
-        self.draw_text_retro(
-            move_text,
-            SCREEN_WIDTH / 4.0 - move_dims.width / 2.0,
-            SCREEN_HEIGHT - 30.0,
-            24.0,
-            Color::from_rgba(255, 255, 255, 180),
-        );
+    /// Whether typed letters and digits go into the menu's name box
+    fn name_takes_text(&self) -> bool {
+        self.state == GameState::Menu
+            && self.controls_screen.is_none()
+            && !self.show_touch_settings
+            && !self.console.is_open()
+    }
 
-        let shoot_text = "SHOOT";
-        let shoot_dims = self.measure_text_retro(shoot_text, 24);
-        self.draw_text_retro(
-            shoot_text,
-            SCREEN_WIDTH * 3.0 / 4.0 - shoot_dims.width / 2.0,
-            SCREEN_HEIGHT - 30.0,
-            24.0,
-            Color::from_rgba(255, 255, 255, 180),
-        );
+    /// Collect this frame's input from every source
+    fn poll_input(&mut self) -> InputFrame {
+        let mut frame = InputFrame::default();
+        self.keyboard.poll(&mut frame);
+        self.mouse.poll(&mut frame);
+        self.touch.controls = self.state == GameState::Playing;
+        self.touch.poll(&mut frame);
+        self.touching = frame.touching;
+        self.touch_used |= frame.touching;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.gamepad.poll(&mut frame);
+            match self.gamepad.take_change() {
+                Some(GamepadChange::Connected(name)) => {
+                    self.set_status_message(format!("Controller connected: {}", name));
+                }
+                Some(GamepadChange::Disconnected(name)) => {
+                    // Don't let the run go on without the player
+                    if self.state == GameState::Playing {
+                        self.paused = true;
+                    }
+                    self.set_status_message(format!("Controller disconnected: {}", name));
+                }
+                None => {}
+            }
+        }
+        frame
     }
 
     fn handle_input(&mut self) {
+        let mut frame = self.poll_input();
+        // Keys that type into the name don't trigger what they are bound to
+        if self.name_takes_text() {
+            frame.ignore_text_keys();
+        }
+
+        // Log overlay works in every state
+        if frame.is_pressed(Action::ToggleLog) {
+            self.show_log = !self.show_log;
+        }
+        if self.debug_tools && frame.is_pressed(Action::ToggleDebug) {
+            self.show_debug = !self.show_debug;
+        }
+        // Mute everywhere except the menu, where M is typed into the name
+        if frame.is_pressed(Action::ToggleMute) && self.state != GameState::Menu {
+            let muted = self.audio.mixer_mut().toggle_mute();
+            log::info!("Sound {}", if muted { "muted" } else { "unmuted" });
+        }
+        if self.debug_tools && frame.is_pressed(Action::ToggleConsole) {
+            self.console.toggle();
+        }
+        if self.console.is_open() {
+            self.handle_console_input(&frame);
+            return;
+        }
+
         match self.state {
             GameState::Menu => {
                 // Skip input processing if we just reset (prevents 'R' from appearing in name)
@@ -1705,6 +3708,37 @@ impl Game {
                     return;
                 }
 
+                // The controls screen takes all input while it is open
+                if let Some(ref mut screen) = self.controls_screen {
+                    if !screen.capturing
+                        && (frame.is_pressed(Action::ToggleControls)
+                            || frame.is_pressed(Action::Back))
+                    {
+                        self.controls_screen = None;
+                        return;
+                    }
+                    let mut bindings = self.keyboard.bindings().clone();
+                    if screen.handle(&frame, &mut bindings) {
+                        self.controls.set(&self.player_name, &bindings);
+                        self.keyboard.set_bindings(bindings);
+                    }
+                    return;
+                }
+                if frame.is_pressed(Action::ToggleControls) {
+                    // The name is done, edit the controls it owns
+                    self.load_player_bindings();
+                    self.controls_screen = Some(ControlsScreen::default());
+                    return;
+                }
+                if frame.is_pressed(Action::Options) {
+                    self.open_options();
+                    return;
+                }
+                if self.show_touch_settings {
+                    self.handle_touch_settings_input(&frame);
+                    return;
+                }
+
                 // Calculate input box position (matches draw_menu layout)
                 let center_x = SCREEN_WIDTH / 2.0;
                 let center_y = SCREEN_HEIGHT / 2.0;
@@ -1716,155 +3750,164 @@ impl Game {
                 let box_y = panel_y + 55.0;
                 let input_box_rect = Rect::new(box_x, box_y, box_width, box_height);
 
-                // Handle touch input for mobile
-                let touch_list = touches();
-                if !touch_list.is_empty() {
-                    for touch in touch_list {
-                        let touch_pos = Vec2::new(touch.position.x, touch.position.y);
-
-                        // Check if touch is on input box - activate keyboard focus
-                        if input_box_rect.contains(touch_pos)
-                            && touch.phase == macroquad::input::TouchPhase::Started
-                        {
-                            self.name_input_focused = true;
-                            println!("Touch on input box - keyboard should appear");
-                        }
-
-                        // Check if touch is on start button
-                        let button_x = panel_x + (320.0 - 280.0) / 2.0;
-                        let button_y = panel_y + 120.0;
-                        let button_rect = Rect::new(button_x, button_y, 280.0, 45.0);
-
-                        if button_rect.contains(touch_pos)
-                            && touch.phase == macroquad::input::TouchPhase::Started
-                        {
-                            println!("Touch on start button");
-                            self.start_game();
-                        }
+                // Start button below the input box
+                let button_x = panel_x + (320.0 - 280.0) / 2.0;
+                let button_y = panel_y + 120.0;
+                let button_rect = Rect::new(button_x, button_y, 280.0, 45.0);
+
+                // Taps and clicks (mobile and desktop)
+                for &tap in &frame.taps {
+                    // Tap on the input box - activate keyboard focus
+                    if input_box_rect.contains(tap) {
+                        self.name_input_focused = true;
+                        log::debug!("Tap on input box - keyboard should appear");
+                    }
+
+                    if button_rect.contains(tap) {
+                        log::debug!("Start button tapped");
+                        self.start_game();
+                        return;
                     }
-                }
 
-                // Handle keyboard text input (works on desktop and mobile when keyboard is shown)
-                if let Some(character) = get_last_key_pressed() {
-                    println!("Key pressed: {:?}", character);
-                    match character {
-                        KeyCode::Backspace => {
-                            self.player_name.pop();
-                            println!("Player name after backspace: {}", self.player_name);
-                        }
-                        KeyCode::Enter => {
-                            println!("Enter pressed, starting game");
-                            self.start_game();
-                        }
-                        _ => {}
+                    if Self::touch_settings_button_rect().contains(tap) {
+                        self.show_touch_settings = true;
+                        return;
+                    }
+                    if Self::options_button_rect().contains(tap) {
+                        self.open_options();
+                        return;
                     }
                 }
 
-                // Handle character input (from keyboard or mobile keyboard)
-                if let Some(ch) = get_char_pressed() {
-                    println!("Char pressed: {}", ch);
-                    if ch.is_alphanumeric() && self.player_name.len() < 20 {
+                // Handle text input (from keyboard or mobile keyboard)
+                if frame.is_pressed(Action::Erase) {
+                    self.player_name.pop();
+                    log::trace!("Player name after backspace: {}", self.player_name);
+                }
+                for ch in frame.text.chars() {
+                    log::trace!("Char pressed: {}", ch);
+                    if ch.is_alphanumeric() && self.player_name.chars().count() < MAX_NAME_LEN {
                         self.player_name.push(ch);
-                        println!("Player name: {}", self.player_name);
+                        log::trace!("Player name: {}", self.player_name);
                     }
                 }
 
-                // Handle mouse click on input box (desktop)
-                if is_mouse_button_pressed(MouseButton::Left) {
-                    let (mouse_x, mouse_y) = mouse_position();
+                // Toggle between local and online scores
+                if frame.is_pressed(Action::ToggleScores)
+                    && self.highscore_manager.has_leaderboard()
+                {
+                    self.show_global_scores = !self.show_global_scores;
+                    self.show_profile = false;
+                }
 
-                    // Check if click is on input box
-                    if input_box_rect.contains(Vec2::new(mouse_x, mouse_y)) {
-                        self.name_input_focused = true;
-                        println!("Click on input box");
-                    }
+                // Cycle the local leaderboard between all time, today and this week
+                if frame.is_pressed(Action::CycleScoreWindow) {
+                    self.highscore_window = match self.highscore_window {
+                        TimeWindow::AllTime => TimeWindow::Today,
+                        TimeWindow::Today => TimeWindow::ThisWeek,
+                        TimeWindow::ThisWeek => TimeWindow::AllTime,
+                    };
+                    self.show_global_scores = false;
+                    self.show_profile = false;
+                }
 
-                    // Check if click is on start button
-                    let button_x = panel_x + (320.0 - 280.0) / 2.0;
-                    let button_y = panel_y + 120.0;
-                    let button_rect = Rect::new(button_x, button_y, 280.0, 45.0);
+                // Opt in or out of the local telemetry log
+                if frame.is_pressed(Action::ToggleTelemetry) {
+                    let enabled = !self.telemetry.is_enabled();
+                    self.telemetry.set_enabled(enabled);
+                    self.set_status_message(format!(
+                        "Telemetry {}",
+                        if enabled { "on" } else { "off" }
+                    ));
+                }
 
-                    if button_rect.contains(Vec2::new(mouse_x, mouse_y)) {
-                        println!("Button clicked!");
-                        self.start_game();
-                    }
+                // Achievement gallery
+                if frame.is_pressed(Action::ToggleAchievements) {
+                    self.show_achievements = !self.show_achievements;
                 }
 
-                // Also allow pressing Space bar to start game from menu
-                if is_key_pressed(KeyCode::Space) {
-                    println!("Space pressed in menu, starting game");
-                    self.start_game();
+                // Profile screen; up/down switches between known players
+                if frame.is_pressed(Action::ToggleProfile) {
+                    self.show_profile = !self.show_profile;
                 }
-            }
-            GameState::Playing => {
-                // Handle touch input for mobile gameplay
-                let touch_list = touches();
-
-                if !touch_list.is_empty() {
-                    // Reset touch shooting flag
-                    let mut new_touch_shooting = false;
-
-                    for touch in touch_list {
-                        let touch_x = touch.position.x;
-                        // touch_y could be used for future vertical controls
-
-                        // Left half of screen: Move player horizontally
-                        // Touch position directly controls player position
-                        if touch_x < SCREEN_WIDTH / 2.0 {
-                            // Map touch X position to player X position
-                            self.player.x = touch_x.clamp(
-                                self.player.base_width / 2.0,
-                                SCREEN_WIDTH - self.player.base_width / 2.0,
-                            );
-                        }
-
-                        // Right half of screen: Shoot
-                        if touch_x >= SCREEN_WIDTH / 2.0 {
-                            new_touch_shooting = true;
-                        }
+                if self.show_profile {
+                    if frame.is_pressed(Action::Up) {
+                        self.cycle_profile(-1);
                     }
-
-                    // Shoot on touch start (not continuous)
-                    if new_touch_shooting && !self.touch_shooting {
-                        self.shoot();
+                    if frame.is_pressed(Action::Down) {
+                        self.cycle_profile(1);
                     }
+                }
 
-                    self.touch_shooting = new_touch_shooting;
-                } else {
-                    self.touch_shooting = false;
+                // Difficulty selector, remembered for the next session
+                if frame.is_pressed(Action::MoveLeft) || frame.is_pressed(Action::MoveRight) {
+                    self.difficulty = if frame.is_pressed(Action::MoveLeft) {
+                        self.difficulty.easier()
+                    } else {
+                        self.difficulty.harder()
+                    };
+                    self.change_settings(Settings {
+                        difficulty: self.difficulty,
+                        ..*self.settings.get()
+                    });
                 }
 
-                // Keyboard controls (desktop fallback)
-                if is_key_down(KeyCode::Left) {
-                    self.player.move_left(get_frame_time(), self.player_speed);
+                // Export / merge the local leaderboard
+                if frame.is_pressed(Action::ExportScores) {
+                    self.export_highscores();
                 }
-                if is_key_down(KeyCode::Right) {
-                    self.player.move_right(get_frame_time(), self.player_speed);
+                if frame.is_pressed(Action::ImportScores) {
+                    self.import_highscores();
                 }
-                if is_key_pressed(KeyCode::Space) {
-                    self.shoot();
+
+                // Enter or Space starts the game
+                if frame.is_pressed(Action::Confirm) || frame.is_pressed(Action::Fire) {
+                    log::debug!("Starting game from the menu");
+                    self.start_game();
                 }
             }
-            GameState::GameOver => {
-                // Handle touch for game over screen
-                let touch_list = touches();
-                if !touch_list.is_empty() {
-                    // Any touch restarts the game
-                    for touch in touch_list {
-                        if touch.phase == macroquad::input::TouchPhase::Started {
-                            self.reset();
-                            break;
-                        }
+            GameState::Playing => {
+                if frame.is_pressed(Action::Pause) || frame.is_pressed(Action::Back) {
+                    if self.paused && frame.is_pressed(Action::Back) {
+                        log::info!("Run abandoned");
+                        self.reset();
+                        return;
+                    }
+                    self.paused = !self.paused;
+                }
+                if self.paused {
+                    return;
+                }
+
+                let mut frame = frame;
+                if let Some(ref mut bot) = self.bot {
+                    bot.observe(&self.world);
+                    bot.poll(&mut frame);
+                }
+                // A recording replaces the player's input and the frame time
+                if let Some(ref mut playback) = self.playback {
+                    if playback.is_finished() {
+                        log::info!("Replay finished");
+                        self.end_run();
+                        return;
                     }
+                    playback.poll(&mut frame);
                 }
 
-                // Keyboard fallback
-                if is_key_pressed(KeyCode::R) {
-                    // Consume any pending character input to prevent 'r' from appearing in name field
-                    let _ = get_char_pressed();
+                // Applied by the next world step
+                self.input = frame.play_input();
+                self.input_frame_time = frame.frame_time;
+            }
+            GameState::GameOver if self.initials_entry.is_some() => {
+                self.handle_initials_input(&frame);
+            }
+            GameState::GameOver => {
+                // Any tap or R returns to the menu
+                if !frame.taps.is_empty() || frame.is_pressed(Action::Restart) {
                     self.reset();
                 }
             }
+            GameState::Options => self.handle_options_input(&frame),
         }
     }
 }
@@ -1884,7 +3927,7 @@ const ICON_64_BYTES: &[u8] = include_bytes!("../assets/icon_64x64.png");
 /// # Type Parameters
 ///
 /// * `LEN` - The expected buffer length in bytes (width × height × 4 channels).
-///           For example, a 16x16 icon needs 16 × 16 × 4 = 1024 bytes.
+///   For example, a 16x16 icon needs 16 × 16 × 4 = 1024 bytes.
 ///
 /// # Arguments
 ///
@@ -2003,6 +4046,39 @@ fn load_window_icon() -> Option<Icon> {
     }
 }
 
+/// Viewport `(x, y, width, height)` of the largest area with the game's
+/// aspect ratio that fits a window of the given size, centered
+fn letterbox(window_width: f32, window_height: f32) -> (i32, i32, i32, i32) {
+    let scale = (window_width / SCREEN_WIDTH).min(window_height / SCREEN_HEIGHT);
+    let (width, height) = (SCREEN_WIDTH * scale, SCREEN_HEIGHT * scale);
+    (
+        ((window_width - width) / 2.0) as i32,
+        ((window_height - height) / 2.0) as i32,
+        width as i32,
+        height as i32,
+    )
+}
+
+/// The all-time local leaderboard: each player's best `MAX_RUNS_PER_PLAYER` runs
+fn main_highscore_manager() -> HighscoreManager {
+    HighscoreManager::new(&storage::key("highscores.txt")).with_policy(LeaderboardPolicy {
+        retention: Retention::BestNPerPlayer(MAX_RUNS_PER_PLAYER),
+        window: TimeWindow::AllTime,
+    })
+}
+
+/// Window settings with the size and mode chosen on the command line,
+/// or else the ones saved on the options screen
+#[cfg(not(target_arch = "wasm32"))]
+fn desktop_window_conf(options: &cli::PlayOptions, settings: &Settings) -> Conf {
+    let mut conf = window_conf();
+    let scale = options.scale.unwrap_or(settings.window_scale);
+    conf.window_width = (SCREEN_WIDTH * scale) as i32;
+    conf.window_height = (SCREEN_HEIGHT * scale) as i32;
+    conf.fullscreen = options.fullscreen.unwrap_or(settings.fullscreen);
+    conf
+}
+
 fn window_conf() -> Conf {
     #[cfg(not(target_arch = "wasm32"))]
     {
@@ -2029,13 +4105,197 @@ fn window_conf() -> Conf {
     }
 }
 
+/// Run a one-off command-line task. Returns the process exit code.
 #[cfg(not(target_arch = "wasm32"))]
-#[macroquad::main(window_conf)]
-async fn main() {
+fn run_command(command: cli::Command) -> i32 {
+    let manager = main_highscore_manager();
+
+    match command {
+        cli::Command::Play(_) => 0,
+        cli::Command::Help => {
+            println!("{}", cli::USAGE);
+            0
+        }
+        cli::Command::ExportScores { path, .. } => match manager.export_to_file(&path) {
+            Ok(count) => {
+                println!("Exported {} scores to {}", count, path.display());
+                0
+            }
+            Err(err) => {
+                eprintln!("Export failed: {}", err);
+                1
+            }
+        },
+        cli::Command::AnalyzeTelemetry { mut paths, .. } => {
+            if paths.is_empty() {
+                paths.push(std::path::PathBuf::from(storage::key(TELEMETRY_FILE)));
+            }
+            let mut logs = Vec::with_capacity(paths.len());
+            for path in &paths {
+                match std::fs::read_to_string(path) {
+                    Ok(log) => logs.push(log),
+                    Err(err) => {
+                        eprintln!("Failed to read {}: {}", path.display(), err);
+                        return 1;
+                    }
+                }
+            }
+            print!("{}", telemetry::analyze(&logs));
+            0
+        }
+        cli::Command::ImportScores { path, mode, .. } => {
+            match manager.import_from_file(&path, mode) {
+                Ok(summary) => {
+                    println!(
+                        "Imported {} scores ({} duplicates skipped), \
+                         leaderboard now has {} entries",
+                        summary.added, summary.duplicates, summary.total
+                    );
+                    0
+                }
+                Err(err) => {
+                    eprintln!("Import failed: {}", err);
+                    1
+                }
+            }
+        }
+    }
+}
+
+/// Keep saved files in the directory given with `--save-dir`, exiting if
+/// it can't be used
+#[cfg(not(target_arch = "wasm32"))]
+fn use_save_dir(dir: Option<&std::path::Path>) {
+    if let Some(dir) = dir {
+        if let Err(err) = storage::set_save_dir(dir.to_path_buf()) {
+            eprintln!("Failed to use save directory {}: {}", dir.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Install the logger with the level from the command line, the
+/// environment or the default, in that order.
+#[cfg(not(target_arch = "wasm32"))]
+fn init_logging(options: &cli::PlayOptions) {
+    let level = options
+        .log_level
+        .or_else(logger::level_from_env)
+        .unwrap_or(logger::DEFAULT_LEVEL);
+
+    let game_logger = match &options.log_file {
+        Some(path) => GameLogger::new(level)
+            .with_file(path)
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to open log file {}: {}", path.display(), err);
+                GameLogger::new(level)
+            }),
+        None => GameLogger::new(level),
+    };
+    let _ = logger::init(game_logger);
+}
+
+/// Read a recording given with `--replay`
+#[cfg(not(target_arch = "wasm32"))]
+fn load_replay(path: &std::path::Path) -> Result<Replay, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    Replay::from_json(&json).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Simulate a run without a window (`--headless FRAMES`) and print how it
+/// went. Plays the recording's inputs if one is given, otherwise the bot
+/// (`--bot`) or an idle player. Returns the process exit code.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_headless(frames: u64, options: &cli::PlayOptions, playback: Option<Replay>) -> i32 {
+    let replay = playback.unwrap_or_else(|| {
+        Replay::new(
+            options.seed.unwrap_or_default(),
+            options.difficulty.unwrap_or_default(),
+            options.wave.unwrap_or(1),
+        )
+    });
+    rand::srand(replay.seed);
+    let mut world = replay.start();
+    let recorded = !replay.frames.is_empty();
+    let mut playback = ReplayInput::new(replay);
+    let mut bot = options.bot.then(BotInput::default);
+    let mut played = 0;
+    let mut seconds = 0.0;
+
+    while played < frames && !world.is_over() {
+        if recorded && playback.is_finished() {
+            break;
+        }
+        let mut frame = InputFrame::default();
+        if let Some(ref mut bot) = bot {
+            bot.observe(&world);
+            bot.poll(&mut frame);
+        }
+        playback.poll(&mut frame);
+
+        let dt = frame.frame_time.unwrap_or(HEADLESS_FRAME_TIME);
+        world.step(dt, &frame.play_input());
+        world.events.clear();
+        played += 1;
+        seconds += dt;
+    }
+
+    println!(
+        "Simulated {} frames ({:.1}s): wave {}, score {}, {}",
+        played,
+        seconds,
+        world.wave_number,
+        world.score,
+        if world.is_over() {
+            "defender line breached"
+        } else {
+            "still defending"
+        }
+    );
+    0
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(cli::Command::Play(options)) => {
+            init_logging(&options);
+            use_save_dir(options.save_dir.as_deref());
+            let playback = match options.replay.as_deref().map(load_replay).transpose() {
+                Ok(playback) => playback,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+            if let Some(frames) = options.headless {
+                std::process::exit(run_headless(frames, &options, playback));
+            }
+            let settings = *SettingsStore::new(&storage::key(SETTINGS_FILE)).get();
+            macroquad::Window::from_config(
+                desktop_window_conf(&options, &settings),
+                run_game(options, playback),
+            )
+        }
+        Ok(command) => {
+            use_save_dir(command.save_dir());
+            std::process::exit(run_command(command))
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_game(options: cli::PlayOptions, playback: Option<Replay>) {
     // Desktop version
     log::info!("Starting BumbleBees game (Desktop)");
 
     let mut game = Game::new().await;
+    game.apply_options(&options, playback);
 
     loop {
         let dt = get_frame_time();
@@ -2051,6 +4311,7 @@ async fn main() {
 #[cfg(target_arch = "wasm32")]
 #[macroquad::main(window_conf)]
 async fn main() {
+    let _ = logger::init(GameLogger::new(logger::DEFAULT_LEVEL));
     update_progress("Initializing game...");
 
     let mut game = Game::new().await;
@@ -2071,6 +4332,7 @@ async fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use systems::generate_wave;
 
     #[test]
     fn test_window_configuration() {
@@ -2081,12 +4343,23 @@ mod tests {
         assert!(!conf.window_resizable);
     }
 
+    #[test]
+    fn test_letterbox_keeps_aspect_ratio() {
+        assert_eq!(letterbox(SCREEN_WIDTH, SCREEN_HEIGHT), (0, 0, 1024, 575));
+        assert_eq!(letterbox(2048.0, 1150.0), (0, 0, 2048, 1150));
+        // Wider window: bars left and right
+        assert_eq!(letterbox(1920.0, 575.0), (448, 0, 1024, 575));
+        // Taller window: bars above and below
+        assert_eq!(letterbox(2048.0, 1536.0), (0, 193, 2048, 1150));
+    }
+
     #[test]
     fn test_game_state_transitions() {
         // Test game state enum values
         assert_eq!(GameState::Menu as u8, 0);
         assert_eq!(GameState::Playing as u8, 1);
         assert_eq!(GameState::GameOver as u8, 2);
+        assert_eq!(GameState::Options as u8, 3);
     }
 
     #[test]
@@ -2252,8 +4525,7 @@ mod tests {
         // All candidates should contain the filename
         for candidate in &candidates {
             assert!(
-                candidate.contains("resources/bg_main.png")
-                    || candidate.contains("bg_main.png"),
+                candidate.contains("resources/bg_main.png") || candidate.contains("bg_main.png"),
                 "Candidate '{}' should reference bg_main.png",
                 candidate
             );
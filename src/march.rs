@@ -0,0 +1,154 @@
+//! The march: a four-note bass loop under the gameplay.
+//!
+//! Like the heartbeat of the arcade original, the beat gets faster as the
+//! formation thins out and comes down. [`March`] only decides when which
+//! note is due; the notes themselves are synthesized by [`note`] and played
+//! by the audio manager.
+
+use crate::constants::*;
+use crate::wav::Pcm;
+
+/// Sample rate of the synthesized notes
+const SAMPLE_RATE: u32 = 44100;
+
+/// Sequencer of the march.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::march::March;
+///
+/// let mut march = March::new();
+/// // The first note sounds as soon as there is a formation
+/// assert_eq!(march.update(0.016, 50, Some(100.0)), Some(0));
+/// assert_eq!(march.update(0.016, 50, Some(100.0)), None);
+/// // No enemies, no march
+/// assert_eq!(march.update(10.0, 0, None), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct March {
+    /// Seconds until the next beat
+    timer: f32,
+    /// Next note of `MARCH_NOTES`
+    note: usize,
+    /// Enemies at the start of the wave
+    wave_size: usize,
+    /// Current seconds between beats
+    interval: f32,
+}
+
+impl March {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            interval: MARCH_SLOWEST_INTERVAL,
+            ..Self::default()
+        }
+    }
+
+    /// Seconds between beats with `enemies` of `wave_size` left and the
+    /// lowest enemy `distance` pixels above the defender line.
+    #[must_use]
+    pub fn tempo_interval(enemies: usize, wave_size: usize, distance: f32) -> f32 {
+        let remaining = (enemies as f32 / wave_size.max(1) as f32).clamp(0.0, 1.0);
+        let height = (distance / (SCREEN_HEIGHT - DEFENDER_LINE)).clamp(0.0, 1.0);
+        let urgency =
+            MARCH_COUNT_WEIGHT * (1.0 - remaining) + (1.0 - MARCH_COUNT_WEIGHT) * (1.0 - height);
+        MARCH_SLOWEST_INTERVAL * (1.0 - urgency) + MARCH_FASTEST_INTERVAL * urgency
+    }
+
+    /// Current seconds between beats.
+    #[must_use]
+    pub fn interval(&self) -> f32 {
+        self.interval
+    }
+
+    /// Start over, e.g. for a new run.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Advance by `dt` seconds with `enemies` left, the lowest at
+    /// `lowest_y`. Returns the index of the note to play, if one is due.
+    pub fn update(&mut self, dt: f32, enemies: usize, lowest_y: Option<f32>) -> Option<usize> {
+        let Some(lowest_y) = lowest_y.filter(|_| enemies > 0) else {
+            // Between waves: start the next one with its first note
+            self.timer = 0.0;
+            self.note = 0;
+            self.wave_size = 0;
+            return None;
+        };
+        // More enemies than before means a new wave
+        if enemies > self.wave_size {
+            self.wave_size = enemies;
+        }
+        let distance = SCREEN_HEIGHT - DEFENDER_LINE - lowest_y;
+        self.interval = Self::tempo_interval(enemies, self.wave_size, distance);
+
+        self.timer -= dt;
+        if self.timer > 0.0 {
+            return None;
+        }
+        // Don't let a long frame queue up beats
+        self.timer = (self.timer + self.interval).max(0.0);
+        let note = self.note;
+        self.note = (self.note + 1) % MARCH_NOTES.len();
+        Some(note)
+    }
+}
+
+/// A march note: a square wave at `frequency` Hz that dies away over
+/// `MARCH_NOTE_LENGTH` seconds.
+#[must_use]
+pub fn note(frequency: f32) -> Pcm {
+    let length = (MARCH_NOTE_LENGTH * SAMPLE_RATE as f32) as usize;
+    // A few milliseconds of fade in keep the start from clicking
+    let attack = SAMPLE_RATE as f32 * 0.003;
+    let samples = (0..length)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let square = if (t * frequency).fract() < 0.5 {
+                1.0
+            } else {
+                -1.0
+            };
+            let envelope = (i as f32 / attack).min(1.0) * (1.0 - i as f32 / length as f32).powi(2);
+            (square * envelope * 0.5 * f32::from(i16::MAX)) as i16
+        })
+        .collect();
+    Pcm {
+        sample_rate: SAMPLE_RATE,
+        channels: 1,
+        samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tempo_rises_as_formation_thins_and_descends() {
+        let top = SCREEN_HEIGHT - DEFENDER_LINE;
+        assert_eq!(March::tempo_interval(50, 50, top), MARCH_SLOWEST_INTERVAL);
+        assert_eq!(March::tempo_interval(0, 50, 0.0), MARCH_FASTEST_INTERVAL);
+        let thinned = March::tempo_interval(10, 50, top);
+        let descended = March::tempo_interval(50, 50, top / 4.0);
+        assert!(thinned < MARCH_SLOWEST_INTERVAL && descended < MARCH_SLOWEST_INTERVAL);
+        assert!(March::tempo_interval(10, 50, top / 4.0) < thinned.min(descended));
+
+        // The loop cycles through the notes at the current interval
+        let mut march = March::new();
+        let lowest = Some(SCREEN_HEIGHT - DEFENDER_LINE - top);
+        let mut notes = Vec::new();
+        for _ in 0..600 {
+            notes.extend(march.update(0.01, 50, lowest));
+        }
+        assert_eq!(notes.len(), (6.0 / MARCH_SLOWEST_INTERVAL).ceil() as usize);
+        assert_eq!(&notes[..5], &[0, 1, 2, 3, 0]);
+
+        let pcm = note(MARCH_NOTES[0]);
+        assert_eq!(pcm.samples.len(), (MARCH_NOTE_LENGTH * 44100.0) as usize);
+        assert_eq!(pcm.samples[0], 0);
+    }
+}
@@ -0,0 +1,450 @@
+//! Music playlists.
+//!
+//! A [`Manifest`] (`resources/music.json`) lists the music tracks and the
+//! playlists that use them. Each playlist belongs to a [`Scene`] and may be
+//! limited to a range of waves; [`Playlists`] picks the playlist for what
+//! is happening in the game and the track it plays. The
+//! [`AudioManager`](crate::audio::AudioManager) crossfades whenever the
+//! track changes.
+//!
+//! A playlist moves on to its next track every time it starts again, e.g.
+//! on a new run; with `shuffle` the order is random. On desktop, music
+//! files dropped into the `music` folder join the playlists of the scene
+//! their name starts with (see [`Manifest::add_user_tracks`]).
+
+use crate::constants::*;
+use serde::{Deserialize, Serialize};
+
+/// What is going on in the game, as far as the music cares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scene {
+    /// Menu, options and the other screens before a run
+    Menu,
+    /// A run in progress
+    Playing,
+    /// The bumblebee is on screen; falls back to the `playing` music
+    Boss,
+    /// The run just ended
+    GameOver,
+}
+
+impl Scene {
+    /// Scene of a user track called `name`: the scene its name starts
+    /// with, gameplay for everything else.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::music::Scene;
+    ///
+    /// assert_eq!(Scene::from_file_name("boss_theme.ogg"), Scene::Boss);
+    /// assert_eq!(Scene::from_file_name("Game Over.wav"), Scene::GameOver);
+    /// assert_eq!(Scene::from_file_name("my_song.ogg"), Scene::Playing);
+    /// ```
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    #[must_use]
+    pub fn from_file_name(name: &str) -> Self {
+        let name: String = name
+            .to_lowercase()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect();
+        if name.starts_with("menu") {
+            Self::Menu
+        } else if name.starts_with("boss") {
+            Self::Boss
+        } else if name.starts_with("gameover") {
+            Self::GameOver
+        } else {
+            Self::Playing
+        }
+    }
+}
+
+/// A music file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackInfo {
+    /// Name the playlists refer to
+    pub name: String,
+    /// Path of the file
+    pub file: String,
+    /// Volume at full music volume
+    #[serde(default = "default_track_volume")]
+    pub volume: f32,
+}
+
+fn default_track_volume() -> f32 {
+    MUSIC_VOLUME
+}
+
+/// Tracks played in one scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaylistInfo {
+    pub scene: Scene,
+    /// First and last wave the playlist is for; None for no limit
+    #[serde(default)]
+    pub first_wave: Option<u32>,
+    #[serde(default)]
+    pub last_wave: Option<u32>,
+    /// Names of the tracks
+    pub tracks: Vec<String>,
+    /// Play the tracks in random order
+    #[serde(default)]
+    pub shuffle: bool,
+    /// Loop the track; a jingle plays once
+    #[serde(default = "default_looped")]
+    pub looped: bool,
+}
+
+fn default_looped() -> bool {
+    true
+}
+
+impl PlaylistInfo {
+    /// Whether the playlist is for `scene` at wave `wave`.
+    #[must_use]
+    pub fn matches(&self, scene: Scene, wave: u32) -> bool {
+        self.scene == scene
+            && self.first_wave.is_none_or(|first| wave >= first)
+            && self.last_wave.is_none_or(|last| wave <= last)
+    }
+}
+
+/// All music tracks and playlists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub tracks: Vec<TrackInfo>,
+    pub playlists: Vec<PlaylistInfo>,
+}
+
+impl Default for Manifest {
+    /// The bundled tracks: intro in the menu, background music during a
+    /// run and a jingle at game over.
+    fn default() -> Self {
+        let track = |name: &str, file: &str, volume: f32| TrackInfo {
+            name: name.to_string(),
+            file: file.to_string(),
+            volume,
+        };
+        let playlist = |scene: Scene, name: &str, looped: bool| PlaylistInfo {
+            scene,
+            first_wave: None,
+            last_wave: None,
+            tracks: vec![name.to_string()],
+            shuffle: false,
+            looped,
+        };
+        Self {
+            tracks: vec![
+                track("intro", "resources/intro.ogg", INTRO_VOLUME),
+                track("background", "resources/music_background.ogg", MUSIC_VOLUME),
+                track("game_over", "resources/music_game_over.wav", INTRO_VOLUME),
+            ],
+            playlists: vec![
+                playlist(Scene::Menu, "intro", true),
+                playlist(Scene::Playing, "background", true),
+                playlist(Scene::GameOver, "game_over", false),
+            ],
+        }
+    }
+}
+
+impl Manifest {
+    /// Parse a manifest file.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error if `json` isn't a manifest.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|err| err.to_string())
+    }
+
+    /// Add music files of the player: each joins every playlist of the
+    /// scene its file name starts with (`menu`, `boss`, `game_over`; all
+    /// others are gameplay music). A boss track without a boss playlist
+    /// gets one.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn add_user_tracks(&mut self, files: &[String]) {
+        for file in files {
+            let file_name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+            let name = format!("user:{}", file_name);
+            let scene = Scene::from_file_name(file_name);
+            self.tracks.push(TrackInfo {
+                name: name.clone(),
+                file: file.clone(),
+                volume: MUSIC_VOLUME,
+            });
+            let mut joined = false;
+            for playlist in self.playlists.iter_mut().filter(|p| p.scene == scene) {
+                playlist.tracks.push(name.clone());
+                joined = true;
+            }
+            if !joined {
+                self.playlists.push(PlaylistInfo {
+                    scene,
+                    first_wave: None,
+                    last_wave: None,
+                    tracks: vec![name],
+                    shuffle: false,
+                    looped: scene != Scene::GameOver,
+                });
+            }
+        }
+    }
+}
+
+/// Music files (`.ogg` and `.wav`) in the player's music folder `dir`,
+/// sorted by name and at most `USER_MUSIC_MAX_TRACKS` of them.
+#[cfg(not(target_arch = "wasm32"))]
+#[must_use]
+pub fn user_music_files(dir: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    matches!(extension.to_ascii_lowercase().as_str(), "ogg" | "wav")
+                })
+        })
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    if files.len() > USER_MUSIC_MAX_TRACKS {
+        log::warn!(
+            "Only the first {} tracks of {} are used",
+            USER_MUSIC_MAX_TRACKS,
+            dir
+        );
+        files.truncate(USER_MUSIC_MAX_TRACKS);
+    }
+    files
+}
+
+/// A playlist with its tracks resolved to track numbers.
+#[derive(Debug, Clone)]
+struct Playlist {
+    info: PlaylistInfo,
+    /// Track numbers in playing order
+    order: Vec<usize>,
+    /// Position in `order`; None before the first track
+    position: Option<usize>,
+}
+
+/// Picks the music for the current scene.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::music::{Manifest, Playlists, Scene};
+///
+/// let mut playlists = Playlists::new(&Manifest::default(), 1);
+/// // The menu plays the intro, looped
+/// assert_eq!(playlists.select(Scene::Menu, 1), Some((0, true)));
+/// // No boss music in the bundled manifest: the gameplay music goes on
+/// assert_eq!(playlists.select(Scene::Boss, 3), Some((1, true)));
+/// // The game over jingle plays once
+/// assert_eq!(playlists.select(Scene::GameOver, 3), Some((2, false)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Playlists {
+    playlists: Vec<Playlist>,
+    /// Playlist heard right now
+    active: Option<usize>,
+    /// Xorshift state for shuffling
+    rng: u32,
+}
+
+impl Playlists {
+    /// Playlists of a manifest. Names without a track are skipped;
+    /// `seed` drives the shuffling.
+    #[must_use]
+    pub fn new(manifest: &Manifest, seed: u32) -> Self {
+        let playlists = manifest
+            .playlists
+            .iter()
+            .map(|info| {
+                let order = info
+                    .tracks
+                    .iter()
+                    .filter_map(|name| {
+                        let index = manifest.tracks.iter().position(|t| &t.name == name);
+                        if index.is_none() {
+                            log::warn!("Playlist refers to unknown track {}", name);
+                        }
+                        index
+                    })
+                    .collect();
+                Playlist {
+                    info: info.clone(),
+                    order,
+                    position: None,
+                }
+            })
+            .collect();
+        Self {
+            playlists,
+            active: None,
+            // Xorshift never leaves zero
+            rng: seed.max(1),
+        }
+    }
+
+    /// Track number to play and whether it loops, or None for silence.
+    /// Called every frame; a playlist that takes over from another one
+    /// moves on to its next track.
+    pub fn select(&mut self, scene: Scene, wave: u32) -> Option<(usize, bool)> {
+        let index = self.find(scene, wave).or_else(|| {
+            // Without boss music the gameplay music goes on
+            (scene == Scene::Boss)
+                .then(|| self.find(Scene::Playing, wave))
+                .flatten()
+        })?;
+        if self.active != Some(index) {
+            self.active = Some(index);
+            self.advance(index);
+        }
+        let playlist = &self.playlists[index];
+        let track = playlist.order[playlist.position?];
+        Some((track, playlist.info.looped))
+    }
+
+    /// First playlist with tracks for the scene and wave.
+    fn find(&self, scene: Scene, wave: u32) -> Option<usize> {
+        self.playlists
+            .iter()
+            .position(|p| !p.order.is_empty() && p.info.matches(scene, wave))
+    }
+
+    /// Move a playlist on to its next track, shuffling again after the
+    /// last one.
+    fn advance(&mut self, index: usize) {
+        let next = self.playlists[index]
+            .position
+            .map_or(0, |position| position + 1);
+        let next = if next >= self.playlists[index].order.len() {
+            0
+        } else {
+            next
+        };
+        if next == 0 && self.playlists[index].info.shuffle {
+            self.shuffle(index);
+        }
+        self.playlists[index].position = Some(next);
+    }
+
+    /// Fisher-Yates shuffle of a playlist's order.
+    fn shuffle(&mut self, index: usize) {
+        for i in (1..self.playlists[index].order.len()).rev() {
+            let j = self.next_random() as usize % (i + 1);
+            self.playlists[index].order.swap(i, j);
+        }
+    }
+
+    fn next_random(&mut self) -> u32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "tracks": [
+            { "name": "menu", "file": "menu.ogg" },
+            { "name": "early", "file": "early.ogg", "volume": 0.4 },
+            { "name": "late_a", "file": "late_a.ogg" },
+            { "name": "late_b", "file": "late_b.ogg" },
+            { "name": "boss", "file": "boss.ogg" },
+            { "name": "jingle", "file": "jingle.ogg" }
+        ],
+        "playlists": [
+            { "scene": "menu", "tracks": ["menu"] },
+            { "scene": "playing", "last_wave": 4, "tracks": ["early"] },
+            { "scene": "playing", "first_wave": 5, "tracks": ["late_a", "late_b"] },
+            { "scene": "boss", "first_wave": 3, "tracks": ["boss", "missing"] },
+            { "scene": "game_over", "tracks": ["jingle"], "looped": false }
+        ]
+    }"#;
+
+    #[test]
+    fn test_playlists_follow_scene_and_wave() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        assert_eq!(manifest.tracks[0].volume, MUSIC_VOLUME);
+        let mut playlists = Playlists::new(&manifest, 7);
+
+        assert_eq!(playlists.select(Scene::Menu, 1), Some((0, true)));
+        assert_eq!(playlists.select(Scene::Playing, 1), Some((1, true)));
+        assert_eq!(playlists.select(Scene::Playing, 4), Some((1, true)));
+        // Before wave 3 there is no boss music
+        assert_eq!(playlists.select(Scene::Boss, 2), Some((1, true)));
+        assert_eq!(playlists.select(Scene::Boss, 3), Some((4, true)));
+        assert_eq!(playlists.select(Scene::Playing, 5), Some((2, true)));
+        // Staying in the playlist keeps the track
+        assert_eq!(playlists.select(Scene::Playing, 6), Some((2, true)));
+        assert_eq!(playlists.select(Scene::GameOver, 6), Some((5, false)));
+        // The next run through the late waves plays the next track
+        assert_eq!(playlists.select(Scene::Playing, 5), Some((3, true)));
+        assert_eq!(playlists.select(Scene::Menu, 5), Some((0, true)));
+        assert_eq!(playlists.select(Scene::Playing, 5), Some((2, true)));
+
+        assert!(Manifest::parse("{\"tracks\": []}").is_err());
+    }
+
+    #[test]
+    fn test_shuffle_and_user_tracks() {
+        let mut manifest = Manifest::default();
+        manifest.add_user_tracks(&[
+            "music/Boss Fight.ogg".to_string(),
+            "music/a.ogg".to_string(),
+            "music/b.ogg".to_string(),
+            "music/c.ogg".to_string(),
+        ]);
+        assert_eq!(manifest.tracks.len(), 7);
+        assert_eq!(manifest.playlists[1].tracks.len(), 4);
+        let boss = manifest.playlists.last().unwrap();
+        assert_eq!((boss.scene, boss.looped), (Scene::Boss, true));
+        assert_eq!(boss.tracks, vec!["user:Boss Fight.ogg".to_string()]);
+
+        // A shuffled playlist plays every track once per round
+        manifest.playlists[1].shuffle = true;
+        let mut playlists = Playlists::new(&manifest, 99);
+        let mut round: Vec<usize> = (0..4)
+            .filter_map(|_| {
+                let track = playlists.select(Scene::Playing, 1);
+                playlists.select(Scene::Menu, 1);
+                track.map(|(track, _)| track)
+            })
+            .collect();
+        round.sort_unstable();
+        assert_eq!(round, vec![1, 4, 5, 6]);
+        assert_eq!(playlists.select(Scene::Boss, 1), Some((3, true)));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_user_music_folder() {
+        let test_dir = "test_user_music";
+        let _ = std::fs::remove_dir_all(test_dir);
+        assert!(user_music_files(test_dir).is_empty());
+
+        std::fs::create_dir_all(test_dir).unwrap();
+        for name in ["b.OGG", "a.wav", "notes.txt"] {
+            std::fs::write(format!("{}/{}", test_dir, name), b"").unwrap();
+        }
+        let files = user_music_files(test_dir);
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("a.wav") && files[1].ends_with("b.OGG"));
+
+        let _ = std::fs::remove_dir_all(test_dir);
+    }
+}
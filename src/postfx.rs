@@ -0,0 +1,451 @@
+//! Post-processing with full-screen shader passes.
+//!
+//! With any [`Pass`] switched on, the game draws the scene into an
+//! offscreen render target instead of the window. [`PostFx::present`] then
+//! runs the passes one after the other, each reading the output of the one
+//! before, and draws the result into the letterboxed window:
+//!
+//! 1. C64 wave: the bundled `c64_wave` shader wobbles and color-cycles the
+//!    titles, which are drawn onto a layer of their own
+//! 2. Palette: colors snap to the 16 colors of the C64
+//! 3. Bloom: bright pixels glow
+//! 4. CRT: scanlines, screen curvature and dark corners
+//!
+//! Every pass has its own uniforms, set each frame from the game time and
+//! the screen effects setting (see [`Pass::uniforms`]). A shader that fails
+//! to compile only disables its pass.
+
+use crate::constants::*;
+use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// One post-processing step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pass {
+    C64Wave,
+    Palette,
+    Bloom,
+    Crt,
+}
+
+impl Pass {
+    /// All passes in the order they run.
+    pub const ALL: [Self; 4] = [Self::C64Wave, Self::Palette, Self::Bloom, Self::Crt];
+
+    /// Name shown on the options screen.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::C64Wave => "C64",
+            Self::Palette => "Palette",
+            Self::Bloom => "Bloom",
+            Self::Crt => "CRT",
+        }
+    }
+
+    /// Whether the pass works on the whole screen. The C64 wave only
+    /// touches the title layer and keeps running at zero intensity, where
+    /// the titles still cycle colors.
+    #[must_use]
+    pub const fn is_full_screen(self) -> bool {
+        !matches!(self, Self::C64Wave)
+    }
+
+    /// Uniforms of the pass and their values this frame: `time` in
+    /// seconds, `intensity` from the screen effects setting (0 to 1).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::postfx::{Pass, Uniform};
+    ///
+    /// let uniforms = Pass::Crt.uniforms(2.0, 0.5);
+    /// assert!(uniforms.contains(&("intensity", Uniform::Float(0.5))));
+    /// ```
+    #[must_use]
+    pub fn uniforms(self, time: f32, intensity: f32) -> Vec<(&'static str, Uniform)> {
+        let resolution = Uniform::Vec2(SCREEN_WIDTH, SCREEN_HEIGHT);
+        let intensity = Uniform::Float(intensity.clamp(0.0, 1.0));
+        match self {
+            Self::C64Wave => vec![("time", Uniform::Float(time)), ("intensity", intensity)],
+            Self::Palette => vec![("intensity", intensity)],
+            Self::Bloom => vec![
+                ("intensity", intensity),
+                ("resolution", resolution),
+                ("threshold", Uniform::Float(POSTFX_BLOOM_THRESHOLD)),
+            ],
+            Self::Crt => vec![
+                ("time", Uniform::Float(time)),
+                ("intensity", intensity),
+                ("resolution", resolution),
+            ],
+        }
+    }
+
+    fn shader(self) -> ShaderSource<'static> {
+        let vertex = include_str!("../shaders/postfx.vert");
+        match self {
+            Self::C64Wave => ShaderSource::Glsl {
+                vertex: include_str!("../shaders/c64_wave.vert"),
+                fragment: include_str!("../shaders/c64_wave.frag"),
+            },
+            Self::Palette => ShaderSource::Glsl {
+                vertex,
+                fragment: include_str!("../shaders/palette.frag"),
+            },
+            Self::Bloom => ShaderSource::Glsl {
+                vertex,
+                fragment: include_str!("../shaders/bloom.frag"),
+            },
+            Self::Crt => ShaderSource::Glsl {
+                vertex,
+                fragment: include_str!("../shaders/crt.frag"),
+            },
+        }
+    }
+
+    fn load_material(self) -> Option<Material> {
+        let uniforms = self
+            .uniforms(0.0, 0.0)
+            .into_iter()
+            .map(|(name, value)| UniformDesc::new(name, value.uniform_type()))
+            .collect();
+        // The title layer is laid over the scene; the other passes replace it
+        let pipeline_params = if self.is_full_screen() {
+            PipelineParams::default()
+        } else {
+            PipelineParams {
+                color_blend: Some(BlendState::new(
+                    Equation::Add,
+                    BlendFactor::Value(BlendValue::SourceAlpha),
+                    BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                )),
+                ..Default::default()
+            }
+        };
+        load_material(
+            self.shader(),
+            MaterialParams {
+                pipeline_params,
+                uniforms,
+                ..Default::default()
+            },
+        )
+        .map_err(|err| log::warn!("{} shader unavailable: {}", self.name(), err))
+        .ok()
+    }
+}
+
+/// Value of a shader uniform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Uniform {
+    Float(f32),
+    Vec2(f32, f32),
+}
+
+impl Uniform {
+    fn uniform_type(self) -> UniformType {
+        match self {
+            Self::Float(_) => UniformType::Float1,
+            Self::Vec2(..) => UniformType::Float2,
+        }
+    }
+
+    fn apply(self, material: &Material, name: &str) {
+        match self {
+            Self::Float(value) => material.set_uniform(name, value),
+            Self::Vec2(x, y) => material.set_uniform(name, [x, y]),
+        }
+    }
+}
+
+/// Which passes are switched on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PassSet {
+    pub c64_wave: bool,
+    pub palette: bool,
+    pub bloom: bool,
+    pub crt: bool,
+}
+
+impl Default for PassSet {
+    /// Only the C64 titles, as the game looked before post-processing.
+    fn default() -> Self {
+        Self::NONE.with(Pass::C64Wave)
+    }
+}
+
+impl PassSet {
+    /// No passes.
+    pub const NONE: Self = Self {
+        c64_wave: false,
+        palette: false,
+        bloom: false,
+        crt: false,
+    };
+
+    /// Combinations offered on the options screen, in order.
+    pub const PRESETS: [Self; 6] = [
+        Self::NONE,
+        Self::NONE.with(Pass::C64Wave),
+        Self::NONE.with(Pass::C64Wave).with(Pass::Crt),
+        Self::NONE
+            .with(Pass::C64Wave)
+            .with(Pass::Bloom)
+            .with(Pass::Crt),
+        Self::NONE.with(Pass::C64Wave).with(Pass::Palette),
+        Self {
+            c64_wave: true,
+            palette: true,
+            bloom: true,
+            crt: true,
+        },
+    ];
+
+    /// The set with `pass` switched on.
+    #[must_use]
+    pub const fn with(self, pass: Pass) -> Self {
+        match pass {
+            Pass::C64Wave => Self {
+                c64_wave: true,
+                ..self
+            },
+            Pass::Palette => Self {
+                palette: true,
+                ..self
+            },
+            Pass::Bloom => Self {
+                bloom: true,
+                ..self
+            },
+            Pass::Crt => Self { crt: true, ..self },
+        }
+    }
+
+    #[must_use]
+    pub const fn contains(self, pass: Pass) -> bool {
+        match pass {
+            Pass::C64Wave => self.c64_wave,
+            Pass::Palette => self.palette,
+            Pass::Bloom => self.bloom,
+            Pass::Crt => self.crt,
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self == Self::NONE
+    }
+
+    /// The next (`step` 1) or previous (-1) preset. A hand-edited set that
+    /// is no preset continues from the first one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::postfx::PassSet;
+    ///
+    /// assert_eq!(PassSet::NONE.cycle(-1), PassSet::PRESETS[5]);
+    /// assert_eq!(PassSet::PRESETS[5].cycle(1), PassSet::NONE);
+    /// ```
+    #[must_use]
+    pub fn cycle(self, step: isize) -> Self {
+        let count = Self::PRESETS.len() as isize;
+        let index = Self::PRESETS
+            .iter()
+            .position(|&preset| preset == self)
+            .unwrap_or(0) as isize;
+        Self::PRESETS[(index + step).rem_euclid(count) as usize]
+    }
+
+    /// Names of the passes, e.g. "C64 + CRT"; None without passes.
+    #[must_use]
+    pub fn label(self) -> Option<String> {
+        let names: Vec<&str> = Pass::ALL
+            .into_iter()
+            .filter(|&pass| self.contains(pass))
+            .map(Pass::name)
+            .collect();
+        (!names.is_empty()).then(|| names.join(" + "))
+    }
+
+    /// The passes that do something at this intensity: full-screen passes
+    /// at zero intensity are skipped.
+    #[must_use]
+    pub fn active(self, intensity: f32) -> Self {
+        if intensity > 0.0 {
+            return self;
+        }
+        Self {
+            c64_wave: self.c64_wave,
+            ..Self::NONE
+        }
+    }
+}
+
+/// Render targets and shaders of the post-processing passes.
+///
+/// Needs the graphics context, so it is created after the window opens.
+pub struct PostFx {
+    /// The scene as the game draws it
+    scene: RenderTarget,
+    /// Transparent layer for the C64 titles
+    titles: RenderTarget,
+    /// Outputs of the full-screen passes, used in turn
+    swap: [RenderTarget; 2],
+    /// Shader of each pass, None if it failed to compile
+    materials: Vec<Option<Material>>,
+}
+
+impl Default for PostFx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PostFx {
+    /// Create the render targets at the size of the game area and compile
+    /// the shaders.
+    #[must_use]
+    pub fn new() -> Self {
+        let target = || {
+            let target = render_target(SCREEN_WIDTH as u32, SCREEN_HEIGHT as u32);
+            target.texture.set_filter(FilterMode::Nearest);
+            target
+        };
+        Self {
+            scene: target(),
+            titles: target(),
+            swap: [target(), target()],
+            materials: Pass::ALL.into_iter().map(Pass::load_material).collect(),
+        }
+    }
+
+    /// The passes of `passes` that compiled and do something at this
+    /// intensity.
+    #[must_use]
+    pub fn usable(&self, passes: PassSet, intensity: f32) -> PassSet {
+        let passes = passes.active(intensity);
+        Pass::ALL
+            .into_iter()
+            .filter(|&pass| passes.contains(pass) && self.material(pass).is_some())
+            .fold(PassSet::NONE, PassSet::with)
+    }
+
+    fn material(&self, pass: Pass) -> Option<&Material> {
+        self.materials[pass as usize].as_ref()
+    }
+
+    /// Camera drawing the game area into the offscreen scene.
+    #[must_use]
+    pub fn scene_camera(&self) -> Camera2D {
+        Self::target_camera(&self.scene)
+    }
+
+    /// Camera drawing onto the title layer of the C64 wave.
+    #[must_use]
+    pub fn titles_camera(&self) -> Camera2D {
+        Self::target_camera(&self.titles)
+    }
+
+    fn target_camera(target: &RenderTarget) -> Camera2D {
+        let mut camera =
+            Camera2D::from_display_rect(Rect::new(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT));
+        camera.render_target = Some(target.clone());
+        camera
+    }
+
+    /// Run `passes` over the drawn scene and show the result through
+    /// `screen`, the camera of the window.
+    pub fn present(&self, passes: PassSet, time: f32, intensity: f32, screen: &Camera2D) {
+        let mut source = self.scene.texture.clone();
+        let mut outputs = self.swap.iter().cycle();
+        for pass in Pass::ALL.into_iter().filter(|&pass| passes.contains(pass)) {
+            let Some(material) = self.material(pass) else {
+                continue;
+            };
+            if pass.is_full_screen() {
+                let Some(output) = outputs.next() else {
+                    continue;
+                };
+                set_camera(&Self::target_camera(output));
+                Self::run(material, pass, time, intensity, &source);
+                source = output.texture.clone();
+            } else {
+                set_camera(&self.scene_camera());
+                Self::run(material, pass, time, intensity, &self.titles.texture);
+            }
+        }
+
+        set_camera(screen);
+        draw_texture_ex(
+            &source,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(SCREEN_WIDTH, SCREEN_HEIGHT)),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Draw `texture` over the whole game area through a pass's shader.
+    fn run(material: &Material, pass: Pass, time: f32, intensity: f32, texture: &Texture2D) {
+        for (name, value) in pass.uniforms(time, intensity) {
+            value.apply(material, name);
+        }
+        gl_use_material(material);
+        draw_texture_ex(
+            texture,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(SCREEN_WIDTH, SCREEN_HEIGHT)),
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pass_sets_and_uniforms() {
+        // Every preset is reached once per round
+        let mut set = PassSet::NONE;
+        for preset in PassSet::PRESETS.iter().skip(1) {
+            set = set.cycle(1);
+            assert_eq!(set, *preset);
+        }
+        assert_eq!(PassSet::PRESETS[3].label().unwrap(), "C64 + Bloom + CRT");
+        assert_eq!(PassSet::NONE.label(), None);
+
+        // Settings files list the passes by name; missing ones keep their
+        // defaults, and sets that are no preset start the cycle over
+        let set: PassSet = serde_json::from_str(r#"{"crt": true}"#).unwrap();
+        assert_eq!(set, PassSet::PRESETS[2]);
+        let set: PassSet = serde_json::from_str(r#"{"c64_wave": false, "bloom": true}"#).unwrap();
+        assert_eq!(set.label().unwrap(), "Bloom");
+        assert_eq!(set.cycle(1), PassSet::PRESETS[1]);
+
+        // Without intensity only the titles are drawn through a shader
+        let all = PassSet::PRESETS[5];
+        assert_eq!(all.active(0.0), PassSet::default());
+        assert_eq!(all.active(0.25), all);
+
+        for pass in Pass::ALL {
+            let uniforms = pass.uniforms(3.0, 2.0);
+            assert!(uniforms.contains(&("intensity", Uniform::Float(1.0))));
+            if pass != Pass::Palette && pass != Pass::Bloom {
+                assert!(uniforms.contains(&("time", Uniform::Float(3.0))));
+            }
+        }
+    }
+}
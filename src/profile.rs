@@ -0,0 +1,271 @@
+//! Player profiles with lifetime stats.
+//!
+//! Profiles are keyed by player name and remember which name was used
+//! last, so the menu can pre-fill it. Storage follows `HighscoreManager`:
+//! a JSON file on desktop and a localStorage entry on WASM.
+
+use crate::difficulty::Difficulty;
+use crate::stats::RunStats;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Maximum number of profiles kept; the least recently played are dropped.
+const MAX_PROFILES: usize = 100;
+
+/// Lifetime stats of one player.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerProfile {
+    /// Player name (as typed in the menu or entered as initials)
+    pub name: String,
+    /// Finished runs
+    pub games_played: u32,
+    /// Enemies destroyed over all runs
+    pub total_kills: u32,
+    /// Highest wave reached
+    pub best_wave: u32,
+    /// Highest score
+    pub best_score: u32,
+    /// Seconds spent playing
+    pub play_time: f32,
+    /// Finished runs per difficulty
+    pub games_per_difficulty: BTreeMap<Difficulty, u32>,
+    /// Sequence number of the last run, used to order profiles by recency
+    pub last_played: u64,
+}
+
+impl PlayerProfile {
+    /// Create an empty profile.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Add a finished run to the lifetime stats.
+    pub fn record_run(&mut self, stats: &RunStats, wave: u32, score: u32, difficulty: Difficulty) {
+        self.games_played += 1;
+        self.total_kills += stats.kills.total();
+        self.best_wave = self.best_wave.max(wave);
+        self.best_score = self.best_score.max(score);
+        self.play_time += stats.duration;
+        *self.games_per_difficulty.entry(difficulty).or_insert(0) += 1;
+    }
+
+    /// Most played difficulty; ties go to the harder one.
+    #[must_use]
+    pub fn favorite_difficulty(&self) -> Option<Difficulty> {
+        Difficulty::ALL
+            .iter()
+            .copied()
+            .filter_map(|difficulty| {
+                let games = self.games_per_difficulty.get(&difficulty).copied()?;
+                (games > 0).then_some((games, difficulty))
+            })
+            .max()
+            .map(|(_, difficulty)| difficulty)
+    }
+}
+
+/// Everything persisted by [`ProfileManager`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfileStore {
+    /// Name used for the most recent run
+    last_used: Option<String>,
+    /// Known profiles, in no particular order
+    profiles: Vec<PlayerProfile>,
+    /// Counter handed out as `PlayerProfile::last_played`
+    run_counter: u64,
+}
+
+/// Cross-platform player profile persistence.
+///
+/// Uses the same storage backends as `HighscoreManager`:
+/// - **Desktop**: JSON file named by the storage key
+/// - **WASM**: Browser localStorage entry named by the storage key
+///
+/// Unreadable or corrupted storage starts with no profiles, so the game
+/// always starts.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bumblebees::difficulty::Difficulty;
+/// use bumblebees::profile::ProfileManager;
+/// use bumblebees::stats::RunStats;
+///
+/// let mut profiles = ProfileManager::new("profiles.json");
+/// profiles.record_run("PLAYER1", &RunStats::new(), 3, 1200, Difficulty::Normal);
+/// assert_eq!(profiles.last_used_name(), Some("PLAYER1"));
+/// ```
+pub struct ProfileManager {
+    /// Storage key: filename (desktop) or localStorage key (WASM)
+    storage_key: String,
+    store: ProfileStore,
+}
+
+impl ProfileManager {
+    /// Load profiles from the given storage key.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        let store = storage::load(key)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            storage_key: key.to_string(),
+            store,
+        }
+    }
+
+    /// Name used for the most recent run, if any.
+    #[must_use]
+    pub fn last_used_name(&self) -> Option<&str> {
+        self.store.last_used.as_deref()
+    }
+
+    /// Profile of the named player, if they have played before.
+    #[must_use]
+    pub fn profile(&self, name: &str) -> Option<&PlayerProfile> {
+        self.store
+            .profiles
+            .iter()
+            .find(|profile| profile.name == name)
+    }
+
+    /// All profiles, most recently played first.
+    #[must_use]
+    pub fn profiles(&self) -> Vec<&PlayerProfile> {
+        let mut profiles: Vec<_> = self.store.profiles.iter().collect();
+        profiles.sort_by_key(|profile| std::cmp::Reverse(profile.last_played));
+        profiles
+    }
+
+    /// Add a finished run to the player's profile (creating it on their
+    /// first run), remember the name as last used, and persist.
+    pub fn record_run(
+        &mut self,
+        name: &str,
+        stats: &RunStats,
+        wave: u32,
+        score: u32,
+        difficulty: Difficulty,
+    ) {
+        self.store.run_counter += 1;
+        let run_counter = self.store.run_counter;
+
+        let index = match self
+            .store
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+        {
+            Some(index) => index,
+            None => {
+                self.store.profiles.push(PlayerProfile::new(name));
+                self.store.profiles.len() - 1
+            }
+        };
+
+        let profile = &mut self.store.profiles[index];
+        profile.record_run(stats, wave, score, difficulty);
+        profile.last_played = run_counter;
+        self.store.last_used = Some(name.to_string());
+
+        // Drop the least recently played profiles beyond the limit
+        self.store
+            .profiles
+            .sort_by_key(|profile| std::cmp::Reverse(profile.last_played));
+        self.store.profiles.truncate(MAX_PROFILES);
+
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Ok(json) = serde_json::to_string(&self.store) {
+            storage::save(&self.storage_key, &json);
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use crate::entities::EnemyType;
+
+    fn run(kills: u32, duration: f32) -> RunStats {
+        let mut stats = RunStats::new();
+        for _ in 0..kills {
+            stats.record_kill(EnemyType::Standard);
+        }
+        stats.duration = duration;
+        stats
+    }
+
+    #[test]
+    fn test_record_run_accumulates_lifetime_stats() {
+        let test_file = "test_profiles_lifetime.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut manager = ProfileManager::new(test_file);
+        assert!(manager.last_used_name().is_none());
+
+        manager.record_run("Alice", &run(10, 60.0), 3, 500, Difficulty::Normal);
+        manager.record_run("Alice", &run(5, 30.0), 2, 800, Difficulty::Hard);
+        manager.record_run("Alice", &run(1, 10.0), 1, 100, Difficulty::Hard);
+
+        let alice = manager.profile("Alice").unwrap();
+        assert_eq!(alice.games_played, 3);
+        assert_eq!(alice.total_kills, 16);
+        assert_eq!(alice.best_wave, 3);
+        assert_eq!(alice.best_score, 800);
+        assert_eq!(alice.play_time, 100.0);
+        assert_eq!(alice.favorite_difficulty(), Some(Difficulty::Hard));
+
+        let _ = std::fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_profiles_persist_with_last_used_name() {
+        let test_file = "test_profiles_persist.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut manager = ProfileManager::new(test_file);
+        manager.record_run("Alice", &run(1, 1.0), 1, 10, Difficulty::Easy);
+        manager.record_run("Bob", &run(2, 2.0), 2, 20, Difficulty::Normal);
+
+        let reloaded = ProfileManager::new(test_file);
+        assert_eq!(reloaded.last_used_name(), Some("Bob"));
+        let names: Vec<_> = reloaded
+            .profiles()
+            .iter()
+            .map(|profile| profile.name.as_str())
+            .collect();
+        assert_eq!(names, ["Bob", "Alice"]);
+        assert_eq!(reloaded.profile("Alice").unwrap().total_kills, 1);
+
+        let _ = std::fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_corrupted_storage_starts_empty() {
+        let test_file = "test_profiles_corrupted.json";
+        std::fs::write(test_file, "not json").unwrap();
+
+        let manager = ProfileManager::new(test_file);
+        assert!(manager.profiles().is_empty());
+        assert!(manager.last_used_name().is_none());
+
+        let _ = std::fs::remove_file(test_file);
+    }
+
+    #[test]
+    fn test_favorite_difficulty_without_games() {
+        assert_eq!(PlayerProfile::new("Nobody").favorite_difficulty(), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Run recordings.
+//!
+//! A [`Replay`] stores what is needed to play a run again: the random seed,
+//! difficulty and start wave, plus the frame time and [`PlayInput`] of every
+//! gameplay frame. Because [`World`] is deterministic for a given seed,
+//! stepping a fresh world with the recorded frames reproduces the run.
+//!
+//! The game records every run and saves the last one as
+//! [`REPLAY_FILE`](crate::constants::REPLAY_FILE); `--replay FILE` plays a
+//! recording back, `--headless` simulates it without a window.
+
+use crate::difficulty::Difficulty;
+use crate::world::{PlayInput, World};
+use serde::{Deserialize, Serialize};
+
+/// Format version written into every recording.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// One recorded gameplay frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    /// Seconds the frame advanced the world
+    pub dt: f32,
+    /// Input applied during the frame
+    #[serde(flatten)]
+    pub input: PlayInput,
+}
+
+/// A recorded run.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::difficulty::Difficulty;
+/// use bumblebees::replay::Replay;
+/// use bumblebees::world::PlayInput;
+///
+/// let mut replay = Replay::new(42, Difficulty::Hard, 1);
+/// replay.record(1.0 / 60.0, PlayInput { fire: true, ..PlayInput::default() });
+///
+/// let loaded = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+/// assert_eq!(loaded, replay);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// Seed the random generator was started with
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub start_wave: u32,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Start an empty recording.
+    #[must_use]
+    pub fn new(seed: u64, difficulty: Difficulty, start_wave: u32) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            difficulty,
+            start_wave,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Append a gameplay frame.
+    pub fn record(&mut self, dt: f32, input: PlayInput) {
+        self.frames.push(ReplayFrame { dt, input });
+    }
+
+    /// The world the recording starts from.
+    #[must_use]
+    pub fn start(&self) -> World {
+        World::new(self.difficulty, self.start_wave, self.seed)
+    }
+
+    /// Recorded run time in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.dt).sum()
+    }
+
+    /// Serialize the recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's message if encoding fails.
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|err| err.to_string())
+    }
+
+    /// Parse a recording written by [`Replay::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for invalid JSON or recordings of another version.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let replay: Self =
+            serde_json::from_str(json).map_err(|err| format!("invalid replay: {}", err))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!(
+                "replay version {} is not supported (expected {})",
+                replay.version, REPLAY_VERSION
+            ));
+        }
+        Ok(replay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(replay: &Replay) -> World {
+        let mut world = replay.start();
+        for frame in &replay.frames {
+            world.step(frame.dt, &frame.input);
+            world.events.clear();
+        }
+        world
+    }
+
+    #[test]
+    fn test_playback_reproduces_run() {
+        let mut replay = Replay::new(7, Difficulty::Normal, 2);
+        for frame in 0..600u32 {
+            let input = PlayInput {
+                move_left: frame % 120 < 60,
+                move_right: frame % 120 >= 60,
+                fire: frame.is_multiple_of(10),
+                target_x: None,
+                speed: None,
+            };
+            replay.record(1.0 / 60.0, input);
+        }
+        let replay = Replay::from_json(&replay.to_json().unwrap()).unwrap();
+
+        let first = play(&replay);
+        let second = play(&replay);
+        assert_eq!(first.score, second.score);
+        assert_eq!(first.wave_number, second.wave_number);
+        assert_eq!(first.player.x, second.player.x);
+        assert_eq!(first.enemies.len(), second.enemies.len());
+        assert!((replay.duration() - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let mut replay = Replay::new(1, Difficulty::Easy, 1);
+        replay.version = REPLAY_VERSION + 1;
+        assert!(Replay::from_json(&replay.to_json().unwrap()).is_err());
+        assert!(Replay::from_json("not json").is_err());
+    }
+}
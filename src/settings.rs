@@ -0,0 +1,327 @@
+//! Player settings and the options screen rows.
+//!
+//! [`SettingsStore`] loads and saves [`Settings`] with the same backends as
+//! the other saves: a JSON file on desktop and a localStorage entry on WASM.
+//! Settings missing from an older save get their defaults, and values out of
+//! range are clamped, so a hand-edited file can't break the game.
+
+use crate::audio::SoundPack;
+use crate::constants::*;
+use crate::difficulty::Difficulty;
+use crate::i18n::{Language, Text};
+use crate::postfx::PassSet;
+use crate::storage;
+use crate::touch::TouchLayout;
+use serde::{Deserialize, Serialize};
+
+/// Everything the options screen changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Volume of all sound, 0 to 1
+    pub master_volume: f32,
+    /// Volume of the music relative to the master volume, 0 to 1
+    pub music_volume: f32,
+    /// Volume of the sound effects relative to the master volume, 0 to 1
+    pub sfx_volume: f32,
+    /// Volume of the interface sounds relative to the master volume, 0 to 1
+    pub ui_volume: f32,
+    /// Sample files or synthesized sound effects
+    pub sound_pack: SoundPack,
+    pub fullscreen: bool,
+    /// Window size as a multiple of the game area (desktop)
+    pub window_scale: f32,
+    /// Difficulty selected in the menu
+    pub difficulty: Difficulty,
+    /// Touch control scheme and its zones
+    pub touch: TouchLayout,
+    /// Strength of the screen effects, 0 (off) to 1
+    pub effects: f32,
+    /// Post-processing shader passes
+    pub postfx: PassSet,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            ui_volume: 1.0,
+            sound_pack: SoundPack::default(),
+            fullscreen: false,
+            window_scale: 1.0,
+            difficulty: Difficulty::default(),
+            touch: TouchLayout::default(),
+            effects: 1.0,
+            postfx: PassSet::default(),
+            language: Language::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// The settings with every value in its allowed range.
+    #[must_use]
+    pub fn clamped(self) -> Self {
+        Self {
+            master_volume: self.master_volume.clamp(0.0, 1.0),
+            music_volume: self.music_volume.clamp(0.0, 1.0),
+            sfx_volume: self.sfx_volume.clamp(0.0, 1.0),
+            ui_volume: self.ui_volume.clamp(0.0, 1.0),
+            window_scale: self.window_scale.clamp(1.0, WINDOW_SCALE_MAX),
+            touch: self.touch.clamped(),
+            effects: self.effects.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Change the setting of `row` one step up (`step` 1) or down (-1).
+    pub fn adjust(&mut self, row: OptionRow, step: i32) {
+        // Round so repeated steps don't drift away from the labels
+        let nudge = |value: f32, by: f32| ((value + by * step as f32) * 100.0).round() / 100.0;
+        match row {
+            OptionRow::MasterVolume => {
+                self.master_volume = nudge(self.master_volume, VOLUME_STEP);
+            }
+            OptionRow::MusicVolume => self.music_volume = nudge(self.music_volume, VOLUME_STEP),
+            OptionRow::SfxVolume => self.sfx_volume = nudge(self.sfx_volume, VOLUME_STEP),
+            OptionRow::UiVolume => self.ui_volume = nudge(self.ui_volume, VOLUME_STEP),
+            OptionRow::SoundPack => self.sound_pack = self.sound_pack.toggled(),
+            OptionRow::Fullscreen => self.fullscreen = !self.fullscreen,
+            OptionRow::WindowScale => {
+                self.window_scale = nudge(self.window_scale, WINDOW_SCALE_STEP);
+            }
+            OptionRow::Difficulty => {
+                self.difficulty = if step > 0 {
+                    self.difficulty.harder()
+                } else {
+                    self.difficulty.easier()
+                };
+            }
+            OptionRow::ControlScheme => {
+                self.touch.scheme = self.touch.scheme.cycle(step as isize);
+            }
+            OptionRow::Effects => self.effects = nudge(self.effects, EFFECTS_STEP),
+            OptionRow::PostFx => self.postfx = self.postfx.cycle(step as isize),
+            OptionRow::Language => self.language = self.language.cycle(step as isize),
+        }
+        *self = self.clamped();
+    }
+}
+
+/// One line of the options screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionRow {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    UiVolume,
+    SoundPack,
+    Fullscreen,
+    WindowScale,
+    Difficulty,
+    ControlScheme,
+    Effects,
+    PostFx,
+    Language,
+}
+
+impl OptionRow {
+    /// The rows shown on this platform, top to bottom. The browser decides
+    /// the size of the web version, so it has no window size.
+    #[must_use]
+    pub fn rows() -> Vec<Self> {
+        [
+            Self::MasterVolume,
+            Self::MusicVolume,
+            Self::SfxVolume,
+            Self::UiVolume,
+            Self::SoundPack,
+            Self::Fullscreen,
+            Self::WindowScale,
+            Self::Difficulty,
+            Self::ControlScheme,
+            Self::Effects,
+            Self::PostFx,
+            Self::Language,
+        ]
+        .into_iter()
+        .filter(|&row| cfg!(not(target_arch = "wasm32")) || row != Self::WindowScale)
+        .collect()
+    }
+
+    /// Label of the row.
+    #[must_use]
+    pub const fn label(self) -> Text {
+        match self {
+            Self::MasterVolume => Text::MasterVolume,
+            Self::MusicVolume => Text::MusicVolume,
+            Self::SfxVolume => Text::SfxVolume,
+            Self::UiVolume => Text::UiVolume,
+            Self::SoundPack => Text::SoundPack,
+            Self::Fullscreen => Text::Fullscreen,
+            Self::WindowScale => Text::WindowScale,
+            Self::Difficulty => Text::Difficulty,
+            Self::ControlScheme => Text::ControlScheme,
+            Self::Effects => Text::Effects,
+            Self::PostFx => Text::PostFx,
+            Self::Language => Text::Language,
+        }
+    }
+
+    /// The row's current value as shown on screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::settings::{OptionRow, Settings};
+    ///
+    /// let mut settings = Settings::default();
+    /// settings.adjust(OptionRow::MusicVolume, -1);
+    /// assert_eq!(OptionRow::MusicVolume.value(&settings), "90%");
+    /// ```
+    #[must_use]
+    pub fn value(self, settings: &Settings) -> String {
+        let percent = |value: f32| format!("{:.0}%", value * 100.0);
+        let language = settings.language;
+        match self {
+            Self::MasterVolume => percent(settings.master_volume),
+            Self::MusicVolume => percent(settings.music_volume),
+            Self::SfxVolume => percent(settings.sfx_volume),
+            Self::UiVolume => percent(settings.ui_volume),
+            Self::SoundPack => match settings.sound_pack {
+                SoundPack::Samples => language.text(Text::PackSamples).to_string(),
+                SoundPack::Retro => language.text(Text::PackRetro).to_string(),
+            },
+            Self::Fullscreen if settings.fullscreen => language.text(Text::On).to_string(),
+            Self::Fullscreen => language.text(Text::Off).to_string(),
+            Self::WindowScale => format!("{}x", settings.window_scale),
+            Self::Difficulty => settings.difficulty.name().to_string(),
+            Self::ControlScheme => settings.touch.scheme.name().to_string(),
+            Self::Effects => percent(settings.effects),
+            Self::PostFx => settings
+                .postfx
+                .label()
+                .unwrap_or_else(|| language.text(Text::Off).to_string()),
+            Self::Language => language.name().to_string(),
+        }
+    }
+}
+
+/// Cross-platform persistence of the [`Settings`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use bumblebees::settings::SettingsStore;
+///
+/// let mut store = SettingsStore::new("settings.json");
+/// let mut settings = *store.get();
+/// settings.fullscreen = true;
+/// store.set(settings);
+/// ```
+pub struct SettingsStore {
+    /// Storage key: filename (desktop) or localStorage key (WASM)
+    storage_key: String,
+    settings: Settings,
+}
+
+impl SettingsStore {
+    /// Load the settings from the given storage key; defaults if there are
+    /// none or they are unreadable.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        let settings = storage::load(key)
+            .and_then(|json| serde_json::from_str::<Settings>(&json).ok())
+            .map(Settings::clamped)
+            .unwrap_or_default();
+
+        Self {
+            storage_key: key.to_string(),
+            settings,
+        }
+    }
+
+    /// Current settings.
+    #[must_use]
+    pub fn get(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Replace the settings, saving them if anything changed.
+    pub fn set(&mut self, settings: Settings) {
+        let settings = settings.clamped();
+        if settings == self.settings {
+            return;
+        }
+        self.settings = settings;
+        if let Ok(json) = serde_json::to_string(&self.settings) {
+            storage::save(&self.storage_key, &json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::touch::TouchScheme;
+
+    #[test]
+    fn test_adjust_steps_and_clamps() {
+        let mut settings = Settings::default();
+        for _ in 0..15 {
+            settings.adjust(OptionRow::SfxVolume, -1);
+        }
+        assert_eq!(settings.sfx_volume, 0.0);
+        settings.adjust(OptionRow::SfxVolume, 1);
+        settings.adjust(OptionRow::MasterVolume, -1);
+        settings.adjust(OptionRow::MasterVolume, -1);
+        assert_eq!(OptionRow::SfxVolume.value(&settings), "10%");
+        assert_eq!(OptionRow::MasterVolume.value(&settings), "80%");
+
+        settings.adjust(OptionRow::WindowScale, -1);
+        assert_eq!(settings.window_scale, 1.0);
+        settings.adjust(OptionRow::WindowScale, 1);
+        assert_eq!(OptionRow::WindowScale.value(&settings), "1.25x");
+
+        settings.adjust(OptionRow::ControlScheme, 1);
+        assert_eq!(settings.touch.scheme, TouchScheme::Joystick);
+        settings.adjust(OptionRow::PostFx, -1);
+        assert_eq!(OptionRow::PostFx.value(&settings), "Off");
+        settings.adjust(OptionRow::SoundPack, -1);
+        assert_eq!(settings.sound_pack, SoundPack::Retro);
+        settings.adjust(OptionRow::Language, 1);
+        assert_eq!(OptionRow::Fullscreen.value(&settings), "Aus");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_store_persists_and_clamps() {
+        let test_file = "test_settings_persist.json";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut store = SettingsStore::new(test_file);
+        assert_eq!(*store.get(), Settings::default());
+        let mut settings = *store.get();
+        settings.difficulty = Difficulty::Hard;
+        settings.touch.left_handed = true;
+        store.set(settings);
+        assert_eq!(*SettingsStore::new(test_file).get(), settings);
+
+        // Old or hand-edited saves: missing values default, others are clamped
+        std::fs::write(
+            test_file,
+            r#"{"music_volume":3.0,"touch":{"scheme":"Drag","zone_scale":9.0}}"#,
+        )
+        .unwrap();
+        let loaded = *SettingsStore::new(test_file).get();
+        assert_eq!(loaded.music_volume, 1.0);
+        assert_eq!(loaded.master_volume, 1.0);
+        assert_eq!(loaded.touch.scheme, TouchScheme::Drag);
+        assert_eq!(loaded.touch.zone_scale, TOUCH_ZONE_SCALE_MAX);
+
+        let _ = std::fs::remove_file(test_file);
+    }
+}
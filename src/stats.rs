@@ -0,0 +1,375 @@
+//! Per-run statistics.
+//!
+//! [`RunStats`] is filled in while a run is played and shown on the
+//! game-over screen. It is saved with the run's highscore entry.
+
+use crate::constants::{DEFENDER_LINE, SCREEN_HEIGHT};
+use crate::entities::{Enemy, EnemyType};
+use crate::events::GameEvent;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of score samples kept for the score-over-time graph.
+const MAX_SCORE_SAMPLES: usize = 120;
+
+/// Initial interval between score samples in seconds.
+const SCORE_SAMPLE_INTERVAL: f32 = 1.0;
+
+/// Enemies destroyed during a run, per [`EnemyType`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KillCounts {
+    pub standard: u32,
+    pub fast: u32,
+    pub tank: u32,
+    pub swooper: u32,
+}
+
+impl KillCounts {
+    /// Count one destroyed enemy of the given type.
+    pub fn add(&mut self, enemy_type: EnemyType) {
+        *self.count_mut(enemy_type) += 1;
+    }
+
+    /// Number of destroyed enemies of the given type.
+    #[must_use]
+    pub fn get(&self, enemy_type: EnemyType) -> u32 {
+        match enemy_type {
+            EnemyType::Standard => self.standard,
+            EnemyType::Fast => self.fast,
+            EnemyType::Tank => self.tank,
+            EnemyType::Swooper => self.swooper,
+        }
+    }
+
+    /// Total number of destroyed enemies.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.standard + self.fast + self.tank + self.swooper
+    }
+
+    fn count_mut(&mut self, enemy_type: EnemyType) -> &mut u32 {
+        match enemy_type {
+            EnemyType::Standard => &mut self.standard,
+            EnemyType::Fast => &mut self.fast,
+            EnemyType::Tank => &mut self.tank,
+            EnemyType::Swooper => &mut self.swooper,
+        }
+    }
+}
+
+/// Statistics of a single run.
+///
+/// A *combo* is a streak of kills (enemies or bees) without a bullet
+/// leaving the screen in between.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::entities::EnemyType;
+/// use bumblebees::stats::RunStats;
+///
+/// let mut stats = RunStats::new();
+/// stats.record_shot(2);
+/// stats.record_hits(1);
+/// stats.record_kill(EnemyType::Fast);
+/// assert_eq!(stats.accuracy(), 0.5);
+/// assert_eq!(stats.kills.fast, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    /// Bullets fired
+    pub shots_fired: u32,
+    /// Bullets that hit an enemy or a bee
+    pub hits: u32,
+    /// Enemies destroyed per type
+    pub kills: KillCounts,
+    /// Flying bees shot down
+    pub bees_destroyed: u32,
+    /// Seconds spent on each wave, including the one the run ended in
+    pub wave_times: Vec<f32>,
+    /// Smallest distance in pixels between an enemy and the defender line
+    pub closest_approach: Option<f32>,
+    /// Longest streak of kills without a miss
+    pub highest_combo: u32,
+    /// Length of the run in seconds
+    pub duration: f32,
+    /// Score sampled every `sample_interval` seconds
+    pub score_samples: Vec<u32>,
+    /// Seconds between score samples (doubles as the run gets long)
+    pub sample_interval: f32,
+    #[serde(skip)]
+    combo: u32,
+    #[serde(skip)]
+    wave_time: f32,
+    #[serde(skip)]
+    sample_timer: f32,
+}
+
+impl RunStats {
+    /// Empty statistics for a new run.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            shots_fired: 0,
+            hits: 0,
+            kills: KillCounts::default(),
+            bees_destroyed: 0,
+            wave_times: Vec::new(),
+            closest_approach: None,
+            highest_combo: 0,
+            duration: 0.0,
+            score_samples: Vec::new(),
+            sample_interval: SCORE_SAMPLE_INTERVAL,
+            combo: 0,
+            wave_time: 0.0,
+            sample_timer: 0.0,
+        }
+    }
+
+    /// Update the counters from a gameplay event.
+    pub fn record_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::RunStarted => *self = Self::new(),
+            GameEvent::ShotFired { bullets, .. } => self.record_shot(bullets),
+            GameEvent::ShotMissed { bullets } => self.record_misses(bullets),
+            GameEvent::EnemyHit { .. } => self.record_hits(1),
+            GameEvent::EnemyDestroyed { enemy_type, .. } => self.record_kill(enemy_type),
+            GameEvent::BeeDestroyed { bullets, .. } => {
+                self.record_hits(bullets);
+                self.record_bee();
+            }
+            GameEvent::WaveCleared { .. } => self.record_wave_cleared(),
+            GameEvent::FormationDescended { .. } | GameEvent::DefenderBreached { .. } => {}
+        }
+    }
+
+    /// Count fired bullets.
+    pub fn record_shot(&mut self, bullets: usize) {
+        self.shots_fired += bullets as u32;
+    }
+
+    /// Count bullets that hit an enemy or a bee.
+    pub fn record_hits(&mut self, bullets: usize) {
+        self.hits += bullets as u32;
+    }
+
+    /// Count bullets that left the screen without hitting anything.
+    /// Any miss ends the current combo.
+    pub fn record_misses(&mut self, bullets: usize) {
+        if bullets > 0 {
+            self.combo = 0;
+        }
+    }
+
+    /// Count a destroyed enemy and extend the combo.
+    pub fn record_kill(&mut self, enemy_type: EnemyType) {
+        self.kills.add(enemy_type);
+        self.extend_combo();
+    }
+
+    /// Count a destroyed bee and extend the combo.
+    pub fn record_bee(&mut self) {
+        self.bees_destroyed += 1;
+        self.extend_combo();
+    }
+
+    /// Close the current wave's timer and start the next one.
+    pub fn record_wave_cleared(&mut self) {
+        self.wave_times.push(self.wave_time);
+        self.wave_time = 0.0;
+    }
+
+    /// Advance run time, sample the score and track how close the
+    /// formation came to the defender line.
+    pub fn update(&mut self, dt: f32, score: u32, enemies: &[Enemy]) {
+        self.duration += dt;
+        self.wave_time += dt;
+
+        let line_y = SCREEN_HEIGHT - DEFENDER_LINE;
+        if let Some(lowest) = enemies.iter().map(|enemy| enemy.y).reduce(f32::max) {
+            let distance = (line_y - lowest).max(0.0);
+            self.closest_approach = Some(
+                self.closest_approach
+                    .map_or(distance, |closest| closest.min(distance)),
+            );
+        }
+
+        self.sample_timer += dt;
+        while self.sample_timer >= self.sample_interval {
+            self.sample_timer -= self.sample_interval;
+            self.push_sample(score);
+        }
+    }
+
+    /// Record the final wave time and score when the run ends.
+    pub fn finish(&mut self, score: u32) {
+        self.wave_times.push(self.wave_time);
+        self.wave_time = 0.0;
+        // Off the regular grid, so never thinned out by a later halving
+        self.score_samples.push(score);
+    }
+
+    /// Fraction of fired bullets that hit something (0.0 without shots).
+    #[must_use]
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
+
+    fn extend_combo(&mut self) {
+        self.combo += 1;
+        self.highest_combo = self.highest_combo.max(self.combo);
+    }
+
+    /// Append a score sample, halving the resolution once the buffer is
+    /// full so long runs keep a bounded, evenly spaced history.
+    fn push_sample(&mut self, score: u32) {
+        self.score_samples.push(score);
+        if self.score_samples.len() >= MAX_SCORE_SAMPLES {
+            // Sample i is taken at (i + 1) * interval; keep the ones that
+            // land on multiples of the doubled interval
+            self.score_samples = self
+                .score_samples
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .copied()
+                .collect();
+            self.sample_interval *= 2.0;
+        }
+    }
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accuracy() {
+        let mut stats = RunStats::new();
+        assert_eq!(stats.accuracy(), 0.0);
+
+        stats.record_shot(4);
+        stats.record_hits(3);
+        assert_eq!(stats.accuracy(), 0.75);
+    }
+
+    #[test]
+    fn test_kills_and_combo() {
+        let mut stats = RunStats::new();
+        stats.record_kill(EnemyType::Standard);
+        stats.record_kill(EnemyType::Tank);
+        stats.record_bee();
+        stats.record_misses(1);
+        stats.record_kill(EnemyType::Standard);
+        stats.record_misses(0);
+        stats.record_kill(EnemyType::Fast);
+
+        assert_eq!(stats.kills.get(EnemyType::Standard), 2);
+        assert_eq!(stats.kills.tank, 1);
+        assert_eq!(stats.kills.total(), 4);
+        assert_eq!(stats.bees_destroyed, 1);
+        assert_eq!(stats.highest_combo, 3);
+    }
+
+    #[test]
+    fn test_wave_times_and_closest_approach() {
+        let line_y = SCREEN_HEIGHT - DEFENDER_LINE;
+        let mut stats = RunStats::new();
+
+        stats.update(
+            2.0,
+            0,
+            &[Enemy::new(100.0, line_y - 200.0, 1.0, EnemyType::Standard)],
+        );
+        stats.record_wave_cleared();
+        stats.update(
+            3.0,
+            10,
+            &[
+                Enemy::new(100.0, line_y - 150.0, 1.0, EnemyType::Standard),
+                Enemy::new(100.0, line_y - 40.0, 1.0, EnemyType::Standard),
+            ],
+        );
+        stats.update(1.0, 20, &[]);
+        stats.finish(20);
+
+        assert_eq!(stats.wave_times, vec![2.0, 4.0]);
+        assert_eq!(stats.closest_approach, Some(40.0));
+        assert_eq!(stats.duration, 6.0);
+    }
+
+    #[test]
+    fn test_score_samples_stay_bounded() {
+        let mut stats = RunStats::new();
+        for second in 1..=(MAX_SCORE_SAMPLES as u32 * 3) {
+            stats.update(1.0, second, &[]);
+        }
+
+        assert!(stats.score_samples.len() <= MAX_SCORE_SAMPLES);
+        assert_eq!(stats.sample_interval, 4.0);
+        // Samples stay evenly spaced: sample i is the score at (i + 1) * interval
+        assert_eq!(stats.score_samples[0], 4);
+        assert_eq!(stats.score_samples[1], 8);
+    }
+
+    #[test]
+    fn test_record_events() {
+        let mut stats = RunStats::new();
+        let events = [
+            GameEvent::ShotFired { x: 0.0, bullets: 3 },
+            GameEvent::EnemyHit {
+                enemy_type: EnemyType::Tank,
+                x: 0.0,
+                y: 0.0,
+            },
+            GameEvent::EnemyHit {
+                enemy_type: EnemyType::Fast,
+                x: 0.0,
+                y: 0.0,
+            },
+            GameEvent::EnemyDestroyed {
+                enemy_type: EnemyType::Fast,
+                x: 0.0,
+                y: 0.0,
+                points: 20,
+            },
+            GameEvent::ShotMissed { bullets: 1 },
+        ];
+        for event in &events {
+            stats.record_event(event);
+        }
+
+        assert_eq!(stats.shots_fired, 3);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.kills.total(), 1);
+        assert_eq!(stats.highest_combo, 1);
+
+        stats.record_event(&GameEvent::RunStarted);
+        assert_eq!(stats.shots_fired, 0);
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let mut stats = RunStats::new();
+        stats.record_shot(10);
+        stats.record_kill(EnemyType::Swooper);
+        stats.finish(30);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let loaded: RunStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.shots_fired, 10);
+        assert_eq!(loaded.kills.swooper, 1);
+        assert_eq!(loaded.score_samples, vec![30]);
+    }
+}
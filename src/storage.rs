@@ -0,0 +1,71 @@
+//! Small key-value storage for game state saved as JSON.
+//!
+//! Same backends as `HighscoreManager`: a file named by the key on desktop
+//! and a localStorage entry on WASM. On desktop, [`key`] places files in the
+//! directory chosen with `--save-dir` (the working directory by default).
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::OnceLock;
+
+/// Directory for saved files on desktop, set once at startup.
+#[cfg(not(target_arch = "wasm32"))]
+static SAVE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep saved files in `dir` instead of the working directory, creating it
+/// if needed. Only the first call has an effect.
+///
+/// # Errors
+///
+/// Returns the error if the directory cannot be created.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_save_dir(dir: PathBuf) -> std::io::Result<()> {
+    std::fs::create_dir_all(&dir)?;
+    let _ = SAVE_DIR.set(dir);
+    Ok(())
+}
+
+/// Storage key for a saved file called `name`: its path inside the save
+/// directory on desktop, the name itself on WASM.
+///
+/// # Examples
+///
+/// ```
+/// // Without a save directory files stay in the working directory
+/// assert_eq!(bumblebees::storage::key("profiles.json"), "profiles.json");
+/// ```
+#[must_use]
+pub fn key(name: &str) -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(dir) = SAVE_DIR.get() {
+        return dir.join(name).to_string_lossy().into_owned();
+    }
+    name.to_string()
+}
+
+/// Read the stored value, or `None` if nothing was saved yet.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(key).ok()
+}
+
+/// Store the value under the key, logging a warning on failure.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn save(key: &str, value: &str) {
+    if let Err(err) = std::fs::write(key, value) {
+        log::warn!("Failed to save {}: {}", key, err);
+    }
+}
+
+/// Read the stored value, or `None` if nothing was saved yet.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn load(key: &str) -> Option<String> {
+    crate::highscore::localstorage_get(key)
+}
+
+/// Store the value under the key.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn save(key: &str, value: &str) {
+    crate::highscore::localstorage_set(key, value);
+}
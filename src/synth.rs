@@ -0,0 +1,394 @@
+//! Chiptune sound effect synthesizer.
+//!
+//! In the spirit of sfxr: a handful of [`SynthParams`] (waveform, pitch
+//! slide, vibrato, arpeggio and a punchy envelope) describe a sound effect,
+//! and [`synthesize`] renders it to PCM. Every [`Clip`] has built-in
+//! parameters; a data file can replace them (see [`clip_params`]). The
+//! results are the "pure retro" sound pack and stand in for sample files
+//! that are missing.
+
+use crate::audio::Clip;
+use crate::wav::Pcm;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Sample rate of the synthesized sounds
+pub const SYNTH_SAMPLE_RATE: u32 = 44100;
+
+/// Noise values per wave period
+const NOISE_STEPS: f32 = 32.0;
+
+/// Basic shape of the sound.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Waveform {
+    #[default]
+    Square,
+    Sawtooth,
+    Sine,
+    Noise,
+}
+
+/// Parameters of a synthesized sound effect. Times are in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SynthParams {
+    pub waveform: Waveform,
+    /// Start frequency in Hz
+    pub frequency: f32,
+    /// Pitch change in octaves per second (negative falls)
+    pub slide: f32,
+    /// A falling sound ends when its pitch drops below this, in Hz
+    pub min_frequency: f32,
+    /// Share of a square wave period spent high, 0.05 to 0.95
+    pub duty: f32,
+    /// Duty change per second
+    pub duty_slide: f32,
+    /// Vibrato depth as a share of the frequency, and its speed in Hz
+    pub vibrato_depth: f32,
+    pub vibrato_speed: f32,
+    /// Pitch multiplier applied after `arpeggio_delay` (1 = none)
+    pub arpeggio: f32,
+    pub arpeggio_delay: f32,
+    /// Envelope: fade in, hold and fade out
+    pub attack: f32,
+    pub sustain: f32,
+    pub decay: f32,
+    /// Extra loudness at the start of the hold, fading over it
+    pub punch: f32,
+    /// Output volume, 0 to 1
+    pub volume: f32,
+    /// Seed of the noise
+    pub seed: u32,
+}
+
+impl Default for SynthParams {
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::Square,
+            frequency: 440.0,
+            slide: 0.0,
+            min_frequency: 20.0,
+            duty: 0.5,
+            duty_slide: 0.0,
+            vibrato_depth: 0.0,
+            vibrato_speed: 0.0,
+            arpeggio: 1.0,
+            arpeggio_delay: 0.0,
+            attack: 0.0,
+            sustain: 0.1,
+            decay: 0.1,
+            punch: 0.0,
+            volume: 0.5,
+            seed: 1,
+        }
+    }
+}
+
+impl SynthParams {
+    /// Built-in parameters of a clip.
+    #[must_use]
+    pub fn preset(clip: Clip) -> Self {
+        let defaults = Self::default();
+        match clip {
+            Clip::Shoot => Self {
+                frequency: 880.0,
+                slide: -3.0,
+                min_frequency: 110.0,
+                duty: 0.25,
+                duty_slide: 1.0,
+                sustain: 0.05,
+                decay: 0.12,
+                punch: 0.3,
+                volume: 0.4,
+                ..defaults
+            },
+            Clip::Hit => Self {
+                waveform: Waveform::Noise,
+                frequency: 600.0,
+                slide: -2.5,
+                min_frequency: 50.0,
+                sustain: 0.03,
+                decay: 0.15,
+                punch: 0.5,
+                volume: 0.5,
+                seed: 7,
+                ..defaults
+            },
+            Clip::Explosion => Self {
+                waveform: Waveform::Noise,
+                frequency: 220.0,
+                slide: -0.8,
+                sustain: 0.15,
+                decay: 0.6,
+                punch: 0.6,
+                volume: 0.6,
+                seed: 42,
+                ..defaults
+            },
+            Clip::Bee => Self {
+                waveform: Waveform::Sawtooth,
+                frequency: 180.0,
+                vibrato_depth: 0.08,
+                vibrato_speed: 18.0,
+                attack: 0.03,
+                sustain: 0.7,
+                decay: 0.15,
+                volume: 0.35,
+                ..defaults
+            },
+            Clip::PowerUp => Self {
+                frequency: 440.0,
+                slide: 1.0,
+                arpeggio: 1.5,
+                arpeggio_delay: 0.1,
+                sustain: 0.2,
+                decay: 0.2,
+                punch: 0.2,
+                volume: 0.4,
+                ..defaults
+            },
+            Clip::Ui => Self {
+                frequency: 1200.0,
+                sustain: 0.02,
+                decay: 0.04,
+                volume: 0.3,
+                ..defaults
+            },
+        }
+    }
+
+    /// Length of the envelope in seconds.
+    #[must_use]
+    pub fn duration(&self) -> f32 {
+        self.attack.max(0.0) + self.sustain.max(0.0) + self.decay.max(0.0)
+    }
+}
+
+/// Parameters of every clip: the built-in ones, changed by those in
+/// `json`. The data file maps clip names to parameters; parameters it
+/// leaves out keep their built-in values:
+///
+/// ```json
+/// { "shoot": { "frequency": 660.0, "slide": -2.0 } }
+/// ```
+///
+/// # Errors
+///
+/// Returns the parse error if `json` isn't such a file.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::audio::Clip;
+/// use bumblebees::synth::{clip_params, SynthParams, Waveform};
+///
+/// let params = clip_params(Some(r#"{"ui": {"waveform": "sine"}}"#)).unwrap();
+/// assert_eq!(params[&Clip::Ui].waveform, Waveform::Sine);
+/// assert_eq!(params[&Clip::Ui].frequency, SynthParams::preset(Clip::Ui).frequency);
+/// assert_eq!(params[&Clip::Hit], SynthParams::preset(Clip::Hit));
+/// ```
+pub fn clip_params(json: Option<&str>) -> Result<HashMap<Clip, SynthParams>, String> {
+    let mut params: HashMap<Clip, SynthParams> = Clip::ALL
+        .iter()
+        .map(|&clip| (clip, SynthParams::preset(clip)))
+        .collect();
+    let Some(json) = json else {
+        return Ok(params);
+    };
+    let custom: HashMap<Clip, serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_str(json).map_err(|err| err.to_string())?;
+    for (clip, fields) in custom {
+        // Lay the given fields over the built-in parameters
+        let mut merged = serde_json::to_value(params[&clip]).map_err(|err| err.to_string())?;
+        if let serde_json::Value::Object(map) = &mut merged {
+            map.extend(fields);
+        }
+        let clip_params = serde_json::from_value(merged).map_err(|err| err.to_string())?;
+        params.insert(clip, clip_params);
+    }
+    Ok(params)
+}
+
+/// Render a sound effect as mono 16-bit PCM.
+///
+/// The same parameters always give the same samples.
+#[must_use]
+pub fn synthesize(params: &SynthParams) -> Pcm {
+    let rate = SYNTH_SAMPLE_RATE as f32;
+    let length = (params.duration() * rate) as usize;
+    let mut samples = Vec::with_capacity(length);
+    let mut phase = 0.0_f32;
+    let mut noise = Noise::new(params.seed);
+    let mut noise_step = 0;
+    let mut noise_value = noise.next_value();
+
+    for i in 0..length {
+        let t = i as f32 / rate;
+
+        let mut frequency = params.frequency * 2.0_f32.powf(params.slide * t);
+        if params.slide < 0.0 && frequency < params.min_frequency {
+            break;
+        }
+        if params.arpeggio != 1.0 && t >= params.arpeggio_delay {
+            frequency *= params.arpeggio;
+        }
+        frequency *=
+            1.0 + params.vibrato_depth * (std::f32::consts::TAU * params.vibrato_speed * t).sin();
+
+        phase += frequency / rate;
+        if phase >= 1.0 {
+            phase = phase.fract();
+        }
+
+        let value = match params.waveform {
+            Waveform::Square => {
+                let duty = (params.duty + params.duty_slide * t).clamp(0.05, 0.95);
+                if phase < duty {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Sawtooth => 1.0 - 2.0 * phase,
+            Waveform::Sine => (std::f32::consts::TAU * phase).sin(),
+            Waveform::Noise => {
+                // A new random value every fraction of a period, so the
+                // pitch still colors the noise
+                let step = (phase * NOISE_STEPS) as usize;
+                if step != noise_step {
+                    noise_step = step;
+                    noise_value = noise.next_value();
+                }
+                noise_value
+            }
+        };
+
+        let sample = value * envelope(params, t) * params.volume;
+        samples.push((sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16);
+    }
+
+    Pcm {
+        sample_rate: SYNTH_SAMPLE_RATE,
+        channels: 1,
+        samples,
+    }
+}
+
+/// Loudness at `t` seconds: attack ramp, punchy hold, linear decay.
+fn envelope(params: &SynthParams, t: f32) -> f32 {
+    if t < params.attack {
+        return t / params.attack;
+    }
+    let t = t - params.attack;
+    if t < params.sustain {
+        return 1.0 + params.punch * (1.0 - t / params.sustain);
+    }
+    let t = t - params.sustain;
+    if params.decay > 0.0 {
+        (1.0 - t / params.decay).max(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// Xorshift generator for the noise waveform.
+struct Noise(u32);
+
+impl Noise {
+    fn new(seed: u32) -> Self {
+        // Xorshift never leaves zero
+        Self(seed.max(1))
+    }
+
+    /// Next value between -1 and 1.
+    fn next_value(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Times the signal goes from negative to positive.
+    fn rising_crossings(samples: &[i16]) -> usize {
+        samples
+            .windows(2)
+            .filter(|pair| pair[0] < 0 && pair[1] >= 0)
+            .count()
+    }
+
+    #[test]
+    fn test_buffers_follow_the_envelope() {
+        for clip in Clip::ALL {
+            let params = SynthParams::preset(clip);
+            let pcm = synthesize(&params);
+            assert_eq!(pcm.channels, 1);
+            assert!(!pcm.samples.is_empty(), "{:?} is silent", clip);
+            assert!(pcm.samples.len() <= (params.duration() * 44100.0) as usize);
+            // Punch may go over the volume, but nothing clips
+            let peak = pcm.samples.iter().map(|s| s.unsigned_abs()).max().unwrap();
+            let limit = params.volume * (1.0 + params.punch) * f32::from(i16::MAX);
+            assert!(f32::from(peak) <= limit + 1.0, "{:?} too loud", clip);
+            // Same parameters, same sound
+            assert_eq!(synthesize(&params), pcm);
+        }
+
+        // The end of the decay is silent
+        let ui = synthesize(&SynthParams::preset(Clip::Ui));
+        assert!(ui.samples.last().unwrap().unsigned_abs() < 400);
+    }
+
+    #[test]
+    fn test_pitch_slide_and_stop() {
+        // A steady square wave crosses zero once per period
+        let steady = SynthParams {
+            frequency: 441.0,
+            sustain: 1.0,
+            decay: 0.0,
+            ..SynthParams::default()
+        };
+        let crossings = rising_crossings(&synthesize(&steady).samples);
+        assert!((440..=442).contains(&crossings), "{} crossings", crossings);
+
+        // Falling an octave per second from 441 Hz passes 300 Hz after
+        // log2(441 / 300) = 0.556 seconds, where the sound ends
+        let falling = SynthParams {
+            slide: -1.0,
+            min_frequency: 300.0,
+            ..steady
+        };
+        let samples = synthesize(&falling).samples;
+        assert!((samples.len() as f32 / 44100.0 - 0.556).abs() < 0.01);
+        assert!(rising_crossings(&samples) < crossings);
+
+        let rising = SynthParams {
+            slide: 1.0,
+            ..steady
+        };
+        assert!(rising_crossings(&synthesize(&rising).samples) > crossings);
+    }
+
+    #[test]
+    fn test_noise_depends_on_seed() {
+        let noise = SynthParams::preset(Clip::Explosion);
+        let other = SynthParams { seed: 43, ..noise };
+        assert_ne!(synthesize(&noise), synthesize(&other));
+        // Noise is not a tone: far more crossings than its frequency
+        let samples = synthesize(&SynthParams {
+            sustain: 1.0,
+            decay: 0.0,
+            slide: 0.0,
+            ..noise
+        })
+        .samples;
+        assert!(rising_crossings(&samples) > 220 * 4);
+
+        assert!(clip_params(Some("{\"shoot\": 3}")).is_err());
+        assert!(clip_params(Some("{\"shoot\": {\"volume\": \"loud\"}}")).is_err());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::constants::COLLISION_RADIUS;
 use crate::entities::{Bullet, Enemy};
+use crate::events::{EventQueue, GameEvent};
 
 const COLLISION_RADIUS_SQ: f32 = COLLISION_RADIUS * COLLISION_RADIUS;
 
@@ -25,22 +26,19 @@ pub fn check_collision(bullet: &Bullet, enemy: &Enemy) -> bool {
 /// Process collisions between bullets and enemies.
 ///
 /// Damages enemies hit by bullets (reduces health), removes bullets that hit,
-/// and returns positions and points for destroyed enemies.
+/// and emits a [`GameEvent::EnemyHit`] per hit plus a
+/// [`GameEvent::EnemyDestroyed`] for every enemy that died.
 ///
 /// # Arguments
 ///
 /// * `enemies` - Mutable vector of enemies to check
 /// * `bullets` - Mutable vector of bullets to check against
-///
-/// # Arguments
-///
-/// * `destroyed_info` - Scratch buffer that will be filled with (x, y, points)
+/// * `events` - Queue the hit and destroy events are appended to
 pub fn process_collisions(
     enemies: &mut Vec<Enemy>,
     bullets: &mut Vec<Bullet>,
-    destroyed_info: &mut Vec<(f32, f32, u32)>,
+    events: &mut EventQueue,
 ) {
-    destroyed_info.clear();
     let initial_enemy_count = enemies.len();
     let initial_bullet_count = bullets.len();
 
@@ -57,8 +55,18 @@ pub fn process_collisions(
                 let destroyed = enemy.take_damage();
                 bullet_hit = true;
 
+                events.emit(GameEvent::EnemyHit {
+                    enemy_type: enemy.enemy_type,
+                    x: enemy.x,
+                    y: enemy.y,
+                });
                 if destroyed {
-                    destroyed_info.push((enemy.x, enemy.y, enemy.enemy_type.points()));
+                    events.emit(GameEvent::EnemyDestroyed {
+                        enemy_type: enemy.enemy_type,
+                        x: enemy.x,
+                        y: enemy.y,
+                        points: enemy.enemy_type.points(),
+                    });
                 }
                 break;
             }
@@ -88,9 +96,38 @@ pub fn process_collisions(
 #[cfg(test)]
 mod tests {
     use super::*;
-
     use crate::entities::EnemyType;
 
+    /// Run one collision pass and return its events.
+    fn collide(enemies: &mut Vec<Enemy>, bullets: &mut Vec<Bullet>) -> Vec<GameEvent> {
+        let mut events = EventQueue::new();
+        process_collisions(enemies, bullets, &mut events);
+        events.drain().collect()
+    }
+
+    /// (x, y, points, type) of every destroyed enemy.
+    fn destroyed(events: &[GameEvent]) -> Vec<(f32, f32, u32, EnemyType)> {
+        events
+            .iter()
+            .filter_map(|event| match *event {
+                GameEvent::EnemyDestroyed {
+                    enemy_type,
+                    x,
+                    y,
+                    points,
+                } => Some((x, y, points, enemy_type)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn hits(events: &[GameEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::EnemyHit { .. }))
+            .count()
+    }
+
     #[test]
     fn test_bullet_collision_detection() {
         let enemy = Enemy::new(100.0, 200.0, 1.0, EnemyType::Standard);
@@ -115,13 +152,11 @@ mod tests {
         let mut bullets = vec![
             Bullet::new(105.0, 205.0), // Should hit first enemy
         ];
-        let mut destroyed_info = Vec::new();
-
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 1);
         assert_eq!(enemies.len(), 2);
         assert_eq!(bullets.len(), 0); // Bullet should be removed
-        assert_eq!(destroyed_info[0], (100.0, 200.0, 10)); // x, y, points
+        assert_eq!(destroyed_info[0], (100.0, 200.0, 10, EnemyType::Standard)); // x, y, points, type
     }
 
     #[test]
@@ -131,9 +166,7 @@ mod tests {
             Bullet::new(95.0, 195.0),  // Should hit
             Bullet::new(105.0, 205.0), // Would also hit, but enemy removed by first bullet
         ];
-        let mut destroyed_info = Vec::new();
-
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 1); // Only one enemy destroyed
         assert_eq!(enemies.len(), 0);
         assert_eq!(bullets.len(), 1); // Only one bullet removed (first hit)
@@ -145,10 +178,10 @@ mod tests {
         let mut bullets = vec![
             Bullet::new(105.0, 205.0), // First hit
         ];
-        let mut destroyed_info = Vec::new();
-
         // First hit - Tank takes damage but survives (3 -> 2 health)
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let events = collide(&mut enemies, &mut bullets);
+        let destroyed_info = destroyed(&events);
+        assert_eq!(hits(&events), 1); // Counts as a hit even though nothing was destroyed
         assert_eq!(destroyed_info.len(), 0); // Not destroyed yet
         assert_eq!(enemies.len(), 1); // Still alive
         assert_eq!(enemies[0].health, 2); // Health reduced
@@ -156,13 +189,13 @@ mod tests {
 
         // Second hit
         bullets.push(Bullet::new(105.0, 205.0));
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 0); // Still not destroyed
         assert_eq!(enemies[0].health, 1); // Health reduced again
 
         // Third hit - Tank destroyed
         bullets.push(Bullet::new(105.0, 205.0));
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 1); // Now destroyed!
         assert_eq!(enemies.len(), 0);
         assert_eq!(destroyed_info[0].2, 50); // Tank worth 50 points
@@ -172,9 +205,7 @@ mod tests {
     fn test_no_collisions() {
         let mut enemies = vec![Enemy::new(100.0, 200.0, 1.0, EnemyType::Standard)];
         let mut bullets = vec![Bullet::new(200.0, 300.0)]; // Far away
-        let mut destroyed_info = Vec::new();
-
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 0);
         assert_eq!(enemies.len(), 1);
         assert_eq!(bullets.len(), 1); // Bullet should remain
@@ -184,9 +215,7 @@ mod tests {
     fn test_empty_inputs() {
         let mut enemies: Vec<Enemy> = vec![];
         let mut bullets: Vec<Bullet> = vec![];
-        let mut destroyed_info = Vec::new();
-
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 0);
         assert_eq!(enemies.len(), 0);
         assert_eq!(bullets.len(), 0);
@@ -205,12 +234,11 @@ mod tests {
             Bullet::new(305.0, 205.0),
         ];
 
-        let mut destroyed_info = Vec::new();
-        process_collisions(&mut enemies, &mut bullets, &mut destroyed_info);
+        let destroyed_info = destroyed(&collide(&mut enemies, &mut bullets));
         assert_eq!(destroyed_info.len(), 3);
 
         // Check points are correct
-        let points: Vec<u32> = destroyed_info.iter().map(|(_, _, p)| *p).collect();
+        let points: Vec<u32> = destroyed_info.iter().map(|(_, _, p, _)| *p).collect();
         assert!(points.contains(&10));
         assert!(points.contains(&20));
         assert!(points.contains(&30));
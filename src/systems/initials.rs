@@ -0,0 +1,144 @@
+//! Arcade-style initials entry.
+//!
+//! Three letter slots cycled with up/down and moved between with left/right,
+//! as on classic cabinets. Works with arrow keys as well as touch, where the
+//! free-text name box is awkward.
+
+/// Characters offered in each slot, in cycling order.
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Number of initials entered.
+pub const INITIALS_LEN: usize = 3;
+
+/// State of an initials entry in progress.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::systems::InitialsEntry;
+///
+/// let mut entry = InitialsEntry::new();
+/// entry.cycle_down(); // 'A' wraps around to '9'
+/// entry.next_slot();
+/// entry.cycle_up();
+/// assert_eq!(entry.initials(), "9BA");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitialsEntry {
+    /// Index into `ALPHABET` for each slot
+    letters: [usize; INITIALS_LEN],
+    /// Slot currently being edited
+    cursor: usize,
+}
+
+impl InitialsEntry {
+    /// Start a new entry at "AAA" with the first slot selected.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            letters: [0; INITIALS_LEN],
+            cursor: 0,
+        }
+    }
+
+    /// Advance the selected slot to the next character, wrapping around.
+    pub fn cycle_up(&mut self) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = (*letter + 1) % ALPHABET.len();
+    }
+
+    /// Move the selected slot to the previous character, wrapping around.
+    pub fn cycle_down(&mut self) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = (*letter + ALPHABET.len() - 1) % ALPHABET.len();
+    }
+
+    /// Select the next slot. Returns `false` if the last slot was already
+    /// selected, which callers treat as "done".
+    pub fn next_slot(&mut self) -> bool {
+        if self.cursor + 1 < INITIALS_LEN {
+            self.cursor += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Select the previous slot (stays on the first slot).
+    pub fn previous_slot(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Select a slot directly (e.g. by tapping it). Out-of-range slots are ignored.
+    pub fn select_slot(&mut self, slot: usize) {
+        if slot < INITIALS_LEN {
+            self.cursor = slot;
+        }
+    }
+
+    /// Index of the slot currently being edited.
+    #[must_use]
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The entered initials as a string.
+    #[must_use]
+    pub fn initials(&self) -> String {
+        self.letters
+            .iter()
+            .map(|&index| ALPHABET[index] as char)
+            .collect()
+    }
+}
+
+impl Default for InitialsEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_entry_starts_at_aaa() {
+        let entry = InitialsEntry::new();
+        assert_eq!(entry.initials(), "AAA");
+        assert_eq!(entry.cursor(), 0);
+    }
+
+    #[test]
+    fn test_cycling_wraps_around() {
+        let mut entry = InitialsEntry::new();
+        entry.cycle_down();
+        assert_eq!(entry.initials(), "9AA");
+        entry.cycle_up();
+        entry.cycle_up();
+        assert_eq!(entry.initials(), "BAA");
+    }
+
+    #[test]
+    fn test_slot_navigation() {
+        let mut entry = InitialsEntry::new();
+        assert!(entry.next_slot());
+        entry.cycle_up();
+        assert!(entry.next_slot());
+        entry.cycle_up();
+        entry.cycle_up();
+        assert!(!entry.next_slot());
+        assert_eq!(entry.cursor(), 2);
+        assert_eq!(entry.initials(), "ABC");
+
+        entry.previous_slot();
+        entry.previous_slot();
+        entry.previous_slot();
+        assert_eq!(entry.cursor(), 0);
+
+        entry.select_slot(7);
+        assert_eq!(entry.cursor(), 0);
+        entry.select_slot(2);
+        assert_eq!(entry.cursor(), 2);
+    }
+}
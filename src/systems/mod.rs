@@ -1,9 +1,12 @@
 //! Game systems modules.
 //!
-//! Contains pure game logic functions for collision detection and wave generation.
+//! Contains pure game logic for collision detection, wave generation and
+//! arcade initials entry.
 
 pub mod collision;
+pub mod initials;
 pub mod wave;
 
 pub use collision::process_collisions;
-pub use wave::generate_wave;
+pub use initials::InitialsEntry;
+pub use wave::{formation_for_wave, generate_wave, FormationType};
@@ -2,6 +2,7 @@
 
 use crate::constants::SCREEN_WIDTH;
 use crate::entities::{Enemy, EnemyType};
+use serde::{Deserialize, Serialize};
 
 #[cfg(not(target_arch = "wasm32"))]
 use rand::{rngs::SmallRng, Rng, SeedableRng};
@@ -10,8 +11,8 @@ use rand::{rngs::SmallRng, Rng, SeedableRng};
 use macroquad::rand::gen_range;
 
 /// Formation pattern types.
-#[derive(Debug, Clone, Copy)]
-enum FormationType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormationType {
     /// Classic grid formation (Space Invaders style)
     Grid,
     /// V-shaped formation
@@ -202,6 +203,18 @@ fn generate_scattered_formation(wave: u32) -> Vec<Enemy> {
     enemies
 }
 
+/// Formation used by the given wave (see [`generate_wave`]).
+#[must_use]
+pub fn formation_for_wave(wave: u32) -> FormationType {
+    match wave % 4 {
+        1 => FormationType::Grid,
+        2 => FormationType::VShape,
+        3 => FormationType::Diamond,
+        0 => FormationType::Scattered,
+        _ => FormationType::Grid, // Fallback
+    }
+}
+
 /// Generate enemies for a given wave number with varied formations and enemy types.
 ///
 /// Uses different formations per wave:
@@ -225,13 +238,7 @@ fn generate_scattered_formation(wave: u32) -> Vec<Enemy> {
 /// A vector of enemies positioned according to the wave's formation pattern
 #[must_use]
 pub fn generate_wave(wave: u32) -> Vec<Enemy> {
-    let formation = match wave % 4 {
-        1 => FormationType::Grid,
-        2 => FormationType::VShape,
-        3 => FormationType::Diamond,
-        0 => FormationType::Scattered,
-        _ => FormationType::Grid, // Fallback
-    };
+    let formation = formation_for_wave(wave);
 
     let enemies = match formation {
         FormationType::Grid => generate_grid_formation(wave),
@@ -254,6 +261,14 @@ pub fn generate_wave(wave: u32) -> Vec<Enemy> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_formation_for_wave() {
+        assert_eq!(formation_for_wave(1), FormationType::Grid);
+        assert_eq!(formation_for_wave(3), FormationType::Diamond);
+        assert_eq!(formation_for_wave(7), FormationType::Diamond);
+        assert_eq!(formation_for_wave(8), FormationType::Scattered);
+    }
+
     #[test]
     fn test_generate_wave_1_grid() {
         let enemies = generate_wave(1);
@@ -0,0 +1,582 @@
+//! Opt-in local gameplay telemetry.
+//!
+//! When enabled, every [`GameEvent`] and a summary of every finished run are
+//! written as one JSON object per line (JSONL):
+//! - **Desktop**: appended to a file named by the storage key
+//! - **WASM**: kept in a localStorage ring buffer of the last
+//!   [`MAX_BROWSER_RECORDS`] lines, at most [`MAX_BROWSER_BYTES`] long
+//!
+//! Nothing leaves the device. [`analyze`] aggregates collected files into
+//! per-wave death rates and average scores for balancing.
+
+use crate::difficulty::Difficulty;
+use crate::events::GameEvent;
+use crate::highscore::{unix_time_now, MAX_LOCALSTORAGE_SIZE};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Game version written into every record.
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Environment variable that enables telemetry on desktop regardless of the
+/// saved setting (any non-empty value other than `0`).
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub const TELEMETRY_ENV_VAR: &str = "BUMBLEBEES_TELEMETRY";
+
+/// Lines kept in the browser's localStorage ring buffer.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub const MAX_BROWSER_RECORDS: usize = 5000;
+
+/// Size limit of the browser's ring buffer in bytes. Stays below what
+/// localStorage values may have to be read back, with room for one more
+/// flush.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub const MAX_BROWSER_BYTES: usize = MAX_LOCALSTORAGE_SIZE - 64 * 1024;
+
+/// Buffered records that trigger a write before the run ends.
+const FLUSH_THRESHOLD: usize = 200;
+
+/// One line of the telemetry log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryRecord {
+    /// Random id shared by all records of one game session
+    pub session: String,
+    /// Game version that wrote the record
+    pub version: String,
+    /// Seed of the run the record belongs to
+    pub seed: u64,
+    /// Wall-clock time in Unix seconds
+    pub timestamp: u64,
+    /// Seconds since the run started
+    pub run_time: f32,
+    /// Wave being played when the record was written
+    pub wave: u32,
+    /// What happened
+    #[serde(flatten)]
+    pub kind: RecordKind,
+}
+
+/// Payload of a [`TelemetryRecord`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordKind {
+    /// A gameplay event
+    Event { event: GameEvent },
+    /// A finished run
+    RunSummary(RunSummary),
+}
+
+/// Outcome of a finished run. The wave it ended in is the record's `wave`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Final score
+    pub score: u32,
+    /// Difficulty the run was played on
+    pub difficulty: Difficulty,
+    /// Enemies destroyed
+    pub kills: u32,
+    /// Fraction of bullets that hit something
+    pub accuracy: f32,
+    /// Length of the run in seconds
+    pub duration: f32,
+    /// Wave the run started at
+    #[serde(default = "first_wave")]
+    pub start_wave: u32,
+    /// Why the run stays off the leaderboards (replay, bot, cheats,
+    /// practice start wave); [`analyze`] leaves these runs out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unranked: Option<String>,
+}
+
+fn first_wave() -> u32 {
+    1
+}
+
+/// Writes telemetry records when the player has opted in.
+///
+/// The opt-in choice is saved under `<key>_opt_in`, next to the log itself.
+///
+/// # Examples
+///
+/// ```no_run
+/// use bumblebees::events::GameEvent;
+/// use bumblebees::telemetry::Telemetry;
+///
+/// let mut telemetry = Telemetry::new("telemetry.jsonl");
+/// telemetry.set_enabled(true);
+/// telemetry.start_run(42, 1);
+/// telemetry.record_event(&GameEvent::RunStarted, 0.0);
+/// telemetry.flush();
+/// ```
+pub struct Telemetry {
+    /// Storage key: filename (desktop) or localStorage key (WASM)
+    storage_key: String,
+    enabled: bool,
+    session: String,
+    seed: u64,
+    wave: u32,
+    /// Serialized records not yet written
+    pending: Vec<String>,
+}
+
+impl Telemetry {
+    /// Create a sink writing to the given storage key, enabled if the player
+    /// opted in before (or, on desktop, if [`TELEMETRY_ENV_VAR`] is set).
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        let opted_in = storage::load(&opt_in_key(key)).is_some_and(|value| value.trim() == "1");
+
+        Self {
+            storage_key: key.to_string(),
+            enabled: opted_in || env_enabled(),
+            session: new_session_id(),
+            seed: 0,
+            wave: 1,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Whether records are being written.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Opt in or out and remember the choice.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.flush();
+        }
+        self.enabled = enabled;
+        storage::save(
+            &opt_in_key(&self.storage_key),
+            if enabled { "1" } else { "0" },
+        );
+    }
+
+    /// Begin a new run played with the given seed, starting at `wave`.
+    pub fn start_run(&mut self, seed: u64, wave: u32) {
+        self.seed = seed;
+        self.wave = wave;
+    }
+
+    /// Record a gameplay event that happened `run_time` seconds into the run.
+    pub fn record_event(&mut self, event: &GameEvent, run_time: f32) {
+        self.record(RecordKind::Event { event: *event }, run_time);
+        if let GameEvent::WaveCleared { wave, .. } = *event {
+            self.wave = wave + 1;
+        }
+        if self.pending.len() >= FLUSH_THRESHOLD {
+            self.flush();
+        }
+    }
+
+    /// Record the outcome of a finished run and write everything buffered.
+    pub fn record_run(&mut self, summary: RunSummary) {
+        let run_time = summary.duration;
+        self.record(RecordKind::RunSummary(summary), run_time);
+        self.flush();
+    }
+
+    /// Write buffered records to storage.
+    pub fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        append_records(&self.storage_key, &self.pending);
+        self.pending.clear();
+    }
+
+    fn record(&mut self, kind: RecordKind, run_time: f32) {
+        if !self.enabled {
+            return;
+        }
+
+        let record = TelemetryRecord {
+            session: self.session.clone(),
+            version: GAME_VERSION.to_string(),
+            seed: self.seed,
+            timestamp: unix_time_now(),
+            run_time,
+            wave: self.wave,
+            kind,
+        };
+        if let Ok(line) = serde_json::to_string(&record) {
+            self.pending.push(line);
+        }
+    }
+}
+
+fn opt_in_key(key: &str) -> String {
+    format!("{}_opt_in", key)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn env_enabled() -> bool {
+    std::env::var(TELEMETRY_ENV_VAR).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn env_enabled() -> bool {
+    false
+}
+
+/// Random-enough id for a session, derived from the current time.
+fn new_session_id() -> String {
+    #[cfg(target_arch = "wasm32")]
+    let nanos = (macroquad::miniquad::date::now() * 1e9) as u128;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default()
+        ^ u128::from(std::process::id()) << 64;
+
+    // SplitMix64 finalizer, so ids from close timestamps look unrelated
+    let mut x = (nanos as u64) ^ ((nanos >> 64) as u64);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    format!("{:016x}", x ^ (x >> 31))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn append_records(key: &str, lines: &[String]) {
+    use std::io::Write;
+
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(key)
+        .and_then(|mut file| {
+            let mut data = lines.join("\n");
+            data.push('\n');
+            file.write_all(data.as_bytes())
+        });
+    if let Err(err) = result {
+        log::warn!("Failed to write telemetry to {}: {}", key, err);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn append_records(key: &str, lines: &[String]) {
+    let existing = storage::load(key).unwrap_or_default();
+    storage::save(key, &ring_buffer(&existing, lines));
+}
+
+/// `existing` with `lines` appended, dropping the oldest lines beyond
+/// `MAX_BROWSER_RECORDS` lines or `MAX_BROWSER_BYTES` bytes.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+fn ring_buffer(existing: &str, lines: &[String]) -> String {
+    let mut records: Vec<&str> = existing.lines().collect();
+    records.extend(lines.iter().map(String::as_str));
+
+    // Walk back from the newest line while it fits, counting separators
+    let mut bytes = 0;
+    let kept = records
+        .iter()
+        .rev()
+        .take(MAX_BROWSER_RECORDS)
+        .take_while(|record| {
+            bytes += record.len() + 1;
+            bytes <= MAX_BROWSER_BYTES + 1
+        })
+        .count();
+    records[records.len() - kept..].join("\n")
+}
+
+/// Aggregated statistics over the run summaries of one or more logs.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TelemetryReport {
+    /// Finished runs found
+    pub runs: u32,
+    /// Distinct sessions found
+    pub sessions: usize,
+    /// Sum of all final scores
+    pub total_score: u64,
+    /// Per wave: runs that reached it and runs that ended in it
+    pub waves: BTreeMap<u32, WaveStats>,
+    /// Lines that could not be parsed
+    pub invalid_lines: u32,
+    /// Runs left out because they weren't ranked
+    pub unranked_runs: u32,
+}
+
+/// How runs fared in one wave.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WaveStats {
+    /// Runs that got to play this wave
+    pub reached: u32,
+    /// Runs that ended in this wave
+    pub deaths: u32,
+    /// Sum of the final scores of runs that ended in this wave
+    pub total_score: u64,
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl TelemetryReport {
+    /// Average final score over all runs (0 without runs).
+    #[must_use]
+    pub fn average_score(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.total_score as f64 / f64::from(self.runs)
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+impl WaveStats {
+    /// Fraction of the runs reaching this wave that ended in it.
+    #[must_use]
+    pub fn death_rate(&self) -> f64 {
+        if self.reached == 0 {
+            0.0
+        } else {
+            f64::from(self.deaths) / f64::from(self.reached)
+        }
+    }
+
+    /// Average final score of the runs that ended in this wave.
+    #[must_use]
+    pub fn average_score(&self) -> f64 {
+        if self.deaths == 0 {
+            0.0
+        } else {
+            self.total_score as f64 / f64::from(self.deaths)
+        }
+    }
+}
+
+impl fmt::Display for TelemetryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Runs: {} in {} sessions", self.runs, self.sessions)?;
+        writeln!(f, "Average score: {:.1}", self.average_score())?;
+        if self.invalid_lines > 0 {
+            writeln!(f, "Skipped {} unreadable lines", self.invalid_lines)?;
+        }
+        if self.unranked_runs > 0 {
+            writeln!(
+                f,
+                "Skipped {} unranked runs (replays, bots, cheats, practice)",
+                self.unranked_runs
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>4}  {:>7}  {:>6}  {:>10}  {:>9}",
+            "Wave", "Reached", "Deaths", "Death rate", "Avg score"
+        )?;
+        for (wave, stats) in &self.waves {
+            writeln!(
+                f,
+                "{:>4}  {:>7}  {:>6}  {:>9.1}%  {:>9.1}",
+                wave,
+                stats.reached,
+                stats.deaths,
+                stats.death_rate() * 100.0,
+                stats.average_score()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Aggregate telemetry logs (JSONL text) into a [`TelemetryReport`].
+///
+/// Only run summaries of ranked runs are counted, from the wave they
+/// started at; event lines are read for their session id. Unparseable lines
+/// and unranked runs are counted and skipped.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::telemetry::analyze;
+///
+/// let log = r#"{"session":"a","version":"1.3.0","seed":1,"timestamp":0,"run_time":60.0,"wave":2,"type":"run_summary","score":300,"difficulty":"Normal","kills":30,"accuracy":0.5,"duration":60.0}"#;
+/// let report = analyze([log]);
+/// assert_eq!(report.runs, 1);
+/// assert_eq!(report.waves[&2].deaths, 1);
+/// ```
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+#[must_use]
+pub fn analyze<I, S>(logs: I) -> TelemetryReport
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut report = TelemetryReport::default();
+    let mut sessions = std::collections::BTreeSet::new();
+
+    for log in logs {
+        for line in log.as_ref().lines().filter(|line| !line.trim().is_empty()) {
+            let Ok(record) = serde_json::from_str::<TelemetryRecord>(line) else {
+                report.invalid_lines += 1;
+                continue;
+            };
+            sessions.insert(record.session);
+
+            if let RecordKind::RunSummary(summary) = record.kind {
+                if summary.unranked.is_some() {
+                    report.unranked_runs += 1;
+                    continue;
+                }
+                report.runs += 1;
+                report.total_score += u64::from(summary.score);
+                for wave in summary.start_wave..=record.wave {
+                    report.waves.entry(wave).or_default().reached += 1;
+                }
+                let stats = report.waves.entry(record.wave).or_default();
+                stats.deaths += 1;
+                stats.total_score += u64::from(summary.score);
+            }
+        }
+    }
+
+    report.sessions = sessions.len();
+    report
+}
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+
+    fn summary(score: u32) -> RunSummary {
+        RunSummary {
+            score,
+            difficulty: Difficulty::Normal,
+            kills: 0,
+            accuracy: 0.0,
+            duration: 10.0,
+            start_wave: 1,
+            unranked: None,
+        }
+    }
+
+    #[test]
+    fn test_browser_ring_buffer_stays_readable() {
+        // A buffer of 1KB lines just below the byte limit
+        let line = "x".repeat(1023);
+        let count = MAX_BROWSER_BYTES / 1024;
+        let existing = vec![line.as_str(); count].join("\n");
+        assert!(existing.len() <= MAX_BROWSER_BYTES);
+
+        // The next flush drops the oldest lines instead of growing past it
+        let flush: Vec<String> = (0..10).map(|i| format!("{:01023}", i)).collect();
+        let buffer = ring_buffer(&existing, &flush);
+        assert!(buffer.len() <= MAX_BROWSER_BYTES);
+        assert!(buffer.len() < MAX_LOCALSTORAGE_SIZE);
+        assert!(buffer.ends_with(&flush[9]));
+        assert_eq!(buffer.lines().count(), count);
+
+        // Short lines are still capped by count
+        let short: Vec<String> = (0..MAX_BROWSER_RECORDS + 5)
+            .map(|i| i.to_string())
+            .collect();
+        let buffer = ring_buffer("", &short);
+        assert_eq!(buffer.lines().count(), MAX_BROWSER_RECORDS);
+        assert!(buffer.starts_with("5\n"));
+    }
+
+    #[test]
+    fn test_analyze_counts_waves_from_start_wave() {
+        let record = TelemetryRecord {
+            session: "a".to_string(),
+            version: GAME_VERSION.to_string(),
+            seed: 1,
+            timestamp: 0,
+            run_time: 10.0,
+            wave: 9,
+            kind: RecordKind::RunSummary(RunSummary {
+                start_wave: 8,
+                ..summary(100)
+            }),
+        };
+        let report = analyze([serde_json::to_string(&record).unwrap()]);
+        assert_eq!(report.waves.keys().copied().collect::<Vec<_>>(), [8, 9]);
+        assert_eq!(report.waves[&8].reached, 1);
+        assert_eq!(report.waves[&9].deaths, 1);
+    }
+
+    #[test]
+    fn test_disabled_writes_nothing() {
+        let test_file = "test_telemetry_disabled.jsonl";
+        let _ = std::fs::remove_file(test_file);
+        let _ = std::fs::remove_file(opt_in_key(test_file));
+
+        let mut telemetry = Telemetry::new(test_file);
+        telemetry.enabled = false;
+        telemetry.record_event(&GameEvent::RunStarted, 0.0);
+        telemetry.record_run(summary(100));
+        assert!(!std::path::Path::new(test_file).exists());
+    }
+
+    #[test]
+    fn test_records_round_trip_through_analyze() {
+        let test_file = "test_telemetry_log.jsonl";
+        let _ = std::fs::remove_file(test_file);
+
+        let mut telemetry = Telemetry::new(test_file);
+        telemetry.set_enabled(true);
+        assert_eq!(std::fs::read_to_string(opt_in_key(test_file)).unwrap(), "1");
+
+        telemetry.start_run(7, 1);
+        telemetry.record_event(&GameEvent::RunStarted, 0.0);
+        telemetry.record_event(
+            &GameEvent::WaveCleared {
+                wave: 1,
+                formation: crate::systems::FormationType::Grid,
+            },
+            12.0,
+        );
+        telemetry.record_run(summary(500));
+
+        telemetry.start_run(8, 1);
+        telemetry.record_run(summary(100));
+
+        // A practice run from wave 3 and a bot run don't count
+        telemetry.start_run(9, 3);
+        telemetry.record_run(RunSummary {
+            start_wave: 3,
+            unranked: Some("practice start wave".to_string()),
+            ..summary(900)
+        });
+        telemetry.start_run(10, 1);
+        telemetry.record_run(RunSummary {
+            unranked: Some("bot".to_string()),
+            ..summary(900)
+        });
+
+        let log = std::fs::read_to_string(test_file).unwrap();
+        let records: Vec<TelemetryRecord> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 6);
+        assert!(records.iter().all(|record| record.version == GAME_VERSION));
+        assert_eq!(records[0].seed, 7);
+        assert_eq!(records[2].wave, 2);
+        assert_eq!(records[3].seed, 8);
+        assert_eq!(records[4].wave, 3);
+
+        let report = analyze([log + "not json\n"]);
+        assert_eq!(report.runs, 2);
+        assert_eq!(report.sessions, 1);
+        assert_eq!(report.invalid_lines, 1);
+        assert_eq!(report.unranked_runs, 2);
+        assert_eq!(report.average_score(), 300.0);
+        assert_eq!(report.waves[&1].reached, 2);
+        assert_eq!(report.waves[&1].death_rate(), 0.5);
+        assert_eq!(report.waves[&2].average_score(), 500.0);
+
+        let _ = std::fs::remove_file(test_file);
+        let _ = std::fs::remove_file(opt_in_key(test_file));
+    }
+}
@@ -0,0 +1,222 @@
+//! Touch control schemes.
+//!
+//! A [`TouchLayout`] is the player's choice of [`TouchScheme`] plus its
+//! settings: mirrored for left-handed play, zone size and how visible the
+//! zones are. [`TouchLayout::zones`] places the zones on the game area;
+//! `TouchInput` reads touches against them and the game draws them, so both
+//! always agree. The layout is saved with the other
+//! [`Settings`](crate::settings::Settings).
+
+use crate::constants::*;
+use macroquad::math::{Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// How touches control the player.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchScheme {
+    /// One side of the screen sets the player's position, the other fires
+    #[default]
+    Split,
+    /// A virtual stick moves the player, a button fires
+    Joystick,
+    /// The player follows a finger anywhere and fires on its own
+    Drag,
+}
+
+impl TouchScheme {
+    /// All schemes, in the order the settings cycle through them.
+    pub const ALL: [Self; 3] = [Self::Split, Self::Joystick, Self::Drag];
+
+    /// Display name.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Split => "Split screen",
+            Self::Joystick => "Joystick",
+            Self::Drag => "Drag + auto-fire",
+        }
+    }
+
+    /// The next scheme in [`TouchScheme::ALL`], wrapping around; `step` is
+    /// +1 or -1.
+    #[must_use]
+    pub fn cycle(self, step: isize) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|&scheme| scheme == self)
+            .unwrap_or(0);
+        let count = Self::ALL.len() as isize;
+        Self::ALL[(index as isize + step).rem_euclid(count) as usize]
+    }
+}
+
+/// A region of the screen that reacts to touches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchZone {
+    Area(Rect),
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl TouchZone {
+    /// Whether `point` lies in the zone.
+    #[must_use]
+    pub fn contains(&self, point: Vec2) -> bool {
+        match *self {
+            Self::Area(rect) => rect.contains(point),
+            Self::Circle { center, radius } => center.distance(point) <= radius,
+        }
+    }
+}
+
+/// Where the zones of a layout are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchZones {
+    /// Touches here move the player
+    pub movement: TouchZone,
+    /// Touches here fire; none for auto-fire
+    pub fire: Option<TouchZone>,
+}
+
+/// The chosen touch scheme and its settings.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::touch::{TouchLayout, TouchScheme, TouchZone};
+/// use macroquad::math::Vec2;
+///
+/// let layout = TouchLayout {
+///     scheme: TouchScheme::Split,
+///     left_handed: true,
+///     ..TouchLayout::default()
+/// };
+/// // Left-handed split: move on the right, fire on the left
+/// let zones = layout.zones();
+/// assert!(zones.movement.contains(Vec2::new(900.0, 300.0)));
+/// assert!(zones.fire.unwrap().contains(Vec2::new(100.0, 300.0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TouchLayout {
+    pub scheme: TouchScheme,
+    /// Swap the sides of the movement and fire zones
+    pub left_handed: bool,
+    /// Zone size relative to the default, between
+    /// [`TOUCH_ZONE_SCALE_MIN`] and [`TOUCH_ZONE_SCALE_MAX`]
+    pub zone_scale: f32,
+    /// Visibility of the zone indicators, 0 to 1
+    pub opacity: f32,
+}
+
+impl Default for TouchLayout {
+    fn default() -> Self {
+        Self {
+            scheme: TouchScheme::Split,
+            left_handed: false,
+            zone_scale: 1.0,
+            opacity: TOUCH_OPACITY_DEFAULT,
+        }
+    }
+}
+
+impl TouchLayout {
+    /// The layout with its settings in their allowed ranges.
+    #[must_use]
+    pub fn clamped(self) -> Self {
+        Self {
+            zone_scale: self
+                .zone_scale
+                .clamp(TOUCH_ZONE_SCALE_MIN, TOUCH_ZONE_SCALE_MAX),
+            opacity: self.opacity.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Zones of the layout in game coordinates.
+    #[must_use]
+    pub fn zones(&self) -> TouchZones {
+        // Mirror x positions for left-handed play
+        let side = |x: f32| {
+            if self.left_handed {
+                SCREEN_WIDTH - x
+            } else {
+                x
+            }
+        };
+
+        match self.scheme {
+            TouchScheme::Split => {
+                let move_width = SCREEN_WIDTH * 0.5 * self.zone_scale;
+                let fire_width = SCREEN_WIDTH - move_width;
+                let (move_x, fire_x) = if self.left_handed {
+                    (fire_width, 0.0)
+                } else {
+                    (0.0, move_width)
+                };
+                TouchZones {
+                    movement: TouchZone::Area(Rect::new(move_x, 0.0, move_width, SCREEN_HEIGHT)),
+                    fire: Some(TouchZone::Area(Rect::new(
+                        fire_x,
+                        0.0,
+                        fire_width,
+                        SCREEN_HEIGHT,
+                    ))),
+                }
+            }
+            TouchScheme::Joystick => {
+                let stick = TOUCH_STICK_RADIUS * self.zone_scale;
+                let button = TOUCH_FIRE_RADIUS * self.zone_scale;
+                TouchZones {
+                    movement: TouchZone::Circle {
+                        center: Vec2::new(
+                            side(TOUCH_MARGIN + stick),
+                            SCREEN_HEIGHT - TOUCH_MARGIN - stick,
+                        ),
+                        radius: stick,
+                    },
+                    fire: Some(TouchZone::Circle {
+                        center: Vec2::new(
+                            side(SCREEN_WIDTH - TOUCH_MARGIN - button),
+                            SCREEN_HEIGHT - TOUCH_MARGIN - button,
+                        ),
+                        radius: button,
+                    }),
+                }
+            }
+            TouchScheme::Drag => TouchZones {
+                movement: TouchZone::Area(Rect::new(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT)),
+                fire: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_scale_resizes_zones() {
+        assert_eq!(TouchScheme::Split.cycle(-1), TouchScheme::Drag);
+
+        let mut layout = TouchLayout {
+            zone_scale: 1.4,
+            ..TouchLayout::default()
+        };
+        let TouchZone::Area(movement) = layout.zones().movement else {
+            panic!("split moves with an area");
+        };
+        assert_eq!(movement.w, SCREEN_WIDTH * 0.7);
+
+        layout.scheme = TouchScheme::Joystick;
+        layout.left_handed = true;
+        let zones = layout.zones();
+        let TouchZone::Circle { center, radius } = zones.movement else {
+            panic!("joystick moves with a circle");
+        };
+        assert_eq!(radius, TOUCH_STICK_RADIUS * 1.4);
+        // Mirrored: the stick is on the right, the button on the left
+        assert!(center.x > SCREEN_WIDTH / 2.0);
+        assert!(zones.fire.unwrap().contains(Vec2::new(100.0, 500.0)));
+    }
+}
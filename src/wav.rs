@@ -0,0 +1,162 @@
+//! Reading and writing 16-bit PCM WAV data.
+//!
+//! Just enough of the format to change sound effects at load time: the game
+//! decodes its `sfx_*.wav` files, edits the samples and hands the result to
+//! the audio device as a new WAV file in memory.
+
+/// Decoded 16-bit PCM audio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcm {
+    pub sample_rate: u32,
+    /// 1 (mono) or 2 (stereo)
+    pub channels: u16,
+    /// Interleaved samples
+    pub samples: Vec<i16>,
+}
+
+impl Pcm {
+    /// Parse a WAV file; only uncompressed 16-bit mono or stereo is
+    /// supported.
+    ///
+    /// # Errors
+    ///
+    /// Describes what is wrong with the data if it isn't such a file.
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err("not a WAV file".to_string());
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut pos = 12;
+        while pos + 8 <= bytes.len() {
+            let id = &bytes[pos..pos + 4];
+            let size = u32::from_le_bytes([
+                bytes[pos + 4],
+                bytes[pos + 5],
+                bytes[pos + 6],
+                bytes[pos + 7],
+            ]) as usize;
+            let body = &bytes[pos + 8..(pos + 8 + size).min(bytes.len())];
+            match id {
+                b"fmt " if body.len() >= 16 => format = Some(body),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            // Chunks are padded to an even size
+            pos += 8 + size + size % 2;
+        }
+
+        let format = format.ok_or("missing fmt chunk")?;
+        let data = data.ok_or("missing data chunk")?;
+        let read_u16 = |at: usize| u16::from_le_bytes([format[at], format[at + 1]]);
+        let (encoding, channels, bits) = (read_u16(0), read_u16(2), read_u16(14));
+        if encoding != 1 || bits != 16 {
+            return Err(format!(
+                "unsupported encoding {} with {} bits",
+                encoding, bits
+            ));
+        }
+        if channels != 1 && channels != 2 {
+            return Err(format!("unsupported channel count {}", channels));
+        }
+
+        Ok(Self {
+            sample_rate: u32::from_le_bytes([format[4], format[5], format[6], format[7]]),
+            channels,
+            samples: data
+                .chunks_exact(2)
+                .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+                .collect(),
+        })
+    }
+
+    /// The audio as a WAV file.
+    #[must_use]
+    pub fn to_wav(&self) -> Vec<u8> {
+        let data_len = (self.samples.len() * 2) as u32;
+        let block_align = self.channels * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate * u32::from(block_align)).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in &self.samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// The audio mixed down to mono and placed on one side of a stereo
+    /// file; the other side is silent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumblebees::wav::Pcm;
+    ///
+    /// let mono = Pcm { sample_rate: 48000, channels: 1, samples: vec![100, -200] };
+    /// let right = mono.on_one_side(false);
+    /// assert_eq!(right.channels, 2);
+    /// assert_eq!(right.samples, vec![0, 100, 0, -200]);
+    /// ```
+    #[must_use]
+    pub fn on_one_side(&self, left: bool) -> Self {
+        let samples = self
+            .samples
+            .chunks_exact(usize::from(self.channels))
+            .flat_map(|frame| {
+                let sum: i32 = frame.iter().map(|&sample| i32::from(sample)).sum();
+                let mono = (sum / frame.len() as i32) as i16;
+                if left {
+                    [mono, 0]
+                } else {
+                    [0, mono]
+                }
+            })
+            .collect();
+        Self {
+            sample_rate: self.sample_rate,
+            channels: 2,
+            samples,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_stereo_mixdown() {
+        let stereo = Pcm {
+            sample_rate: 44100,
+            channels: 2,
+            samples: vec![1000, 3000, -4, -2, i16::MAX, i16::MAX],
+        };
+        let bytes = stereo.to_wav();
+        assert_eq!(bytes.len(), 44 + 12);
+        assert_eq!(Pcm::parse(&bytes).unwrap(), stereo);
+
+        assert_eq!(
+            stereo.on_one_side(true).samples,
+            vec![2000, 0, -3, 0, i16::MAX, 0]
+        );
+
+        let hit = Pcm::parse(include_bytes!("../resources/sfx_hit.wav")).unwrap();
+        assert_eq!((hit.sample_rate, hit.channels), (48000, 1));
+
+        assert!(Pcm::parse(b"RIFF\0\0\0\0WAVE").is_err());
+        let mut float = bytes.clone();
+        float[20] = 3; // IEEE float encoding
+        assert!(Pcm::parse(&float).is_err());
+    }
+}
@@ -0,0 +1,503 @@
+//! Gameplay simulation.
+//!
+//! [`World`] holds everything that decides how a run plays out: the player,
+//! bullets, the enemy formation, the flying bee, the current speeds, wave
+//! and score. [`World::step`] advances it by one frame from a
+//! [`PlayInput`]. It draws nothing and plays no sound, so the game, replays
+//! and headless simulations all run the same code. What happened during a
+//! step is reported as [`GameEvent`]s in [`World::events`].
+
+use crate::constants::*;
+use crate::difficulty::Difficulty;
+use crate::entities::{Bullet, Enemy, Player};
+use crate::events::{EventQueue, GameEvent};
+use crate::systems::{formation_for_wave, generate_wave, process_collisions};
+use macroquad::rand::RandGenerator;
+use serde::{Deserialize, Serialize};
+
+/// Player input for one gameplay frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayInput {
+    /// Move left at the player's speed
+    #[serde(default, rename = "l", skip_serializing_if = "is_false")]
+    pub move_left: bool,
+    /// Move right at the player's speed
+    #[serde(default, rename = "r", skip_serializing_if = "is_false")]
+    pub move_right: bool,
+    /// Fire a shot this frame
+    #[serde(default, rename = "f", skip_serializing_if = "is_false")]
+    pub fire: bool,
+    /// Put the player at this x position (touch controls)
+    #[serde(default, rename = "x", skip_serializing_if = "Option::is_none")]
+    pub target_x: Option<f32>,
+    /// Share of the player's speed to move at (analog sticks), full if unset
+    #[serde(default, rename = "s", skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// State of a run in progress.
+///
+/// # Examples
+///
+/// ```
+/// use bumblebees::difficulty::Difficulty;
+/// use bumblebees::world::{PlayInput, World};
+///
+/// let mut world = World::new(Difficulty::Normal, 1, 42);
+/// let fire = PlayInput { fire: true, ..PlayInput::default() };
+/// world.step(1.0 / 60.0, &fire);
+/// assert_eq!(world.bullets.len(), 1);
+/// ```
+pub struct World {
+    pub player: Player,
+    pub bullets: Vec<Bullet>,
+    pub enemies: Vec<Enemy>,
+    /// Events emitted since the owner last drained the queue
+    pub events: EventQueue,
+    pub difficulty: Difficulty,
+    pub enemy_speed: f32,
+    pub bullet_speed: f32,
+    pub player_speed: f32,
+    pub descent_speed: f32,
+    pub descent_distance: f32, // how much enemies still need to descend
+    pub wave_number: u32,
+    pub score: u32,
+
+    // Flying bee
+    pub bee_x: f32,                // Current X position of flying bee
+    pub bee_y: f32,                // Y position of flying bee
+    pub bee_active: bool,          // Whether bee is currently flying
+    pub bee_next_spawn_timer: f32, // Time until next bee spawn
+
+    /// Enemies reaching the defender line disappear instead of ending the run
+    pub god_mode: bool,
+    /// An enemy crossed the defender line
+    breached: bool,
+    bullet_spawn_buffer: Vec<Bullet>,
+    /// Generator for the bee's timing and height, seeded per run
+    rng: RandGenerator,
+}
+
+impl World {
+    /// Create a run starting at `start_wave`.
+    ///
+    /// The bee's timing comes from a generator seeded with `seed`, so the
+    /// same seed and inputs play out the same run.
+    #[must_use]
+    pub fn new(difficulty: Difficulty, start_wave: u32, seed: u64) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        let mut world = Self {
+            player: Player::new(),
+            bullets: Vec::new(),
+            enemies: Vec::new(),
+            events: EventQueue::new(),
+            difficulty,
+            enemy_speed: INITIAL_ENEMY_SPEED,
+            bullet_speed: BULLET_SPEED,
+            player_speed: PLAYER_SPEED,
+            descent_speed: 100.0, // pixels per second for controlled descent
+            descent_distance: 0.0,
+            wave_number: 1,
+            score: 0,
+            bee_x: SCREEN_WIDTH + 100.0, // Start off-screen to the right
+            bee_y: SCREEN_HEIGHT / 3.0,
+            bee_active: false,
+            bee_next_spawn_timer: rng.gen_range(BEE_SPAWN_MIN_TIME, BEE_SPAWN_MAX_TIME),
+            god_mode: false,
+            breached: false,
+            bullet_spawn_buffer: Vec::with_capacity(3),
+            rng,
+        };
+        world.start_wave(start_wave.max(1));
+        world
+    }
+
+    /// Start the given wave with the speeds and upgrades a player would have
+    /// reached it with.
+    pub fn start_wave(&mut self, wave: u32) {
+        let cleared = wave.saturating_sub(1) as f32;
+        self.wave_number = wave;
+        self.enemy_speed = (INITIAL_ENEMY_SPEED + SPEED_INCREASE_PER_WAVE * cleared)
+            * self.difficulty.enemy_speed_multiplier();
+        self.bullet_speed = BULLET_SPEED + BULLET_SPEED_INCREASE_PER_WAVE * cleared;
+        self.player_speed = PLAYER_SPEED + PLAYER_SPEED_INCREASE_PER_WAVE * cleared;
+        self.player.reset();
+        // One upgrade per cleared wave; the gun is fully upgraded after two
+        for _ in 1..wave.min(3) {
+            self.player.upgrade();
+        }
+        self.enemies = generate_wave(wave);
+        self.bullets.clear();
+        self.descent_distance = 0.0;
+    }
+
+    /// Whether an enemy crossed the defender line, ending the run.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        self.breached
+    }
+
+    /// Advance the run by `dt` seconds: apply the input, then move the bee,
+    /// bullets and enemies, resolve collisions and start the next wave once
+    /// the current one is destroyed. Points of destroyed enemies are added
+    /// to the score.
+    pub fn step(&mut self, dt: f32, input: &PlayInput) {
+        if self.breached {
+            return;
+        }
+        let first_event = self.events.len();
+
+        if let Some(x) = input.target_x {
+            self.player.x = x;
+            self.player.clamp_position();
+        }
+        let speed = self.player_speed * input.speed.unwrap_or(1.0);
+        if input.move_left {
+            self.player.move_left(dt, speed);
+        }
+        if input.move_right {
+            self.player.move_right(dt, speed);
+        }
+        if input.fire {
+            self.shoot();
+        }
+
+        self.update_bee(dt);
+        self.update_bullets(dt);
+        // Before enemy collisions, to remove bullets that hit the bee
+        self.update_bee_collisions();
+        self.update_enemies(dt);
+        process_collisions(&mut self.enemies, &mut self.bullets, &mut self.events);
+        self.check_wave_complete();
+
+        self.score += self
+            .events
+            .iter()
+            .skip(first_event)
+            .map(GameEvent::points)
+            .sum::<u32>();
+    }
+
+    /// Fire the player's gun.
+    pub fn shoot(&mut self) {
+        self.bullet_spawn_buffer.clear();
+        self.player.shoot(&mut self.bullet_spawn_buffer);
+        if !self.bullet_spawn_buffer.is_empty() {
+            self.events.emit(GameEvent::ShotFired {
+                x: self.player.x,
+                bullets: self.bullet_spawn_buffer.len(),
+            });
+            self.bullets.append(&mut self.bullet_spawn_buffer);
+        }
+    }
+
+    /// Send the flying bee now, restarting its flight if it is already out.
+    pub fn spawn_bee(&mut self) {
+        self.bee_active = false;
+        self.bee_next_spawn_timer = 0.0;
+        self.update_bee(0.0);
+    }
+
+    fn update_bullets(&mut self, dt: f32) {
+        for bullet in &mut self.bullets {
+            bullet.update(dt, self.bullet_speed);
+        }
+        let before = self.bullets.len();
+        self.bullets.retain(|bullet| !bullet.is_out_of_bounds());
+        let misses = before - self.bullets.len();
+        if misses > 0 {
+            self.events.emit(GameEvent::ShotMissed { bullets: misses });
+        }
+    }
+
+    fn update_enemies(&mut self, dt: f32) {
+        // Handle gradual descent if active
+        if self.descent_distance > 0.0 {
+            let descent_this_frame = self.descent_speed * dt;
+            if descent_this_frame >= self.descent_distance {
+                // Complete the descent
+                for enemy in &mut self.enemies {
+                    enemy.y += self.descent_distance;
+                }
+                self.descent_distance = 0.0;
+
+                if let Some(lowest_y) = self
+                    .enemies
+                    .iter()
+                    .map(|enemy| enemy.y + COLLISION_RADIUS)
+                    .reduce(f32::max)
+                {
+                    self.events.emit(GameEvent::FormationDescended { lowest_y });
+                }
+            } else {
+                // Continue descending
+                for enemy in &mut self.enemies {
+                    enemy.y += descent_this_frame;
+                }
+                self.descent_distance -= descent_this_frame;
+            }
+        } else {
+            // Normal horizontal movement when not descending
+            for enemy in &mut self.enemies {
+                enemy.update(self.enemy_speed, dt);
+            }
+
+            // Check if any enemy has reached the edge it's moving toward
+            let mut edge_reached = false;
+            for enemy in &self.enemies {
+                let moving_right = enemy.direction > 0.0;
+                let moving_left = enemy.direction < 0.0;
+
+                if (moving_right && enemy.x >= SCREEN_WIDTH - FORMATION_EDGE_MARGIN)
+                    || (moving_left && enemy.x <= FORMATION_EDGE_MARGIN)
+                {
+                    edge_reached = true;
+                    break;
+                }
+            }
+
+            if edge_reached {
+                log::info!("Enemy reached edge - reversing direction and starting descent");
+
+                // Reverse ALL directions
+                for enemy in &mut self.enemies {
+                    enemy.direction *= -1.0;
+                    // Move back into bounds
+                    #[allow(clippy::manual_clamp)]
+                    if enemy.x < FORMATION_EDGE_MARGIN {
+                        enemy.x = FORMATION_EDGE_MARGIN;
+                    } else if enemy.x > SCREEN_WIDTH - FORMATION_EDGE_MARGIN {
+                        enemy.x = SCREEN_WIDTH - FORMATION_EDGE_MARGIN;
+                    }
+                }
+
+                // Start controlled descent for the entire wave
+                self.descent_distance = FORMATION_DESCENT_STEP;
+            }
+        }
+
+        // God mode: enemies reaching the defender line just disappear
+        if self.god_mode {
+            self.enemies
+                .retain(|enemy| !enemy.has_breached_defender_line());
+        }
+
+        // Check if any enemy has breached the defender line
+        for enemy in &self.enemies {
+            if enemy.has_breached_defender_line() {
+                log::warn!("Enemy breached defender line at y={}, game over!", enemy.y);
+                self.events.emit(GameEvent::DefenderBreached {
+                    x: enemy.x,
+                    y: enemy.y,
+                });
+                self.breached = true;
+                return;
+            }
+        }
+    }
+
+    fn update_bee(&mut self, dt: f32) {
+        // Update spawn timer
+        if !self.bee_active {
+            self.bee_next_spawn_timer -= dt;
+
+            // Spawn new bee when timer expires
+            if self.bee_next_spawn_timer <= 0.0 {
+                self.bee_active = true;
+                self.bee_x = SCREEN_WIDTH + 100.0; // Start off-screen to the right
+                self.bee_y = self.rng.gen_range(SCREEN_HEIGHT * 0.2, SCREEN_HEIGHT * 0.5); // Random height in upper portion
+                self.bee_next_spawn_timer =
+                    self.rng.gen_range(BEE_SPAWN_MIN_TIME, BEE_SPAWN_MAX_TIME);
+            }
+        } else {
+            // Move bee from right to left
+            self.bee_x -= BEE_FLY_SPEED * dt;
+
+            // Deactivate bee when it goes off-screen to the left
+            if self.bee_x < -100.0 {
+                self.bee_active = false;
+            }
+        }
+    }
+
+    fn update_bee_collisions(&mut self) {
+        if !self.bee_active {
+            return;
+        }
+
+        // Check collision between bee and each bullet
+        let combined_radius = BEE_RADIUS + COLLISION_RADIUS;
+        let combined_radius_sq = combined_radius * combined_radius;
+        let mut bee_hit = false;
+        let bee_pos = (self.bee_x + BEE_RADIUS, self.bee_y + BEE_RADIUS); // Center of bee
+
+        // Remove bullets that hit the bee
+        let before = self.bullets.len();
+        self.bullets.retain(|bullet| {
+            let dx = bullet.x - bee_pos.0;
+            let dy = bullet.y - bee_pos.1;
+            let distance_sq = dx * dx + dy * dy;
+
+            if distance_sq < combined_radius_sq {
+                bee_hit = true;
+                false // Remove this bullet
+            } else {
+                true // Keep this bullet
+            }
+        });
+
+        // If bee was hit, report it and deactivate the bee
+        if bee_hit {
+            log::info!("Bee hit! Awarding {} points", BEE_POINTS);
+            self.events.emit(GameEvent::BeeDestroyed {
+                x: bee_pos.0,
+                y: bee_pos.1,
+                points: BEE_POINTS,
+                bullets: before - self.bullets.len(),
+            });
+            self.bee_active = false;
+        }
+    }
+
+    fn check_wave_complete(&mut self) {
+        if self.enemies.is_empty() {
+            self.events.emit(GameEvent::WaveCleared {
+                wave: self.wave_number,
+                formation: formation_for_wave(self.wave_number),
+            });
+            self.wave_number += 1;
+            self.enemy_speed += SPEED_INCREASE_PER_WAVE * self.difficulty.enemy_speed_multiplier();
+            self.bullet_speed += BULLET_SPEED_INCREASE_PER_WAVE;
+            self.player_speed += PLAYER_SPEED_INCREASE_PER_WAVE;
+            self.player.upgrade();
+            self.enemies = generate_wave(self.wave_number);
+            log::info!(
+                "Wave {} complete! Starting wave {} with enemy speed {}, bullet speed {}, and player speed {}",
+                self.wave_number - 1,
+                self.wave_number,
+                self.enemy_speed,
+                self.bullet_speed,
+                self.player_speed
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::EnemyType;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn test_start_wave_matches_progression() {
+        let mut played = World::new(Difficulty::Hard, 1, 1);
+        played.enemies.clear();
+        played.step(DT, &PlayInput::default());
+        played.enemies.clear();
+        played.step(DT, &PlayInput::default());
+
+        let jumped = World::new(Difficulty::Hard, 3, 1);
+        assert_eq!(played.wave_number, 3);
+        assert_eq!(jumped.wave_number, 3);
+        assert_eq!(jumped.enemy_speed, played.enemy_speed);
+        assert_eq!(jumped.bullet_speed, played.bullet_speed);
+        assert_eq!(jumped.player_speed, played.player_speed);
+        assert_eq!(jumped.player.available_shots, played.player.available_shots);
+    }
+
+    #[test]
+    fn test_destroyed_enemies_add_to_score() {
+        let mut world = World::new(Difficulty::Normal, 1, 1);
+        world.enemies.truncate(1);
+        let target = (world.enemies[0].x, world.enemies[0].y);
+        world.bullets.push(Bullet::new(target.0, target.1));
+        world.bee_next_spawn_timer = f32::MAX;
+
+        world.step(0.0, &PlayInput::default());
+        let points: u32 = world.events.iter().map(GameEvent::points).sum();
+        assert!(points > 0);
+        assert_eq!(world.score, points);
+        // The emptied wave was replaced by the next one
+        assert_eq!(world.wave_number, 2);
+    }
+
+    #[test]
+    fn test_breach_ends_run_unless_god_mode() {
+        let mut world = World::new(Difficulty::Normal, 1, 1);
+        world.enemies[0].y = SCREEN_HEIGHT;
+        world.god_mode = true;
+        world.step(DT, &PlayInput::default());
+        assert!(!world.is_over());
+
+        world.god_mode = false;
+        world.enemies[0].y = SCREEN_HEIGHT;
+        world.step(DT, &PlayInput::default());
+        assert!(world.is_over());
+        assert!(world
+            .events
+            .iter()
+            .any(|event| matches!(event, GameEvent::DefenderBreached { .. })));
+    }
+
+    #[test]
+    fn test_descent_reports_lowest_enemy() {
+        let mut world = World::new(Difficulty::Normal, 1, 1);
+        world.bee_next_spawn_timer = f32::MAX;
+        // Most of the formation is high up, one enemy reaches past the middle
+        let half = SCREEN_HEIGHT / 2.0;
+        world.enemies = vec![
+            Enemy::new(200.0, 100.0, 1.0, EnemyType::Standard),
+            Enemy::new(300.0, 100.0, 1.0, EnemyType::Standard),
+            Enemy::new(400.0, 100.0, 1.0, EnemyType::Standard),
+            Enemy::new(500.0, half - 10.0, 1.0, EnemyType::Standard),
+        ];
+        world.descent_distance = 1.0;
+        world.step(DT, &PlayInput::default());
+
+        let center_y = world.enemies.iter().map(|enemy| enemy.y).sum::<f32>() / 4.0;
+        assert!(center_y < half);
+        let lowest_y = world.events.iter().find_map(|event| match *event {
+            GameEvent::FormationDescended { lowest_y } => Some(lowest_y),
+            _ => None,
+        });
+        assert_eq!(lowest_y, Some(half - 9.0 + COLLISION_RADIUS));
+    }
+
+    #[test]
+    fn test_input_moves_player() {
+        let mut world = World::new(Difficulty::Normal, 1, 1);
+        let start = world.player.x;
+        let left = PlayInput {
+            move_left: true,
+            ..PlayInput::default()
+        };
+        world.step(0.1, &left);
+        assert!(world.player.x < start);
+
+        // Half a stick tilt covers half the distance
+        let full = start - world.player.x;
+        let half = PlayInput {
+            move_right: true,
+            speed: Some(0.5),
+            ..PlayInput::default()
+        };
+        let before = world.player.x;
+        world.step(0.1, &half);
+        assert!((world.player.x - before - full / 2.0).abs() < 1e-3);
+
+        let touch = PlayInput {
+            target_x: Some(-50.0),
+            ..PlayInput::default()
+        };
+        world.step(DT, &touch);
+        assert_eq!(world.player.x, world.player.base_width / 2.0);
+    }
+}